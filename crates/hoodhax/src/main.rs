@@ -1,6 +1,38 @@
-use std::{io::Write, str::FromStr, sync::Arc};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
-use hoodmem::{scanner::ScanFilter, Process};
+use clap::Parser;
+use hoodmem::scanner::{DivisibleBy, RoundTo, ScanFilter, ToF64, Vec3Filter};
+use serde::{Deserialize, Serialize};
+
+/// Percentage points either side of the given percentage a `percent` scan accepts, unless a tolerance is given
+const DEFAULT_PERCENT_TOLERANCE: f64 = 2.0;
+
+/// How far each component of a `scan vec3` may be from the given value, if no tolerance is given
+const DEFAULT_VEC3_TOLERANCE: f32 = 0.001;
+
+/// Interactive memory scanner. Attaching or choosing a scan type here is the same as doing it at the prompt
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// PID of a process to attach to at startup
+    #[arg(long, conflicts_with = "name")]
+    pid: Option<u32>,
+    /// Name of a process to attach to at startup
+    #[arg(long)]
+    name: Option<String>,
+    /// Type of value to scan for, such as u32 or f32
+    #[arg(long)]
+    scan_type: Option<String>,
+    /// Runs each line of this file as a command, then exits. Use `-` to read commands from stdin
+    #[arg(long)]
+    script: Option<PathBuf>,
+}
 
 #[derive(Debug, Clone, Copy)]
 enum ScanType {
@@ -26,15 +58,18 @@ where
         + PartialEq
         + std::ops::Sub<Output = T>
         + std::ops::Add<Output = T>
+        + RoundTo
+        + ToF64
+        + DivisibleBy
         + FromStr,
 {
     match command.len() {
         0 => {
-            eprintln!("Expected one of: exact, changed, unchanged, increased, decreased, increasedby, decreasedby, unknown");
+            eprintln!("Expected one of: exact, changed, unchanged, increased, decreased, increasedby, decreasedby, divisible, sameas, percent, any, unknown");
         }
         1 => match command[0].trim() {
             "changed" => {
-                scanner.scan(ScanFilter::Unchanged::<T>)?;
+                scanner.scan(ScanFilter::Changed::<T>)?;
             }
             "unchanged" => {
                 scanner.scan(ScanFilter::Unchanged::<T>)?;
@@ -52,6 +87,17 @@ where
                 eprintln!("Expected one of: exact, changed, unchanged, increased, decreased, increasedby, decreasedby, unknown");
             }
         },
+        _ if command[0].trim() == "any" => {
+            // any <value> <value> ...
+            match command[1..]
+                .iter()
+                .map(|value| T::from_str(value.trim()))
+                .collect::<Result<Vec<T>, _>>()
+            {
+                Ok(values) if !values.is_empty() => scanner.scan(ScanFilter::ExactAny(values))?,
+                _ => eprintln!("Expected `any <value> <value> ...`"),
+            }
+        }
         2 => {
             match command[0].trim() {
                 "exact" => {
@@ -75,11 +121,44 @@ where
                         eprintln!("Unable to parse value {}", command[1].trim());
                     }
                 }
+                "divisible" => {
+                    if let Ok(value) = T::from_str(command[1].trim()) {
+                        scanner.scan(ScanFilter::divisible_by(value)?)?;
+                    } else {
+                        eprintln!("Unable to parse value {}", command[1].trim());
+                    }
+                }
+                "sameas" => {
+                    // Finds copies of the value at another address
+                    if let Some(reference) = parse_address(command[1]) {
+                        scanner.scan_same_as::<T>(reference)?;
+                    } else {
+                        eprintln!("Unable to parse address {}", command[1].trim());
+                    }
+                }
                 _ => {
                     eprintln!("Unknown command {}", command[0].trim())
                 }
             }
         }
+        3 | 4 if command[0].trim() == "percent" => {
+            // percent <percent> <max> [tolerance]
+            let percent = command[1].trim().parse::<f64>();
+            let max = T::from_str(command[2].trim());
+            let tolerance = command.get(3).map_or(Ok(DEFAULT_PERCENT_TOLERANCE), |tolerance| {
+                tolerance.trim().parse::<f64>()
+            });
+            match (percent, max, tolerance) {
+                (Ok(percent), Ok(max), Ok(tolerance)) => {
+                    scanner.scan(ScanFilter::PercentOf {
+                        max,
+                        percent,
+                        tolerance,
+                    })?;
+                }
+                _ => eprintln!("Expected `percent <percent> <max> [tolerance]`"),
+            }
+        }
         _ => {
             eprintln!("Unexpected command length {}", command.len());
         }
@@ -87,6 +166,44 @@ where
     Ok(())
 }
 
+/// Repeats a scan `times` times, `interval` apart, stopping early once there are no results left
+///
+/// The REPL is blocked until it finishes, so it can't run until stopped like the GUI's auto scan
+fn do_auto_scan<T>(
+    scanner: &mut hoodmem::scanner::Scanner,
+    interval: Duration,
+    times: usize,
+    command: &[&str],
+) where
+    T: Copy
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + PartialOrd
+        + PartialEq
+        + std::ops::Sub<Output = T>
+        + std::ops::Add<Output = T>
+        + RoundTo
+        + ToF64
+        + DivisibleBy
+        + FromStr,
+{
+    for scan_index in 1..=times {
+        if scan_index > 1 {
+            std::thread::sleep(interval);
+        }
+        if let Err(scan_err) = do_scan::<T>(scanner, command) {
+            eprintln!("Scan failed due to {}", scan_err);
+            return;
+        }
+        let num_results = scanner.count_results().unwrap_or(0);
+        println!("Scan {}/{}: {} results", scan_index, times, num_results);
+        if num_results == 0 {
+            return;
+        }
+    }
+}
+
 fn do_scan_with_results<T>(scanner: &mut hoodmem::scanner::Scanner, command: &[&str])
 where
     T: Copy
@@ -97,6 +214,9 @@ where
         + PartialEq
         + std::ops::Sub<Output = T>
         + std::ops::Add<Output = T>
+        + RoundTo
+        + ToF64
+        + DivisibleBy
         + FromStr
         ,
 {
@@ -105,6 +225,9 @@ where
         eprintln!("Scan failed due to {}", scan_err);
     } else {
         println!("Scan was successful");
+        if let Some(stats) = scanner.last_scan_stats() {
+            println!("{}", stats);
+        }
         let results = scanner.get_results::<T>();
         println!("{} Results found (at most first 100 shown)", results.len());
         results
@@ -114,114 +237,1150 @@ where
     }
 }
 
-fn main() -> hoodmem::Result<()> {
-    #[allow(unused_assignments)]
-    let mut process: Option<Arc<dyn Process>> = None;
-    let mut scanner: Option<hoodmem::scanner::Scanner> = None;
-    let mut scan_type: ScanType = ScanType::U32;
+fn do_set<T: Copy + std::fmt::Debug + FromStr>(
+    scanner: &hoodmem::scanner::Scanner,
+    address: u64,
+    value: &str,
+) {
+    if let Ok(value) = T::from_str(value.trim()) {
+        if let Err(err) = scanner.write_result(address, value) {
+            eprintln!("Failed to write to 0x{:016x}: {}", address, err);
+        } else {
+            println!("0x{:016x} = {:?}", address, value);
+        }
+    } else {
+        eprintln!("Unable to parse value {}", value.trim());
+    }
+}
 
-    loop {
-        print!("hoodhax> ");
-        let _ = std::io::stdout().flush();
-        let stdin = std::io::stdin();
-        let mut command = String::new();
-        if stdin.read_line(&mut command).is_ok() {
-            let command: Vec<&str> = command.split(' ').collect();
-            if command.len() > 0 {
-                match command[0].trim() {
-                    "attach" => {
-                        if command.len() == 2 {
-                            if let Ok(pid) = command[1].trim().parse::<u32>() {
-                                if let Ok(attach_result) = hoodmem::attach_external(pid) {
-                                    process = Some(attach_result);
-                                    scanner =
-                                        Some(hoodmem::scanner::Scanner::new(process.unwrap()));
-                                    println!("Successfully attached to process with PID {}", pid);
-                                } else {
-                                    eprintln!("Failed to attach to process with PID {}", pid);
-                                }
-                            } else {
-                                eprintln!("Unable to parse PID {}", command[1].trim());
-                            }
-                        } else {
-                            eprintln!("Expected a PID to attach to");
+fn do_add<T: Copy + std::fmt::Debug + Send + Sync>(
+    scanner: &mut hoodmem::scanner::Scanner,
+    address: u64,
+) {
+    if let Err(err) = scanner.add_manual_address::<T>(address) {
+        eprintln!("Failed to add 0x{:016x}: {}", address, err);
+    } else if let Some((_, value)) = scanner
+        .get_results::<T>()
+        .into_iter()
+        .find(|(addr, _)| *addr == address)
+    {
+        println!("0x{:016x}: {:?}", address, value);
+    }
+}
+
+fn do_show_as<T: Copy + std::fmt::Debug + Send + Sync>(scanner: &hoodmem::scanner::Scanner) {
+    match scanner.get_results_as::<T>() {
+        Ok(results) => {
+            println!("{} Results found (at most first 100 shown)", results.len());
+            results
+                .into_iter()
+                .take(100)
+                .for_each(|(addr, value)| println!("0x{:016x}: {:?}", addr, value));
+        }
+        Err(err) => eprintln!("{}", err),
+    }
+}
+
+/// A scan result as it is exported. The address is a hex string, since many tools can't handle 64 bit integers
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ExportedResult<T> {
+    address: String,
+    value: T,
+}
+
+/// Writes every scan result to `path` as `json` or `csv`, returning how many were written
+fn do_export<T: Copy + Send + Sync + Serialize>(
+    scanner: &hoodmem::scanner::Scanner,
+    format: &str,
+    path: &Path,
+) -> anyhow::Result<usize> {
+    let results: Vec<ExportedResult<T>> = scanner
+        .get_results::<T>()
+        .into_iter()
+        .map(|(address, value)| ExportedResult {
+            address: format!("0x{:016x}", address),
+            value,
+        })
+        .collect();
+    match format {
+        "json" => serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), &results)?,
+        "csv" => {
+            let mut writer = csv::Writer::from_path(path)?;
+            for result in results.iter() {
+                writer.serialize(result)?;
+            }
+            writer.flush()?;
+        }
+        _ => anyhow::bail!("Unknown export format '{}'. Expected json or csv", format),
+    }
+    Ok(results.len())
+}
+
+/// Parses an address, which is hex if prefixed with `0x`, otherwise decimal
+fn parse_address(address: &str) -> Option<u64> {
+    let address = address.trim();
+    if let Some(hex) = address.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16).ok()
+    } else {
+        address.parse::<u64>().ok()
+    }
+}
+
+/// Scans for 3 contiguous floats, given as `<x> <y> <z> [tolerance]`. Unknown components are given as `?`
+fn do_vec3_scan_with_results(scanner: &mut hoodmem::scanner::Scanner, args: &[&str]) {
+    let parse_component = |component: &str| match component.trim() {
+        "?" => Ok(None),
+        component => component.parse::<f32>().map(Some),
+    };
+    let filter = match args {
+        [x, y, z, rest @ ..] if rest.len() <= 1 => {
+            match (
+                parse_component(x),
+                parse_component(y),
+                parse_component(z),
+                rest.first().map_or(Ok(DEFAULT_VEC3_TOLERANCE), |tolerance| {
+                    tolerance.trim().parse::<f32>()
+                }),
+            ) {
+                (Ok(x), Ok(y), Ok(z), Ok(tolerance)) => Vec3Filter {
+                    components: [x, y, z],
+                    tolerance,
+                },
+                _ => {
+                    eprintln!("Unable to parse vector components");
+                    return;
+                }
+            }
+        }
+        _ => {
+            eprintln!("Expected `scan vec3 <x> <y> <z> [tolerance]`, using `?` for unknown components");
+            return;
+        }
+    };
+    if let Err(scan_err) = scanner.scan_vec3(&filter) {
+        eprintln!("Scan failed due to {}", scan_err);
+    } else {
+        println!("Scan was successful");
+        if let Some(stats) = scanner.last_scan_stats() {
+            println!("{}", stats);
+        }
+        let results = scanner.get_results_vec3();
+        println!("{} Results found (at most first 100 shown)", results.len());
+        results
+            .into_iter()
+            .take(100)
+            .for_each(|(addr, [x, y, z])| println!("0x{:016x}: ({}, {}, {})", addr, x, y, z));
+    }
+}
+
+fn do_aob_scan_with_results(scanner: &mut hoodmem::scanner::Scanner, pattern: &str) {
+    let pattern = match hoodmem::util::parse_aob(pattern) {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            eprintln!("Unable to parse AOB pattern: {}", err);
+            return;
+        }
+    };
+    if let Err(scan_err) = scanner.scan_aob(&pattern) {
+        eprintln!("Scan failed due to {}", scan_err);
+    } else {
+        println!("Scan was successful");
+        if let Some(stats) = scanner.last_scan_stats() {
+            println!("{}", stats);
+        }
+        let results = scanner.get_results::<u8>();
+        println!("{} Results found (at most first 100 shown)", results.len());
+        results
+            .into_iter()
+            .take(100)
+            .for_each(|(addr, _)| println!("0x{:016x}", addr));
+    }
+}
+
+/// Parses a scan type name such as `u32`
+fn parse_scan_type(name: &str) -> Option<ScanType> {
+    match name {
+        "u8" => Some(ScanType::U8),
+        "u16" => Some(ScanType::U16),
+        "u32" => Some(ScanType::U32),
+        "u64" => Some(ScanType::U64),
+        "i8" => Some(ScanType::I8),
+        "i16" => Some(ScanType::I16),
+        "i32" => Some(ScanType::I32),
+        "i64" => Some(ScanType::I64),
+        "f32" => Some(ScanType::F32),
+        "f64" => Some(ScanType::F64),
+        _ => None,
+    }
+}
+
+/// State kept between commands, whether they are typed at the prompt or read from a script
+struct ReplState {
+    scanner: Option<hoodmem::scanner::Scanner>,
+    scan_type: ScanType,
+    snapshots: Vec<hoodmem::snapshot::Snapshot>,
+}
+
+impl Default for ReplState {
+    fn default() -> Self {
+        Self {
+            scanner: None,
+            scan_type: ScanType::U32,
+            snapshots: vec![],
+        }
+    }
+}
+
+/// Restores any patches made in the attached process, before detaching from it or exiting
+fn restore_patches(state: &mut ReplState) {
+    if let Some(scanner) = state.scanner.as_mut() {
+        if !scanner.active_patches().is_empty() {
+            match scanner.restore_all_patches() {
+                Ok(()) => println!("Restored all patches"),
+                Err(err) => eprintln!("Failed to restore some patches: {}", err),
+            }
+        }
+    }
+}
+
+/// Runs a single command line. Returns false once the session should end
+fn execute_command(state: &mut ReplState, line: &str) -> bool {
+    let command: Vec<&str> = line.split(' ').collect();
+    match command[0].trim() {
+        "attach" => {
+            if command.len() == 2 {
+                if let Ok(pid) = command[1].trim().parse::<u32>() {
+                    if let Ok(attach_result) = hoodmem::attach_external(pid) {
+                        restore_patches(state);
+                        state.scanner = Some(hoodmem::scanner::Scanner::new(attach_result));
+                        println!("Successfully attached to process with PID {}", pid);
+                    } else {
+                        eprintln!("Failed to attach to process with PID {}", pid);
+                    }
+                } else {
+                    eprintln!("Unable to parse PID {}", command[1].trim());
+                }
+            } else {
+                eprintln!("Expected a PID to attach to");
+            }
+        }
+        "newscan" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                scanner.new_scan();
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "undo" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                match scanner.undo_scan() {
+                    Ok(()) => match scanner.count_results() {
+                        Some(count) => println!("Restored {} results", count),
+                        None => println!("Undid the first scan"),
+                    },
+                    Err(err) => eprintln!("{}", err),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "scantype" => {
+            if command.len() == 2 {
+                match parse_scan_type(command[1].trim()) {
+                    Some(parsed) => state.scan_type = parsed,
+                    None => {
+                        eprintln!("Unknown scan type '{}'", command[1].trim());
+                    }
+                }
+            } else {
+                eprintln!("Expected a scan type ({{u,i}}{{8,16,32,64}} or f{{32,64}})");
+            }
+        }
+        "scan" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                if command.len() > 1 && command[1].trim() == "aob" {
+                    do_aob_scan_with_results(scanner, &command[2..].join(" "));
+                    return true;
+                }
+                if command.len() > 1 && command[1].trim() == "vec3" {
+                    do_vec3_scan_with_results(scanner, &command[2..]);
+                    return true;
+                }
+                match state.scan_type {
+                    ScanType::U8 => do_scan_with_results::<u8>(scanner, &command[1..]),
+                    ScanType::U16 => {
+                        do_scan_with_results::<u16>(scanner, &command[1..])
+                    }
+                    ScanType::U32 => {
+                        do_scan_with_results::<u32>(scanner, &command[1..])
+                    }
+                    ScanType::U64 => {
+                        do_scan_with_results::<u64>(scanner, &command[1..])
+                    }
+                    ScanType::I8 => do_scan_with_results::<i8>(scanner, &command[1..]),
+                    ScanType::I16 => {
+                        do_scan_with_results::<i16>(scanner, &command[1..])
+                    }
+                    ScanType::I32 => {
+                        do_scan_with_results::<i32>(scanner, &command[1..])
+                    }
+                    ScanType::I64 => {
+                        do_scan_with_results::<i64>(scanner, &command[1..])
+                    }
+                    ScanType::F32 => {
+                        do_scan_with_results::<f32>(scanner, &command[1..])
+                    }
+                    ScanType::F64 => {
+                        do_scan_with_results::<f64>(scanner, &command[1..])
+                    }
+                };
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "autoscan" => {
+            // autoscan <interval ms> <times> <scan arguments...>
+            if let Some(scanner) = state.scanner.as_mut() {
+                match (
+                    command.get(1).map(|interval| interval.trim().parse::<u64>()),
+                    command.get(2).map(|times| times.trim().parse::<usize>()),
+                ) {
+                    (Some(Ok(interval)), Some(Ok(times))) if command.len() > 3 => {
+                        let interval = Duration::from_millis(interval);
+                        let scan = &command[3..];
+                        match state.scan_type {
+                            ScanType::U8 => do_auto_scan::<u8>(scanner, interval, times, scan),
+                            ScanType::U16 => do_auto_scan::<u16>(scanner, interval, times, scan),
+                            ScanType::U32 => do_auto_scan::<u32>(scanner, interval, times, scan),
+                            ScanType::U64 => do_auto_scan::<u64>(scanner, interval, times, scan),
+                            ScanType::I8 => do_auto_scan::<i8>(scanner, interval, times, scan),
+                            ScanType::I16 => do_auto_scan::<i16>(scanner, interval, times, scan),
+                            ScanType::I32 => do_auto_scan::<i32>(scanner, interval, times, scan),
+                            ScanType::I64 => do_auto_scan::<i64>(scanner, interval, times, scan),
+                            ScanType::F32 => do_auto_scan::<f32>(scanner, interval, times, scan),
+                            ScanType::F64 => do_auto_scan::<f64>(scanner, interval, times, scan),
                         }
                     }
-                    "newscan" => {
-                        if let Some(scanner) = scanner.as_mut() {
-                            scanner.new_scan();
-                        } else {
-                            eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+                    _ => eprintln!("Expected `autoscan <interval ms> <times> <scan arguments...>`, such as `autoscan 1000 10 unchanged`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "scanbounds" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                if command.len() == 2 && command[1].trim() == "clear" {
+                    scanner.clear_scan_bounds();
+                    println!("Scan bounds cleared");
+                } else if command.len() == 3 {
+                    match (parse_address(command[1]), parse_address(command[2])) {
+                        (Some(start), Some(end)) if start < end => {
+                            scanner.set_scan_bounds(start, end);
+                            println!(
+                                "New scans will be restricted to 0x{:016x} - 0x{:016x}",
+                                start, end
+                            );
+                        }
+                        (Some(_), Some(_)) => {
+                            eprintln!("Scan bounds start must be before the end")
+                        }
+                        _ => eprintln!(
+                            "Unable to parse scan bounds {} {}",
+                            command[1].trim(),
+                            command[2].trim()
+                        ),
+                    }
+                } else {
+                    eprintln!("Expected `scanbounds <start> <end>` or `scanbounds clear`");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "filteraddr" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                match (
+                    command.get(1).and_then(|start| parse_address(start)),
+                    command.get(2).and_then(|end| parse_address(end)),
+                ) {
+                    (Some(start), Some(end)) if start < end => {
+                        match scanner.filter_results_by_address(start, end) {
+                            Ok(()) => println!(
+                                "{} results between 0x{:016x} and 0x{:016x}",
+                                scanner.count_results().unwrap_or(0),
+                                start,
+                                end
+                            ),
+                            Err(err) => eprintln!("{}", err),
                         }
                     }
-                    "scantype" => {
-                        if command.len() == 2 {
-                            match command[1].trim() {
-                                "u8" => scan_type = ScanType::U8,
-                                "u16" => scan_type = ScanType::U16,
-                                "u32" => scan_type = ScanType::U32,
-                                "u64" => scan_type = ScanType::U64,
-                                "i8" => scan_type = ScanType::I8,
-                                "i16" => scan_type = ScanType::I16,
-                                "i32" => scan_type = ScanType::I32,
-                                "i64" => scan_type = ScanType::I64,
-                                "f32" => scan_type = ScanType::F32,
-                                "f64" => scan_type = ScanType::F64,
-                                _ => {
-                                    eprintln!("Unknown scan type '{}'", command[1].trim());
-                                }
+                    _ => eprintln!("Expected `filteraddr <start> <end>`, with start before end"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "filterpointers" => {
+            // Keeps results whose value points into readable memory
+            if let Some(scanner) = state.scanner.as_mut() {
+                match scanner.filter_pointers() {
+                    Ok(()) => println!(
+                        "{} results point into readable memory",
+                        scanner.count_results().unwrap_or(0)
+                    ),
+                    Err(err) => eprintln!("{}", err),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "clusters" => {
+            // Groups results which are close together, hinting at structs
+            if let Some(scanner) = state.scanner.as_ref() {
+                match command.get(1).map(|window| window.trim().parse::<u64>()) {
+                    Some(Ok(window)) => {
+                        let clusters = scanner.cluster_results(window);
+                        println!("{} clusters (at most first 20 shown)", clusters.len());
+                        clusters.iter().take(20).for_each(|cluster| {
+                            println!(
+                                "0x{:016x} - 0x{:016x}: {} results",
+                                cluster[0],
+                                cluster[cluster.len() - 1],
+                                cluster.len()
+                            )
+                        });
+                    }
+                    _ => eprintln!("Expected `clusters <window>`, such as `clusters 256`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "maxregion" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                match command.get(1).map(|bytes| bytes.trim().parse::<u64>()) {
+                    Some(Ok(bytes)) => {
+                        scanner.set_max_region_bytes(bytes);
+                        println!("New scans will skip regions larger than {} bytes", bytes);
+                    }
+                    _ => eprintln!("Expected `maxregion <bytes>`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "modules" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                scanner.modules().iter().for_each(|module| {
+                    println!(
+                        "0x{:016x} {:>12} bytes  {}",
+                        module.base_address, module.size, module.name
+                    )
+                });
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "resolve" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                match (command.get(1), command.get(2).and_then(|offset| parse_address(offset))) {
+                    (Some(module), Some(offset)) => {
+                        match hoodmem::resolve_module_offset(scanner.process().as_ref(), module.trim(), offset) {
+                            Ok(address) => println!("0x{:016x}", address),
+                            Err(err) => eprintln!("{}", err),
+                        }
+                    }
+                    _ => eprintln!("Expected `resolve <module> <offset>`, such as `resolve game.exe 0x1234`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "align" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                match command.get(1).map(|alignment| alignment.trim().parse::<u64>()) {
+                    Some(Ok(alignment)) if alignment > 0 => {
+                        scanner.set_alignment(alignment);
+                        println!("Scans will only find values at multiples of {}", alignment);
+                    }
+                    _ => eprintln!("Expected `align <bytes>`, such as `align 4`. Use `align 1` to allow any address"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "endianness" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                match command.get(1).map(|endianness| endianness.trim()) {
+                    Some("little") => {
+                        scanner.set_endianness(hoodmem::util::Endianness::Little)
+                    }
+                    Some("big") => scanner.set_endianness(hoodmem::util::Endianness::Big),
+                    _ => eprintln!("Expected `endianness little` or `endianness big`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "set" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                if command.len() == 3 {
+                    if let Some(address) = parse_address(command[1]) {
+                        match state.scan_type {
+                            ScanType::U8 => do_set::<u8>(scanner, address, command[2]),
+                            ScanType::U16 => do_set::<u16>(scanner, address, command[2]),
+                            ScanType::U32 => do_set::<u32>(scanner, address, command[2]),
+                            ScanType::U64 => do_set::<u64>(scanner, address, command[2]),
+                            ScanType::I8 => do_set::<i8>(scanner, address, command[2]),
+                            ScanType::I16 => do_set::<i16>(scanner, address, command[2]),
+                            ScanType::I32 => do_set::<i32>(scanner, address, command[2]),
+                            ScanType::I64 => do_set::<i64>(scanner, address, command[2]),
+                            ScanType::F32 => do_set::<f32>(scanner, address, command[2]),
+                            ScanType::F64 => do_set::<f64>(scanner, address, command[2]),
+                        }
+                    } else {
+                        eprintln!("Unable to parse address {}", command[1].trim());
+                    }
+                } else {
+                    eprintln!("Expected `set <address> <value>`");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "patch" => {
+            // patch <address> <AA BB CC ...>
+            if let Some(scanner) = state.scanner.as_ref() {
+                match (
+                    command.get(1).and_then(|address| parse_address(address)),
+                    hoodmem::util::parse_hex_bytes(&command.get(2..).unwrap_or_default().join(" ")),
+                ) {
+                    (Some(address), Ok(bytes)) => match scanner.write_bytes(address, &bytes) {
+                        Ok(()) => println!("Wrote {} bytes to 0x{:016x}", bytes.len(), address),
+                        Err(err) => eprintln!("Failed to patch 0x{:016x}: {}", address, err),
+                    },
+                    (None, _) => eprintln!("Expected `patch <address> <AA BB CC ...>`"),
+                    (_, Err(err)) => eprintln!("Unable to parse bytes: {}", err),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "nop" => {
+            // nop <address> <len>, rounded up to whole instructions
+            if let Some(scanner) = state.scanner.as_mut() {
+                match (
+                    command.get(1).and_then(|address| parse_address(address)),
+                    command.get(2).map(|len| len.trim().parse::<usize>()),
+                ) {
+                    (Some(address), Some(Ok(len))) => {
+                        let patched = hoodmem::disasm::whole_instructions_len(
+                            scanner.process().as_ref(),
+                            address,
+                            len,
+                        )
+                        .and_then(|len| scanner.nop_patch(address, len));
+                        match patched {
+                            Ok(handle) => println!(
+                                "NOPed {} bytes at 0x{:016x}. Undo with `restore 0x{:x}`",
+                                handle.original.len(),
+                                address,
+                                address
+                            ),
+                            Err(err) => eprintln!("Failed to patch 0x{:016x}: {}", address, err),
+                        }
+                    }
+                    _ => eprintln!("Expected `nop <address> <len>`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "restore" => {
+            // restore <address> | restore all
+            if let Some(scanner) = state.scanner.as_mut() {
+                match command.get(1).map(|target| target.trim()) {
+                    Some("all") => match scanner.restore_all_patches() {
+                        Ok(()) => println!("Restored all patches"),
+                        Err(err) => eprintln!("Failed to restore some patches: {}", err),
+                    },
+                    Some(address) => {
+                        let handle = parse_address(address).and_then(|address| {
+                            scanner
+                                .active_patches()
+                                .iter()
+                                .rev()
+                                .find(|patch| patch.address == address)
+                                .cloned()
+                        });
+                        match handle.map(|handle| scanner.restore_patch(&handle)) {
+                            Some(Ok(())) => println!("Restored patch at {}", address),
+                            Some(Err(err)) => eprintln!("Failed to restore patch at {}: {}", address, err),
+                            None => eprintln!("No active patch at {}", address),
+                        }
+                    }
+                    None => eprintln!("Expected `restore <address>` or `restore all`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "disasm" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                if command.len() == 3 {
+                    match (parse_address(command[1]), command[2].trim().parse::<usize>()) {
+                        (Some(address), Ok(count)) => {
+                            match hoodmem::disasm::disassemble(
+                                scanner.process().as_ref(),
+                                address,
+                                count,
+                            ) {
+                                Ok(instructions) => instructions
+                                    .iter()
+                                    .for_each(|instruction| println!("{}", instruction)),
+                                Err(err) => eprintln!("Failed to disassemble: {}", err),
                             }
-                        } else {
-                            eprintln!("Expected a scan type ({{u,i}}{{8,16,32,64}} or f{{32,64}})");
                         }
+                        _ => eprintln!(
+                            "Unable to parse address and count {} {}",
+                            command[1].trim(),
+                            command[2].trim()
+                        ),
                     }
-                    "scan" => {
-                        if let Some(scanner) = scanner.as_mut() {
-                            match scan_type {
-                                ScanType::U8 => do_scan_with_results::<u8>(scanner, &command[1..]),
-                                ScanType::U16 => {
-                                    do_scan_with_results::<u16>(scanner, &command[1..])
-                                }
-                                ScanType::U32 => {
-                                    do_scan_with_results::<u32>(scanner, &command[1..])
-                                }
-                                ScanType::U64 => {
-                                    do_scan_with_results::<u64>(scanner, &command[1..])
-                                }
-                                ScanType::I8 => do_scan_with_results::<i8>(scanner, &command[1..]),
-                                ScanType::I16 => {
-                                    do_scan_with_results::<i16>(scanner, &command[1..])
-                                }
-                                ScanType::I32 => {
-                                    do_scan_with_results::<i32>(scanner, &command[1..])
-                                }
-                                ScanType::I64 => {
-                                    do_scan_with_results::<i64>(scanner, &command[1..])
-                                }
-                                ScanType::F32 => {
-                                    do_scan_with_results::<f32>(scanner, &command[1..])
-                                }
-                                ScanType::F64 => {
-                                    do_scan_with_results::<f64>(scanner, &command[1..])
-                                }
-                            };
-                        } else {
-                            eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+                } else {
+                    eprintln!("Expected `disasm <address> <count>`");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "dump" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                match (
+                    command.get(1).and_then(|address| parse_address(address)),
+                    command.get(2).map(|len| len.trim().parse::<usize>()),
+                ) {
+                    (Some(address), Some(Ok(len))) => match scanner.read_region(address, len) {
+                        Ok(bytes) => println!("{}", hoodmem::util::hex_dump(&bytes, address)),
+                        Err(err) => eprintln!("Failed to read 0x{:016x}: {}", address, err),
+                    },
+                    _ => eprintln!("Expected `dump <address> <len>`"),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "snapshot" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                match scanner.snapshot() {
+                    Ok(snapshot) => {
+                        println!(
+                            "Took snapshot {} ({} bytes)",
+                            state.snapshots.len(),
+                            snapshot.size()
+                        );
+                        state.snapshots.push(snapshot);
+                    }
+                    Err(err) => eprintln!("Failed to take snapshot: {}", err),
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "diff" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                let indexes: Vec<Option<usize>> = command[1..]
+                    .iter()
+                    .map(|index| index.trim().parse::<usize>().ok())
+                    .collect();
+                let changes = match indexes.as_slice() {
+                    [Some(a)] if *a < state.snapshots.len() => {
+                        Some(state.snapshots[*a].diff(scanner.process().as_ref()))
+                    }
+                    [Some(a), Some(b)] if *a < state.snapshots.len() && *b < state.snapshots.len() => {
+                        Some(Ok(state.snapshots[*a].diff_snapshot(&state.snapshots[*b])))
+                    }
+                    _ => None,
+                };
+                if let Some(changes) = changes {
+                    match changes {
+                        Ok(changes) => {
+                            println!("{} Changes found (at most first 100 shown)", changes.len());
+                            changes.iter().take(100).for_each(|(addr, old, new)| {
+                                println!("0x{:016x}: {:02x?} -> {:02x?}", addr, old, new)
+                            });
                         }
+                        Err(err) => eprintln!("Failed to diff snapshot: {}", err),
+                    }
+                } else {
+                    eprintln!("Expected `diff <snapshot>` or `diff <snapshot> <snapshot>` snapshots taken by `snapshot`");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "add" => {
+            if let Some(scanner) = state.scanner.as_mut() {
+                if let Some(address) = command.get(1).and_then(|address| parse_address(address)) {
+                    match state.scan_type {
+                        ScanType::U8 => do_add::<u8>(scanner, address),
+                        ScanType::U16 => do_add::<u16>(scanner, address),
+                        ScanType::U32 => do_add::<u32>(scanner, address),
+                        ScanType::U64 => do_add::<u64>(scanner, address),
+                        ScanType::I8 => do_add::<i8>(scanner, address),
+                        ScanType::I16 => do_add::<i16>(scanner, address),
+                        ScanType::I32 => do_add::<i32>(scanner, address),
+                        ScanType::I64 => do_add::<i64>(scanner, address),
+                        ScanType::F32 => do_add::<f32>(scanner, address),
+                        ScanType::F64 => do_add::<f64>(scanner, address),
+                    }
+                } else {
+                    eprintln!("Expected `add <address>`");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "savesession" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                if command.len() == 2 {
+                    let path = std::path::Path::new(command[1].trim());
+                    if let Err(err) = scanner.save_session(path) {
+                        eprintln!("Failed to save session: {}", err);
+                    } else {
+                        println!("Session saved to {}", path.display());
                     }
-                    "getresults" => {
-                        if let Some(scanner) = scanner.as_ref() {
-                            scanner.results.values().for_each(|r| r.print::<u8>());
+                } else {
+                    eprintln!("Expected a path to save the session to");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "loadsession" => {
+            if let Some(current_scanner) = state.scanner.as_mut() {
+                if command.len() == 2 {
+                    let path = std::path::Path::new(command[1].trim());
+                    match hoodmem::scanner::Scanner::load_session(
+                        path,
+                        current_scanner.process(),
+                    ) {
+                        Ok(loaded) => {
+                            *current_scanner = loaded;
+                            println!("Session loaded from {}", path.display());
                         }
+                        Err(err) => eprintln!("Failed to load session: {}", err),
                     }
-                    "quit" => break,
-                    _ => {
-                        println!("Unknown command '{}'", command[0].trim());
+                } else {
+                    eprintln!("Expected a path to load the session from");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "export" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                if command.len() == 3 {
+                    let format = command[1].trim();
+                    let path = Path::new(command[2].trim());
+                    let exported = match state.scan_type {
+                        ScanType::U8 => do_export::<u8>(scanner, format, path),
+                        ScanType::U16 => do_export::<u16>(scanner, format, path),
+                        ScanType::U32 => do_export::<u32>(scanner, format, path),
+                        ScanType::U64 => do_export::<u64>(scanner, format, path),
+                        ScanType::I8 => do_export::<i8>(scanner, format, path),
+                        ScanType::I16 => do_export::<i16>(scanner, format, path),
+                        ScanType::I32 => do_export::<i32>(scanner, format, path),
+                        ScanType::I64 => do_export::<i64>(scanner, format, path),
+                        ScanType::F32 => do_export::<f32>(scanner, format, path),
+                        ScanType::F64 => do_export::<f64>(scanner, format, path),
+                    };
+                    match exported {
+                        Ok(count) => println!("Exported {} results to {}", count, path.display()),
+                        Err(err) => eprintln!("Failed to export results: {}", err),
                     }
+                } else {
+                    eprintln!("Expected `export <json|csv> <path>`");
+                }
+            } else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+            }
+        }
+        "as" => {
+            // Shows the results as another type of the same size, such as i32 after scanning for u32
+            let Some(scanner) = state.scanner.as_ref() else {
+                eprintln!("Scanner not yet initialized. Please attach to a process first with `attach <pid>`");
+                return true;
+            };
+            match command.get(1).and_then(|name| parse_scan_type(name.trim())) {
+                Some(ScanType::U8) => do_show_as::<u8>(scanner),
+                Some(ScanType::U16) => do_show_as::<u16>(scanner),
+                Some(ScanType::U32) => do_show_as::<u32>(scanner),
+                Some(ScanType::U64) => do_show_as::<u64>(scanner),
+                Some(ScanType::I8) => do_show_as::<i8>(scanner),
+                Some(ScanType::I16) => do_show_as::<i16>(scanner),
+                Some(ScanType::I32) => do_show_as::<i32>(scanner),
+                Some(ScanType::I64) => do_show_as::<i64>(scanner),
+                Some(ScanType::F32) => do_show_as::<f32>(scanner),
+                Some(ScanType::F64) => do_show_as::<f64>(scanner),
+                None => eprintln!("Expected `as <type>`, such as `as i32`"),
+            }
+        }
+        "getresults" => {
+            if let Some(scanner) = state.scanner.as_ref() {
+                scanner.results.values().for_each(|r| r.print::<u8>());
+            }
+        }
+        "quit" => return false,
+        _ => {
+            println!("Unknown command '{}'", command[0].trim());
+        }
+    }
+    true
+}
+
+/// Runs each line of a script as a command, skipping blank lines and `#` comments, until one ends the session
+fn run_script(state: &mut ReplState, reader: impl BufRead) -> std::io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            // Blank lines and comments
+            continue;
+        }
+        println!("hoodhax> {}", line.trim());
+        if !execute_command(state, &line) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> hoodmem::Result<()> {
+    tracing_subscriber::fmt::init();
+    let args = Args::parse();
+    let mut state = ReplState::default();
+
+    if let Some(name) = args.scan_type.as_deref() {
+        match parse_scan_type(name.trim()) {
+            Some(parsed) => state.scan_type = parsed,
+            None => eprintln!("Unknown scan type '{}'", name.trim()),
+        }
+    }
+    if let Some(pid) = args.pid {
+        match hoodmem::attach_external(pid) {
+            Ok(attach_result) => {
+                state.scanner = Some(hoodmem::scanner::Scanner::new(attach_result));
+                println!("Successfully attached to process with PID {}", pid);
+            }
+            Err(err) => eprintln!("Failed to attach to process with PID {}: {}", pid, err),
+        }
+    } else if let Some(name) = args.name.as_deref() {
+        match hoodmem::attach_external_by_name(name) {
+            Ok(attach_result) => {
+                state.scanner = Some(hoodmem::scanner::Scanner::new(attach_result));
+                println!("Successfully attached to '{}'", name);
+            }
+            Err(err) => eprintln!("Failed to attach to '{}': {}", name, err),
+        }
+    }
+
+    if let Some(script) = args.script.as_deref() {
+        // Scripts run without a prompt, and end the session once every line has run
+        let reader: Box<dyn BufRead> = if script == Path::new("-") {
+            Box::new(std::io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(script)?))
+        };
+        run_script(&mut state, reader)?;
+        restore_patches(&mut state);
+        return Ok(());
+    }
+
+    loop {
+        print!("hoodhax> ");
+        let _ = std::io::stdout().flush();
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            // End of input, such as when stdin is piped from a file
+            Ok(0) => break,
+            Ok(_) => {
+                if !execute_command(&mut state, &line) {
+                    break;
                 }
             }
+            Err(_) => {}
         }
     }
 
+    restore_patches(&mut state);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hoodmem::mock::MockProcess;
+    use hoodmem::{GenericMemoryRead, MemoryRead};
+    use std::sync::Arc;
+
+    /// Base address of the first region of test processes
+    const BASE: u64 = 0x10000;
+
+    /// Gets a session attached to `process`, as if `attach` had been run
+    fn attached_state(process: MockProcess) -> (Arc<MockProcess>, ReplState) {
+        let process = Arc::new(process);
+        let state = ReplState {
+            scanner: Some(hoodmem::scanner::Scanner::new(process.clone())),
+            ..Default::default()
+        };
+        (process, state)
+    }
+
+    fn result_addresses(state: &ReplState) -> Vec<u64> {
+        state
+            .scanner
+            .as_ref()
+            .unwrap()
+            .get_results::<u32>()
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect()
+    }
+
+    #[test]
+    fn scan_commands_dispatch_to_matching_filters() {
+        let (process, mut state) = attached_state(MockProcess::new().with_region(BASE, vec![0; 16]));
+        for offset in [0, 4, 8, 12] {
+            process.set_value(BASE + offset, 10u32);
+        }
+        assert!(execute_command(&mut state, "scan exact 10"));
+        assert_eq!(result_addresses(&state), vec![BASE, BASE + 4, BASE + 8, BASE + 12]);
+
+        process.set_value(BASE + 4, 11u32);
+        process.set_value(BASE + 8, 9u32);
+        assert!(execute_command(&mut state, "scan changed"));
+        assert_eq!(result_addresses(&state), vec![BASE + 4, BASE + 8]);
+        assert!(execute_command(&mut state, "undo"));
+        assert!(execute_command(&mut state, "scan unchanged"));
+        assert_eq!(result_addresses(&state), vec![BASE, BASE + 12]);
+        assert!(execute_command(&mut state, "undo"));
+        assert!(execute_command(&mut state, "scan increased"));
+        assert_eq!(result_addresses(&state), vec![BASE + 4]);
+        assert!(execute_command(&mut state, "undo"));
+        assert!(execute_command(&mut state, "scan decreased"));
+        assert_eq!(result_addresses(&state), vec![BASE + 8]);
+    }
+
+    #[test]
+    fn scanbounds_command_limits_new_scans() {
+        let mut memory = vec![0u8; 0x100];
+        for offset in [0x0, 0x40, 0x80] {
+            memory[offset..offset + 4].copy_from_slice(&7u32.to_le_bytes());
+        }
+        let (_, mut state) = attached_state(MockProcess::new().with_region(BASE, memory));
+
+        assert!(execute_command(&mut state, "scanbounds 0x10040 0x10080"));
+        assert!(execute_command(&mut state, "scan exact 7"));
+        assert_eq!(result_addresses(&state), vec![BASE + 0x40]);
+
+        // Reversed or unparseable bounds leave the current ones in place
+        assert!(execute_command(&mut state, "scanbounds 0x10080 0x10040"));
+        assert!(execute_command(&mut state, "scanbounds zzz 0x10040"));
+        assert!(execute_command(&mut state, "newscan"));
+        assert!(execute_command(&mut state, "scan exact 7"));
+        assert_eq!(result_addresses(&state), vec![BASE + 0x40]);
+
+        assert!(execute_command(&mut state, "scanbounds clear"));
+        assert!(execute_command(&mut state, "newscan"));
+        assert!(execute_command(&mut state, "scan exact 7"));
+        assert_eq!(result_addresses(&state), vec![BASE, BASE + 0x40, BASE + 0x80]);
+    }
+
+    #[test]
+    fn set_command_writes_value_as_scan_type() {
+        let (process, mut state) =
+            attached_state(MockProcess::new().with_region(BASE, vec![0; 16]));
+
+        assert!(execute_command(&mut state, "set 0x10004 1234"));
+        let value: u32 = process.read_memory(BASE + 4).unwrap();
+        assert_eq!(value, 1234);
+
+        assert!(execute_command(&mut state, "scantype f32"));
+        assert!(execute_command(&mut state, "set 0x10008 2.5"));
+        let value: f32 = process.read_memory(BASE + 8).unwrap();
+        assert_eq!(value, 2.5);
+
+        // Values which don't parse as the scan type aren't written
+        assert!(execute_command(&mut state, "scantype u8"));
+        assert!(execute_command(&mut state, "set 0x10000 300"));
+        let value: u8 = process.read_memory(BASE).unwrap();
+        assert_eq!(value, 0);
+    }
+
+    #[test]
+    fn snapshot_and_diff_commands_keep_snapshots() {
+        let (process, mut state) =
+            attached_state(MockProcess::new().with_region(BASE, vec![0; 16]));
+
+        assert!(execute_command(&mut state, "snapshot"));
+        process.set_bytes(BASE + 4, &[7]);
+        assert!(execute_command(&mut state, "snapshot"));
+        assert_eq!(state.snapshots.len(), 2);
+        assert!(execute_command(&mut state, "diff 0 1"));
+        assert!(execute_command(&mut state, "diff 0"));
+        // Out of range snapshots are rejected rather than panicking
+        assert!(execute_command(&mut state, "diff 2"));
+        assert_eq!(
+            state.snapshots[0].diff_snapshot(&state.snapshots[1]),
+            vec![(BASE + 4, vec![0], vec![7])]
+        );
+    }
+
+    #[test]
+    fn add_command_adds_result_without_scanning() {
+        let (process, mut state) =
+            attached_state(MockProcess::new().with_region(BASE, vec![0; 16]));
+        process.set_value(BASE + 8, 99u32);
+
+        assert!(execute_command(&mut state, "add 0x10008"));
+        assert_eq!(result_addresses(&state), vec![BASE + 8]);
+        // Unmapped addresses aren't added
+        assert!(execute_command(&mut state, "add 0x20000"));
+        assert_eq!(result_addresses(&state), vec![BASE + 8]);
+    }
+
+    #[test]
+    fn args_attach_by_pid_with_scan_type() {
+        let args = Args::try_parse_from(["hoodhax", "--pid", "1234", "--scan-type", "u32"]).unwrap();
+        assert_eq!(args.pid, Some(1234));
+        assert_eq!(args.name, None);
+        assert!(matches!(
+            args.scan_type.as_deref().and_then(parse_scan_type),
+            Some(ScanType::U32)
+        ));
+    }
+
+    #[test]
+    fn args_attach_by_name() {
+        let args = Args::try_parse_from(["hoodhax", "--name", "window title"]).unwrap();
+        assert_eq!(args.pid, None);
+        assert_eq!(args.name.as_deref(), Some("window title"));
+    }
+
+    #[test]
+    fn args_are_optional() {
+        let args = Args::try_parse_from(["hoodhax"]).unwrap();
+        assert!(args.pid.is_none() && args.name.is_none() && args.scan_type.is_none() && args.script.is_none());
+    }
+
+    #[test]
+    fn args_refuse_pid_and_name_together() {
+        assert!(Args::try_parse_from(["hoodhax", "--pid", "1", "--name", "game"]).is_err());
+        assert!(Args::try_parse_from(["hoodhax", "--pid", "not a pid"]).is_err());
+    }
+
+    #[test]
+    fn script_runs_commands_until_quit() {
+        let mut memory = vec![0u8; 16];
+        memory[0..4].copy_from_slice(&7u32.to_le_bytes());
+        memory[8..12].copy_from_slice(&7u32.to_le_bytes());
+        let (process, mut state) = attached_state(MockProcess::new().with_region(BASE, memory));
+        let script = "# Find the value, then change one of them\n\
+                      scantype u32\n\
+                      scan exact 7\n\
+                      \n\
+                      set 0x10008 9\n\
+                      quit\n\
+                      scan exact 9\n";
+
+        run_script(&mut state, std::io::Cursor::new(script)).unwrap();
+        let value: u32 = process.read_memory(BASE + 8).unwrap();
+        assert_eq!(value, 9);
+        // Nothing after `quit` runs
+        assert_eq!(result_addresses(&state), vec![BASE, BASE + 8]);
+    }
+
+    #[test]
+    fn export_round_trips_results() {
+        let mut memory = vec![0u8; 16];
+        memory[0..4].copy_from_slice(&7u32.to_le_bytes());
+        memory[8..12].copy_from_slice(&7u32.to_le_bytes());
+        let (_, mut state) = attached_state(MockProcess::new().with_region(BASE, memory));
+        assert!(execute_command(&mut state, "scantype u32"));
+        assert!(execute_command(&mut state, "scan exact 7"));
+        let expected = vec![
+            ExportedResult { address: "0x0000000000010000".to_string(), value: 7u32 },
+            ExportedResult { address: "0x0000000000010008".to_string(), value: 7u32 },
+        ];
+
+        let json_path = std::env::temp_dir().join(format!("hoodhax_export_{}.json", std::process::id()));
+        assert!(execute_command(&mut state, &format!("export json {}", json_path.display())));
+        let exported: Vec<ExportedResult<u32>> =
+            serde_json::from_reader(File::open(&json_path).unwrap()).unwrap();
+        assert_eq!(exported, expected);
+        std::fs::remove_file(&json_path).unwrap();
+
+        let csv_path = std::env::temp_dir().join(format!("hoodhax_export_{}.csv", std::process::id()));
+        assert!(execute_command(&mut state, &format!("export csv {}", csv_path.display())));
+        let exported: Vec<ExportedResult<u32>> = csv::Reader::from_path(&csv_path)
+            .unwrap()
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(exported, expected);
+        std::fs::remove_file(&csv_path).unwrap();
+    }
+
+    #[test]
+    fn filteraddr_command_drops_hits_out_of_range() {
+        let mut memory = vec![0u8; 16];
+        for offset in [0, 4, 8, 12] {
+            memory[offset..offset + 4].copy_from_slice(&7u32.to_le_bytes());
+        }
+        let (_, mut state) = attached_state(MockProcess::new().with_region(BASE, memory));
+        assert!(execute_command(&mut state, "scan exact 7"));
+        assert!(execute_command(&mut state, "filteraddr 0x10004 0x1000c"));
+        assert_eq!(result_addresses(&state), vec![BASE + 4, BASE + 8]);
+
+        // Reversed bounds are refused
+        assert!(execute_command(&mut state, "filteraddr 0x1000c 0x10004"));
+        assert_eq!(result_addresses(&state), vec![BASE + 4, BASE + 8]);
+    }
+
+    #[test]
+    fn patch_command_writes_bytes() {
+        let (process, mut state) =
+            attached_state(MockProcess::new().with_region(BASE, vec![0xcc; 8]));
+        assert!(execute_command(&mut state, "patch 0x10004 90 90"));
+        assert_eq!(
+            process.read_memory_bytes(BASE, 8).unwrap(),
+            vec![0xcc, 0xcc, 0xcc, 0xcc, 0x90, 0x90, 0xcc, 0xcc]
+        );
+
+        // Nothing is written unless both the address and every byte parse
+        assert!(execute_command(&mut state, "patch 0x10000 90 zz"));
+        assert!(execute_command(&mut state, "patch nowhere 90"));
+        assert_eq!(process.read_memory_bytes(BASE, 2).unwrap(), vec![0xcc, 0xcc]);
+    }
+
+    #[test]
+    fn restore_command_restores_patches() {
+        let code = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let (process, mut state) =
+            attached_state(MockProcess::new().with_region(BASE, code.clone()));
+        let scanner = state.scanner.as_mut().unwrap();
+        scanner.nop_patch(BASE, 2).unwrap();
+        scanner.nop_patch(BASE + 4, 2).unwrap();
+
+        // Unknown addresses leave every patch in place
+        assert!(execute_command(&mut state, "restore 0x10001"));
+        assert!(execute_command(&mut state, "restore 0x10004"));
+        assert_eq!(
+            process.read_memory_bytes(BASE, 8).unwrap(),
+            vec![0x90, 0x90, 3, 4, 5, 6, 7, 8]
+        );
+        assert!(execute_command(&mut state, "restore all"));
+        assert_eq!(process.read_memory_bytes(BASE, 8).unwrap(), code);
+        assert!(state.scanner.as_ref().unwrap().active_patches().is_empty());
+    }
+}
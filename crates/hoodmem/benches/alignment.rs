@@ -0,0 +1,39 @@
+//! Compares scanning every offset for a `u32` against scanning only 4-aligned offsets
+//!
+//! Run with `cargo bench --features mock`
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use hoodmem::mock::MockProcess;
+use hoodmem::scanner::{ScanFilter, Scanner};
+
+/// Gets a process with 64 regions of 64 KiB, filled with a value which is never scanned for
+fn process_with_many_regions() -> Arc<MockProcess> {
+    Arc::new((0..64u64).fold(MockProcess::new(), |process, i| {
+        process.with_region(0x10000 + i * 0x20000, vec![0xAB; 0x10000])
+    }))
+}
+
+fn alignment(c: &mut Criterion) {
+    let process = process_with_many_regions();
+    let mut group = c.benchmark_group("alignment");
+    for alignment in [1, 4] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(alignment),
+            &alignment,
+            |b, alignment| {
+                b.iter(|| {
+                    let mut scanner = Scanner::new(process.clone());
+                    scanner.set_alignment(*alignment);
+                    scanner.scan(ScanFilter::Exact(0u32)).unwrap();
+                    scanner
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, alignment);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+//! Compares new scans of a process with thousands of small regions, reading regions in parallel and on one thread
+//!
+//! Run with `cargo bench --features mock`
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use hoodmem::mock::MockProcess;
+use hoodmem::scanner::{ScanFilter, Scanner};
+
+/// Gets a process with 4096 regions of 4 KiB, like a process with many small allocations
+fn process_with_many_small_regions() -> Arc<MockProcess> {
+    Arc::new((0..4096u64).fold(MockProcess::new(), |process, i| {
+        process.with_region(0x10000 + i * 0x2000, vec![0xAB; 0x1000])
+    }))
+}
+
+fn scan(process: &Arc<MockProcess>) -> Scanner {
+    let mut scanner = Scanner::new(process.clone());
+    scanner.scan(ScanFilter::Exact(0u32)).unwrap();
+    scanner
+}
+
+fn region_reads(c: &mut Criterion) {
+    let process = process_with_many_small_regions();
+    let single_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("region_reads");
+    group.bench_function("parallel", |b| b.iter(|| scan(&process)));
+    group.bench_function("sequential", |b| {
+        b.iter(|| single_thread.install(|| scan(&process)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, region_reads);
+criterion_main!(benches);
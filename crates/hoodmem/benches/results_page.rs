@@ -0,0 +1,40 @@
+//! Compares getting a page of results with [`Scanner::results_page`] against flattening every result and slicing it
+//!
+//! Run with `cargo bench --features mock`
+
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hoodmem::mock::MockProcess;
+use hoodmem::scanner::{ScanFilter, Scanner};
+
+/// Rows shown at once by a results table
+const PAGE_SIZE: usize = 100;
+
+/// Gets a scanner with 64 regions of 64 KiB, every byte of which is a hit
+fn scanner_with_many_hits() -> Scanner {
+    let process = (0..64u64).fold(MockProcess::new(), |process, i| {
+        process.with_region(0x10000 + i * 0x20000, vec![0; 0x10000])
+    });
+    let mut scanner = Scanner::new(Arc::new(process));
+    scanner.scan(ScanFilter::Exact(0u8)).unwrap();
+    scanner
+}
+
+fn results_page(c: &mut Criterion) {
+    let scanner = scanner_with_many_hits();
+    // Near the end, where flattening has to skip the most results
+    let offset = scanner.count_results().unwrap() - PAGE_SIZE;
+
+    let mut group = c.benchmark_group("results_page");
+    group.bench_function("results_page", |b| {
+        b.iter(|| scanner.results_page::<u8>(black_box(offset), PAGE_SIZE))
+    });
+    group.bench_function("get_results_range", |b| {
+        b.iter(|| scanner.get_results_range::<u8>(black_box(offset), offset + PAGE_SIZE - 1))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, results_page);
+criterion_main!(benches);
@@ -0,0 +1,134 @@
+use crate::*;
+use iced_x86::{Decoder, DecoderOptions, Formatter, IntelFormatter};
+
+/// The longest an x86 instruction can be, in bytes
+const MAX_INSTRUCTION_LEN: usize = 15;
+
+/// A decoded x86-64 instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub operands: String,
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "0x{:016x}: {} {}",
+            self.address, self.mnemonic, self.operands
+        )
+    }
+}
+
+/// Disassembles up to `count` x86-64 instructions starting at `address`
+///
+/// Stops early if the memory runs out or an invalid instruction is found
+pub fn disassemble(process: &dyn Process, address: u64, count: usize) -> Result<Vec<Instruction>> {
+    let memory = process.read_memory_bytes(address, count * MAX_INSTRUCTION_LEN)?;
+    Ok(decode(&memory, address, count))
+}
+
+/// Rounds `len` up to the length of the whole x86-64 instructions starting at `address` which cover it
+///
+/// Fails if an invalid instruction is found first, such as when `address` isn't the start of an instruction
+pub fn whole_instructions_len(process: &dyn Process, address: u64, len: usize) -> Result<usize> {
+    let memory = process.read_memory_bytes(address, len + MAX_INSTRUCTION_LEN)?;
+    let mut covered = 0;
+    for instruction in decode(&memory, address, len) {
+        if covered >= len {
+            break;
+        }
+        covered += instruction.bytes.len();
+    }
+    if covered < len {
+        anyhow::bail!(
+            "Failed to decode {} bytes of instructions at 0x{:016x}",
+            len,
+            address
+        );
+    }
+    Ok(covered)
+}
+
+/// Decodes up to `count` x86-64 instructions from `bytes`, which were read from `address`
+pub fn decode(bytes: &[u8], address: u64, count: usize) -> Vec<Instruction> {
+    let mut decoder = Decoder::with_ip(64, bytes, address, DecoderOptions::NONE);
+    let mut formatter = IntelFormatter::new();
+    let mut instructions = Vec::with_capacity(count);
+    while instructions.len() < count && decoder.can_decode() {
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            break;
+        }
+        let start = (instruction.ip() - address) as usize;
+        let mut mnemonic = String::new();
+        formatter.format_mnemonic(&instruction, &mut mnemonic);
+        let mut operands = String::new();
+        formatter.format_all_operands(&instruction, &mut operands);
+        instructions.push(Instruction {
+            address: instruction.ip(),
+            bytes: bytes[start..start + instruction.len()].to_vec(),
+            mnemonic,
+            operands,
+        });
+    }
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockProcess;
+
+    const BASE: u64 = 0x401000;
+
+    /// `mov rax, rcx`, `nop`, `ret`
+    const CODE: [u8; 5] = [0x48, 0x89, 0xc8, 0x90, 0xc3];
+
+    #[test]
+    fn disassemble_reads_instructions_from_process() {
+        let process = MockProcess::new().with_region(BASE, CODE.to_vec());
+        let instructions = disassemble(&process, BASE, 2).unwrap();
+        assert_eq!(
+            instructions,
+            vec![
+                Instruction {
+                    address: BASE,
+                    bytes: vec![0x48, 0x89, 0xc8],
+                    mnemonic: "mov".into(),
+                    operands: "rax,rcx".into(),
+                },
+                Instruction {
+                    address: BASE + 3,
+                    bytes: vec![0x90],
+                    mnemonic: "nop".into(),
+                    operands: "".into(),
+                },
+            ]
+        );
+
+        // The read stops at the end of the region, so only the instructions in it are decoded
+        let instructions = disassemble(&process, BASE, 10).unwrap();
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[2].mnemonic, "ret");
+        assert!(disassemble(&process, BASE + 0x1000, 1).is_err());
+    }
+
+    #[test]
+    fn decode_stops_at_invalid_instruction() {
+        // 0x06 (push es) isn't valid in 64-bit mode
+        let instructions = decode(&[0x90, 0x06, 0x90], BASE, 3);
+        assert_eq!(instructions.len(), 1);
+    }
+
+    #[test]
+    fn whole_instructions_len_covers_partial_instructions() {
+        let process = MockProcess::new().with_region(BASE, CODE.to_vec());
+        assert_eq!(whole_instructions_len(&process, BASE, 2).unwrap(), 3);
+        assert_eq!(whole_instructions_len(&process, BASE, 4).unwrap(), 4);
+        assert!(whole_instructions_len(&process, BASE, 6).is_err());
+    }
+}
@@ -1,9 +1,14 @@
+pub mod disasm;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 mod platforms;
 pub mod scanner;
+pub mod snapshot;
 pub mod util;
 
 pub use anyhow::Result;
 pub use std::ffi::{c_void, CString};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 #[cfg(target_os = "windows")]
@@ -12,33 +17,145 @@ pub use crate::platforms::windows::*;
 #[cfg(target_os = "linux")]
 pub use crate::platforms::linux::*;
 
+#[cfg(target_os = "macos")]
+pub use crate::platforms::macos::*;
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+pub use crate::platforms::linux_watch::*;
+
 pub trait MemoryRead {
+    /// Reads up to `bytes_to_read` bytes from `address`
+    ///
+    /// The returned buffer may be shorter than requested if only part of the memory could be read
     fn read_memory_bytes(&self, address: u64, bytes_to_read: usize) -> Result<Vec<u8>>;
+
+    /// Reads like [`MemoryRead::read_memory_bytes`], also returning the ranges of the buffer which couldn't be read
+    ///
+    /// Those ranges are zero filled rather than cutting the read short, so they must not be taken for real values.
+    /// Only platforms which zero fill unreadable pages have any
+    fn read_memory_with_gaps(
+        &self,
+        address: u64,
+        bytes_to_read: usize,
+    ) -> Result<(Vec<u8>, Vec<std::ops::Range<usize>>)> {
+        Ok((self.read_memory_bytes(address, bytes_to_read)?, vec![]))
+    }
 }
 
 pub trait GenericMemoryRead<T: Copy> {
     fn read_memory(&self, address: u64) -> Result<T>;
 }
 
-impl<T: MemoryRead, U: Copy> GenericMemoryRead<U> for T {
+impl<T: MemoryRead + ?Sized, U: Copy> GenericMemoryRead<U> for T {
     fn read_memory(&self, address: u64) -> Result<U> {
         let result: Vec<u8> = self.read_memory_bytes(address, std::mem::size_of::<U>())?;
-        unsafe {
-            Ok(*std::mem::transmute::<*const Vec<u8>, *const U>(
-                &result as *const Vec<u8>,
-            ))
+        if result.len() < std::mem::size_of::<U>() {
+            return Err(anyhow::anyhow!(
+                "Expected {} bytes at 0x{:016x}, but only read {}",
+                std::mem::size_of::<U>(),
+                address,
+                result.len()
+            ));
         }
+        Ok(util::read_from_buffer::<U>(&result, 0))
+    }
+}
+
+pub trait MemoryWrite {
+    fn write_memory_bytes(&self, address: u64, bytes: &[u8]) -> Result<()>;
+}
+
+pub trait GenericMemoryWrite<T: Copy> {
+    fn write_memory(&self, address: u64, value: T) -> Result<()>;
+}
+
+impl<T: MemoryWrite + ?Sized, U: Copy> GenericMemoryWrite<U> for T {
+    fn write_memory(&self, address: u64, value: U) -> Result<()> {
+        let bytes = unsafe {
+            std::slice::from_raw_parts(&value as *const U as *const u8, std::mem::size_of::<U>())
+        };
+        self.write_memory_bytes(address, bytes)
+    }
+}
+
+bitflags::bitflags! {
+    /// Access allowed to a region of memory
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+    pub struct Protection: u8 {
+        const READ = 0b001;
+        const WRITE = 0b010;
+        const EXECUTE = 0b100;
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl std::fmt::Display for Protection {
+    /// Formats like the permissions in `/proc/<pid>/maps`, such as `r-x`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (flag, symbol) in [
+            (Protection::READ, 'r'),
+            (Protection::WRITE, 'w'),
+            (Protection::EXECUTE, 'x'),
+        ] {
+            write!(f, "{}", if self.contains(flag) { symbol } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
+/// A region of memory. Regions are ordered by address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct MemoryRegion {
     pub base_address: u64,
     pub size: u64,
+    /// Empty if unknown, such as for manually added addresses
+    #[serde(default)]
+    pub protection: Protection,
+}
+
+/// An executable or shared library mapped into a process
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Module {
+    pub name: String,
+    pub base_address: u64,
+    pub size: u64,
+}
+
+impl Module {
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.base_address && address - self.base_address < self.size
+    }
+}
+
+/// Finds the module `address` falls inside, if any
+pub fn find_module(modules: &[Module], address: u64) -> Option<&Module> {
+    modules.iter().find(|module| module.contains(address))
+}
+
+/// Gets the address `offset` bytes into the module named `module`, such as an address from a cheat table
+pub fn resolve_module_offset(process: &dyn Process, module: &str, offset: u64) -> Result<u64> {
+    process
+        .get_modules()
+        .iter()
+        .find(|loaded| loaded.name == module)
+        .map(|loaded| loaded.base_address + offset)
+        .ok_or_else(|| anyhow::anyhow!("Module '{}' is not loaded", module))
 }
 
 pub trait ScannableMemoryRegions {
     fn get_writable_regions(&self) -> Vec<MemoryRegion>;
+    /// Gets every readable region, including read-only and executable ones such as code and constants
+    ///
+    /// Defaults to the writable regions, for platforms which can't tell them apart
+    fn get_readable_regions(&self) -> Vec<MemoryRegion> {
+        self.get_writable_regions()
+    }
+    /// Gets the modules mapped into the process, named by file name
+    fn get_modules(&self) -> Vec<Module>;
+}
+
+pub trait ProcessStatus {
+    /// Checks whether the process is still running
+    fn is_alive(&self) -> bool;
 }
 
 pub fn attach_external(pid: u32) -> Result<Arc<dyn Process>> {
@@ -46,15 +163,96 @@ pub fn attach_external(pid: u32) -> Result<Arc<dyn Process>> {
     return Ok(Arc::new(WinProcess::attach(pid)?));
 
     #[cfg(target_os = "linux")]
-    return Ok(Arc::new(LinuxProcess::attach(pid)))
+    {
+        let process = LinuxProcess::attach(pid);
+        if !process.is_alive() {
+            return Err(anyhow::anyhow!("No process with PID {} is running", pid));
+        }
+        return Ok(Arc::new(process));
+    }
+
+    #[cfg(target_os = "macos")]
+    return Ok(Arc::new(MacProcess::attach(pid)?));
 }
 
 pub fn attach_external_by_name(name: &str) -> Result<Arc<dyn Process>> {
     #[cfg(target_os = "windows")]
     return Ok(Arc::new(WinProcess::attach_by_name(name)?));
     #[cfg(target_os = "linux")]
-    return Ok(Arc::new(LinuxProcess::attach_by_proc_name(name)))
+    return Ok(Arc::new(LinuxProcess::attach_by_proc_name(name)?));
+    #[cfg(target_os = "macos")]
+    return Err(anyhow::anyhow!(
+        "Attaching by name ({}) is not supported on macOS",
+        name
+    ));
+}
+
+/// Attaches to the process running the executable at `path`. Only supported on Linux
+pub fn attach_external_by_exe_path(path: &str) -> Result<Arc<dyn Process>> {
+    #[cfg(target_os = "windows")]
+    return Err(anyhow::anyhow!(
+        "Attaching by executable path ({}) is not supported on Windows",
+        path
+    ));
+    #[cfg(target_os = "linux")]
+    return Ok(Arc::new(LinuxProcess::attach_by_exe_path(path)?));
+    #[cfg(target_os = "macos")]
+    return Err(anyhow::anyhow!(
+        "Attaching by executable path ({}) is not supported on macOS",
+        path
+    ));
+}
+
+pub trait Process:
+    MemoryRead + MemoryWrite + ScannableMemoryRegions + ProcessStatus + 'static + Send + Sync
+{
+}
+impl<T: MemoryRead + MemoryWrite + ScannableMemoryRegions + ProcessStatus + 'static + Send + Sync>
+    Process for T
+{
 }
 
-pub trait Process: MemoryRead + ScannableMemoryRegions + 'static + Send + Sync {}
-impl<T: MemoryRead + ScannableMemoryRegions + 'static + Send + Sync> Process for T {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockProcess;
+
+    #[test]
+    fn read_memory_reads_target_bytes_unaligned() {
+        let process = MockProcess::new().with_region(0x1000, vec![0, 0x78, 0x56, 0x34, 0x12, 0]);
+        let value: u32 = process.read_memory(0x1001).unwrap();
+        assert_eq!(value, 0x12345678);
+    }
+
+    #[test]
+    fn read_memory_fails_on_short_read() {
+        let process = MockProcess::new().with_region(0x1000, vec![0x78, 0x56]);
+        assert!(GenericMemoryRead::<u32>::read_memory(&process, 0x1000).is_err());
+    }
+
+    #[test]
+    fn resolve_module_offset_adds_offset_to_module_base() {
+        let process = MockProcess::new()
+            .with_module("game.exe", 0x400000, 0x10000)
+            .with_module("engine.dll", 0x7ff0000, 0x20000);
+        assert_eq!(resolve_module_offset(&process, "engine.dll", 0x1234).unwrap(), 0x7ff1234);
+        assert_eq!(resolve_module_offset(&process, "game.exe", 0).unwrap(), 0x400000);
+    }
+
+    #[test]
+    fn resolve_module_offset_fails_for_unknown_module() {
+        let process = MockProcess::new().with_module("game.exe", 0x400000, 0x10000);
+        let err = resolve_module_offset(&process, "missing.dll", 0x10).unwrap_err();
+        assert!(err.to_string().contains("missing.dll"));
+        // Names have to match exactly
+        assert!(resolve_module_offset(&process, "game", 0x10).is_err());
+    }
+
+    #[test]
+    fn protection_formats_like_proc_maps() {
+        assert_eq!(Protection::empty().to_string(), "---");
+        assert_eq!((Protection::READ | Protection::EXECUTE).to_string(), "r-x");
+        assert_eq!((Protection::READ | Protection::WRITE).to_string(), "rw-");
+        assert_eq!(Protection::all().to_string(), "rwx");
+    }
+}
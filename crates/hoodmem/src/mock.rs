@@ -0,0 +1,211 @@
+//! An in-memory [`Process`] for testing scanners and tools without attaching to a real process
+//!
+//! Only built for tests, or with the `mock` feature
+
+use crate::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// A fake process whose memory is a set of byte buffers
+///
+/// Reads behave like they do on Linux: a read stops early at the end of a region or at an unreadable range,
+/// and fails if nothing at all could be read
+#[derive(Default)]
+pub struct MockProcess {
+    /// Each region and its bytes, in address order
+    memory: Mutex<Vec<(MemoryRegion, Vec<u8>)>>,
+    modules: Vec<Module>,
+    /// `[start, end)` ranges which fail to read, such as guard pages
+    unreadable: Mutex<Vec<(u64, u64)>>,
+    /// `[start, end)` ranges which read as zeros, like pages Windows fails to read partway through a read
+    zero_filled: Mutex<Vec<(u64, u64)>>,
+    dead: AtomicBool,
+}
+
+impl MockProcess {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a readable and writable region holding `bytes` at `base_address`
+    pub fn with_region(self, base_address: u64, bytes: Vec<u8>) -> Self {
+        self.with_protected_region(base_address, bytes, Protection::READ | Protection::WRITE)
+    }
+
+    /// Adds a region holding `bytes` at `base_address` with the given protection
+    ///
+    /// Regions without [`Protection::WRITE`] can still be written, like a debugger can, but aren't scanned
+    pub fn with_protected_region(
+        self,
+        base_address: u64,
+        bytes: Vec<u8>,
+        protection: Protection,
+    ) -> Self {
+        {
+            let mut memory = self.memory.lock().unwrap();
+            let region = MemoryRegion {
+                base_address,
+                size: bytes.len() as u64,
+                protection,
+            };
+            memory.push((region, bytes));
+            memory.sort_by_key(|(region, _)| *region);
+        }
+        self
+    }
+
+    /// Adds a module named `name` covering `[base_address, base_address + size)`
+    pub fn with_module(mut self, name: &str, base_address: u64, size: u64) -> Self {
+        self.modules.push(Module {
+            name: name.to_string(),
+            base_address,
+            size,
+        });
+        self
+    }
+
+    /// Makes reads of `[address, address + len)` fail, as if the pages were decommitted
+    pub fn with_unreadable(self, address: u64, len: u64) -> Self {
+        self.set_unreadable(address, len);
+        self
+    }
+
+    /// Makes reads of `[address, address + len)` fail from now on
+    pub fn set_unreadable(&self, address: u64, len: u64) {
+        self.unreadable
+            .lock()
+            .unwrap()
+            .push((address, address + len));
+    }
+
+    /// Makes `[address, address + len)` read as zeros without failing the read, and be reported as a gap
+    pub fn with_zero_filled(self, address: u64, len: u64) -> Self {
+        self.zero_filled
+            .lock()
+            .unwrap()
+            .push((address, address + len));
+        self
+    }
+
+    /// Overwrites memory at `address`, like another thread of the process would. Panics if it isn't mapped
+    pub fn set_bytes(&self, address: u64, bytes: &[u8]) {
+        self.write_memory_bytes(address, bytes)
+            .expect("mock memory should be mapped");
+    }
+
+    /// Overwrites the value at `address`, like another thread of the process would
+    pub fn set_value<T: Copy>(&self, address: u64, value: T) {
+        self.write_memory(address, value)
+            .expect("mock memory should be mapped");
+    }
+
+    /// Makes [`ProcessStatus::is_alive`] return `alive`
+    pub fn set_alive(&self, alive: bool) {
+        self.dead.store(!alive, Ordering::Relaxed);
+    }
+
+    /// Gets the region containing `address`, and the offset of `address` into it
+    fn locate<'a>(
+        memory: &'a mut [(MemoryRegion, Vec<u8>)],
+        address: u64,
+    ) -> Option<(usize, &'a mut Vec<u8>)> {
+        memory
+            .iter_mut()
+            .find(|(region, _)| {
+                address >= region.base_address && address - region.base_address < region.size
+            })
+            .map(|(region, bytes)| ((address - region.base_address) as usize, bytes))
+    }
+}
+
+impl MockProcess {
+    /// Reads memory as it is stored, without zero filling
+    fn read_stored_bytes(&self, address: u64, bytes_to_read: usize) -> Result<Vec<u8>> {
+        let mut memory = self.memory.lock().unwrap();
+        let Some((offset, bytes)) = Self::locate(&mut memory, address) else {
+            anyhow::bail!("0x{:016x} is not mapped", address);
+        };
+        let mut end = (offset + bytes_to_read).min(bytes.len());
+        for (start, stop) in self.unreadable.lock().unwrap().iter() {
+            if (*start..*stop).contains(&address) {
+                anyhow::bail!("0x{:016x} is not readable", address);
+            }
+            if *start > address && *start - address < (end - offset) as u64 {
+                end = offset + (*start - address) as usize;
+            }
+        }
+        Ok(bytes[offset..end].to_vec())
+    }
+}
+
+impl MemoryRead for MockProcess {
+    fn read_memory_bytes(&self, address: u64, bytes_to_read: usize) -> Result<Vec<u8>> {
+        Ok(self.read_memory_with_gaps(address, bytes_to_read)?.0)
+    }
+
+    fn read_memory_with_gaps(
+        &self,
+        address: u64,
+        bytes_to_read: usize,
+    ) -> Result<(Vec<u8>, Vec<std::ops::Range<usize>>)> {
+        let mut buffer = self.read_stored_bytes(address, bytes_to_read)?;
+        let read_end = address + buffer.len() as u64;
+        let mut gaps = vec![];
+        for (start, stop) in self.zero_filled.lock().unwrap().iter() {
+            if *start < read_end && *stop > address {
+                let gap = ((*start).max(address) - address) as usize
+                    ..((*stop).min(read_end) - address) as usize;
+                buffer[gap.clone()].fill(0);
+                gaps.push(gap);
+            }
+        }
+        gaps.sort_by_key(|gap| gap.start);
+        Ok((buffer, gaps))
+    }
+}
+
+impl MemoryWrite for MockProcess {
+    fn write_memory_bytes(&self, address: u64, bytes: &[u8]) -> Result<()> {
+        let mut memory = self.memory.lock().unwrap();
+        let Some((offset, region_bytes)) = Self::locate(&mut memory, address) else {
+            anyhow::bail!("0x{:016x} is not mapped", address);
+        };
+        if offset + bytes.len() > region_bytes.len() {
+            anyhow::bail!(
+                "Writing {} bytes at 0x{:016x} runs past the end of its region",
+                bytes.len(),
+                address
+            );
+        }
+        region_bytes[offset..offset + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl ScannableMemoryRegions for MockProcess {
+    fn get_writable_regions(&self) -> Vec<MemoryRegion> {
+        self.get_readable_regions()
+            .into_iter()
+            .filter(|region| region.protection.contains(Protection::WRITE))
+            .collect()
+    }
+
+    fn get_readable_regions(&self) -> Vec<MemoryRegion> {
+        self.memory
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(region, _)| *region)
+            .collect()
+    }
+
+    fn get_modules(&self) -> Vec<Module> {
+        self.modules.clone()
+    }
+}
+
+impl ProcessStatus for MockProcess {
+    fn is_alive(&self) -> bool {
+        !self.dead.load(Ordering::Relaxed)
+    }
+}
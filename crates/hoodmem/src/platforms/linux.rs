@@ -1,16 +1,25 @@
-use std::io::IoSliceMut;
+use std::collections::BTreeMap;
+use std::io::{IoSlice, IoSliceMut};
+use std::path::{Path, PathBuf};
 
-use crate::{MemoryRead, MemoryRegion, ScannableMemoryRegions};
+use crate::{
+    util::merge_adjacent_regions, MemoryRead, MemoryRegion, MemoryWrite, Module, ProcessStatus,
+    Protection, ScannableMemoryRegions,
+};
 use anyhow::{anyhow, Result};
 use nix::{
-    sys::uio::{process_vm_readv, RemoteIoVec},
+    errno::Errno,
+    sys::uio::{process_vm_readv, process_vm_writev, RemoteIoVec},
     unistd::Pid,
 };
-use proc_maps::get_process_maps;
+use proc_maps::{get_process_maps, MapRange};
+
+/// Contiguous writable maps are merged into regions of at most this many bytes
+const MAX_MERGED_REGION_SIZE: u64 = 256 * 1024 * 1024;
 
 #[derive(Debug, Clone, Copy)]
 pub struct LinuxProcess {
-    pid: Pid,
+    pub(crate) pid: Pid,
 }
 
 impl LinuxProcess {
@@ -20,8 +29,84 @@ impl LinuxProcess {
         }
     }
 
-    pub fn attach_by_proc_name(name: &str) -> Self {
-        unimplemented!()
+    /// Attach to the process whose name matches `name`
+    ///
+    /// `name` is matched against `/proc/<pid>/comm`, the executable name in `/proc/<pid>/cmdline`, and the full command line.
+    /// Fails if no process matches, or if more than one process matches
+    pub fn attach_by_proc_name(name: &str) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Process name cannot be empty"));
+        }
+        let mut matching_pids: Vec<u32> = std::fs::read_dir("/proc")?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter(|pid| process_name_matches(*pid, name))
+            .collect();
+        matching_pids.sort();
+        match matching_pids.as_slice() {
+            [] => Err(anyhow!("No process named '{}' found", name)),
+            [pid] => Ok(Self::attach(*pid)),
+            pids => Err(anyhow!(
+                "Multiple processes named '{}' found (pids: {:?}). Attach by PID instead",
+                name,
+                pids
+            )),
+        }
+    }
+
+    /// Attach to the process running the executable at `path`
+    ///
+    /// `path` is compared with the `/proc/<pid>/exe` symlink of every process, after resolving any symlinks in it.
+    /// Fails if no process matches, or if more than one process matches
+    pub fn attach_by_exe_path(path: &str) -> Result<Self> {
+        let path = path.trim();
+        if path.is_empty() {
+            return Err(anyhow!("Executable path cannot be empty"));
+        }
+        let exe_path = std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+        let mut matching_pids: Vec<u32> = std::fs::read_dir("/proc")?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter(|pid| {
+                // Processes owned by other users can't be read without permission, so never match
+                std::fs::read_link(format!("/proc/{}/exe", pid))
+                    .is_ok_and(|pid_exe| pid_exe == exe_path)
+            })
+            .collect();
+        matching_pids.sort();
+        match matching_pids.as_slice() {
+            [] => Err(anyhow!("No process running '{}' found", exe_path.display())),
+            [pid] => Ok(Self::attach(*pid)),
+            pids => Err(anyhow!(
+                "Multiple processes running '{}' found (pids: {:?}). Attach by PID instead",
+                exe_path.display(),
+                pids
+            )),
+        }
+    }
+}
+
+/// Checks whether the process with the given pid has the given name
+fn process_name_matches(pid: u32, name: &str) -> bool {
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default();
+    if comm.trim_end() == name {
+        return true;
+    }
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+    let args: Vec<String> = cmdline
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    match args.first() {
+        Some(exe) => {
+            let exe_name = std::path::Path::new(exe)
+                .file_name()
+                .map(|exe_name| exe_name.to_string_lossy().into_owned());
+            exe == name || exe_name.as_deref() == Some(name) || args.join(" ") == name
+        }
+        None => false,
     }
 }
 
@@ -36,34 +121,237 @@ impl MemoryRead for LinuxProcess {
             base: address as usize,
             len: bytes_to_read,
         }];
-        let bytes_read = process_vm_readv(self.pid, &mut local_iov, &remote_iov)?;
-        if bytes_read != bytes_to_read {
+        let bytes_read = match process_vm_readv(self.pid, &mut local_iov, &remote_iov) {
+            Ok(bytes_read) => bytes_read,
+            Err(Errno::ESRCH) => {
+                return Err(anyhow!("Process (pid={}) no longer exists", self.pid));
+            }
+            Err(Errno::EFAULT) => {
+                return Err(anyhow!(
+                    "Memory at 0x{:016x} is not mapped in process (pid={})",
+                    address,
+                    self.pid
+                ));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        if bytes_read == 0 && bytes_to_read > 0 {
+            return Err(anyhow!(
+                "Failed to read any bytes at 0x{:016x} from process (pid={})",
+                address,
+                self.pid
+            ));
+        }
+        // Part of the memory may have been unmapped since it was enumerated. Keep whatever could be read
+        buffer.truncate(bytes_read);
+
+        Ok(buffer)
+    }
+}
+
+impl MemoryWrite for LinuxProcess {
+    fn write_memory_bytes(&self, address: u64, bytes: &[u8]) -> Result<()> {
+        let local_iov = [IoSlice::new(bytes)];
+        let remote_iov = [RemoteIoVec {
+            base: address as usize,
+            len: bytes.len(),
+        }];
+        let bytes_written = process_vm_writev(self.pid, &local_iov, &remote_iov)?;
+        if bytes_written != bytes.len() {
             return Err(anyhow!(
-                "Failed to read {} bytes from process (pid={}). Only {} bytes read",
-                bytes_to_read,
+                "Failed to write {} bytes to process (pid={}). Only {} bytes written",
+                bytes.len(),
                 self.pid,
-                bytes_read
+                bytes_written
             ));
         }
 
-        Ok(buffer)
+        Ok(())
     }
 }
 
+impl LinuxProcess {
+    /// Gets the maps of the process which have all of the `protection` flags, merged into regions
+    fn get_regions(&self, protection: Protection) -> Vec<MemoryRegion> {
+        let maps = get_process_maps(self.pid.into()).unwrap_or_default();
+        regions_from_maps(maps.iter().map(map_protection), protection)
+    }
+}
+
+/// Gets the start, size and protection of a map
+fn map_protection(map: &MapRange) -> (u64, u64, Protection) {
+    let mut protection = Protection::empty();
+    protection.set(Protection::READ, map.is_read());
+    protection.set(Protection::WRITE, map.is_write());
+    protection.set(Protection::EXECUTE, map.is_exec());
+    (map.start() as u64, map.size() as u64, protection)
+}
+
+/// Gets the regions of the maps, given as `(start, size, protection)`, which have all of the `protection` flags
+///
+/// Adjacent maps with the same protection are merged
+fn regions_from_maps(
+    maps: impl IntoIterator<Item = (u64, u64, Protection)>,
+    protection: Protection,
+) -> Vec<MemoryRegion> {
+    let regions = maps
+        .into_iter()
+        .filter(|(_, _, map_protection)| map_protection.contains(protection))
+        .map(|(base_address, size, protection)| MemoryRegion {
+            base_address,
+            size,
+            protection,
+        })
+        .collect();
+    merge_adjacent_regions(regions, MAX_MERGED_REGION_SIZE)
+}
+
 impl ScannableMemoryRegions for LinuxProcess {
     fn get_writable_regions(&self) -> Vec<MemoryRegion> {
-        let mut regions = Vec::new();
-        if let Ok(maps) = get_process_maps(self.pid.into()) {
-            for map in maps {
-                if map.is_write() && map.is_read() {
-                    regions.push(MemoryRegion {
-                        base_address: map.start() as u64,
-                        size: map.size() as u64,
-                    })
-                }
-            }
+        self.get_regions(Protection::READ | Protection::WRITE)
+    }
+
+    fn get_readable_regions(&self) -> Vec<MemoryRegion> {
+        self.get_regions(Protection::READ)
+    }
+
+    fn get_modules(&self) -> Vec<Module> {
+        let maps = get_process_maps(self.pid.into()).unwrap_or_default();
+        modules_from_maps(
+            maps.iter()
+                .filter_map(|map| Some((map.filename()?, map.start() as u64, map.size() as u64))),
+        )
+    }
+}
+
+/// Gets the modules mapped by file backed maps, given as `(path, start, size)`
+///
+/// A module is mapped as several maps of the same file, so it spans from the first to the last of them
+fn modules_from_maps<'a>(maps: impl IntoIterator<Item = (&'a Path, u64, u64)>) -> Vec<Module> {
+    let mut spans: BTreeMap<PathBuf, (u64, u64)> = BTreeMap::new();
+    for (path, start, size) in maps {
+        let end = start + size;
+        let span = spans.entry(path.to_path_buf()).or_insert((start, end));
+        span.0 = span.0.min(start);
+        span.1 = span.1.max(end);
+    }
+
+    let mut modules: Vec<Module> = spans
+        .into_iter()
+        .map(|(path, (start, end))| Module {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string_lossy().into_owned()),
+            base_address: start,
+            size: end - start,
+        })
+        .collect();
+    modules.sort_by_key(|module| module.base_address);
+    modules
+}
+
+impl ProcessStatus for LinuxProcess {
+    fn is_alive(&self) -> bool {
+        // Zombies keep their /proc entry until they are reaped, but have no memory left to read
+        match std::fs::read_to_string(format!("/proc/{}/stat", self.pid)) {
+            Ok(stat) => match stat.rsplit_once(')') {
+                Some((_, fields)) => !fields.trim_start().starts_with(['Z', 'X']),
+                None => true,
+            },
+            Err(_) => false,
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_module;
+
+    /// Lives in the test executable's data, so it's inside the executable's module
+    static KNOWN: u32 = 0x600d_f00d;
+
+    #[test]
+    fn get_modules_names_own_executable() {
+        let process = LinuxProcess::attach(std::process::id());
+        let address = &KNOWN as *const u32 as u64;
+        let exe = std::env::current_exe().unwrap();
+        let exe_name = exe.file_name().unwrap().to_string_lossy();
+
+        let modules = process.get_modules();
+        let module = find_module(&modules, address).expect("KNOWN should be in a module");
+        assert_eq!(module.name, exe_name);
+        assert!(modules
+            .windows(2)
+            .all(|pair| pair[0].base_address <= pair[1].base_address));
+    }
+
+    #[test]
+    fn modules_span_every_map_of_their_file() {
+        let game = Path::new("/opt/game/game");
+        let libc = Path::new("/usr/lib/libc.so.6");
+        let modules = modules_from_maps([
+            (libc, 0x7f00_0000_2000, 0x1000),
+            (game, 0x5000_1000, 0x2000),
+            (libc, 0x7f00_0000_0000, 0x1000),
+            (game, 0x5000_0000, 0x1000),
+            (libc, 0x7f00_0000_4000, 0x3000),
+        ]);
+        assert_eq!(
+            modules,
+            vec![
+                Module {
+                    name: "game".to_string(),
+                    base_address: 0x5000_0000,
+                    size: 0x3000,
+                },
+                Module {
+                    name: "libc.so.6".to_string(),
+                    base_address: 0x7f00_0000_0000,
+                    size: 0x7000,
+                },
+            ]
+        );
+        assert!(modules_from_maps([]).is_empty());
+    }
+
+    #[test]
+    fn attach_by_exe_path_finds_own_process() {
+        let exe = std::env::current_exe().unwrap();
+        let process = LinuxProcess::attach_by_exe_path(exe.to_str().unwrap()).unwrap();
+        assert_eq!(process.pid.as_raw() as u32, std::process::id());
+    }
+
+    #[test]
+    fn read_only_maps_are_only_readable() {
+        let rw = Protection::READ | Protection::WRITE;
+        let rx = Protection::READ | Protection::EXECUTE;
+        let maps = [
+            (0x1000, 0x1000, rx),
+            (0x2000, 0x1000, Protection::READ),
+            (0x3000, 0x2000, rw),
+            (0x5000, 0x1000, rw),
+            (0x6000, 0x1000, Protection::empty()),
+            (0x8000, 0x1000, Protection::WRITE),
+        ];
+        let region = |base_address, size, protection| MemoryRegion {
+            base_address,
+            size,
+            protection,
+        };
 
-        regions
+        assert_eq!(
+            regions_from_maps(maps, Protection::READ | Protection::WRITE),
+            vec![region(0x3000, 0x3000, rw)]
+        );
+        assert_eq!(
+            regions_from_maps(maps, Protection::READ),
+            vec![
+                region(0x1000, 0x1000, rx),
+                region(0x2000, 0x1000, Protection::READ),
+                region(0x3000, 0x3000, rw),
+            ]
+        );
     }
 }
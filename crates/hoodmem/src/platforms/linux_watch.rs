@@ -0,0 +1,308 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::LinuxProcess;
+use anyhow::{anyhow, Result};
+use nix::{
+    errno::Errno,
+    libc,
+    sys::{
+        ptrace,
+        wait::{waitpid, WaitPidFlag, WaitStatus},
+    },
+    unistd::Pid,
+};
+
+/// Offset of `u_debugreg` within `struct user` on x86-64
+const DEBUG_REGISTERS_OFFSET: usize = 848;
+
+/// How long the watch thread sleeps when no watched thread is stopped
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Kind of memory access which triggers a watch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Write,
+    ReadWrite,
+    Execute,
+}
+
+/// A hardware breakpoint set with [`LinuxProcess::watch_address`]
+///
+/// The breakpoint is removed and the process detached from when this is stopped or dropped
+pub struct WatchHandle {
+    hits: Arc<Mutex<Vec<u64>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl WatchHandle {
+    /// Gets the instruction pointer of every access to the watched address so far
+    ///
+    /// `Write` and `ReadWrite` watches trap after the access, so their hits point at the instruction after the one which accessed the address
+    pub fn hits(&self) -> Vec<u64> {
+        self.hits
+            .lock()
+            .map(|hits| hits.clone())
+            .unwrap_or_default()
+    }
+
+    /// Removes the breakpoint and detaches from the process
+    pub fn stop(mut self) -> Result<()> {
+        self.stop_thread()
+    }
+
+    fn stop_thread(&mut self) -> Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .map_err(|_| anyhow!("Watch thread panicked"))?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        let _ = self.stop_thread();
+    }
+}
+
+impl LinuxProcess {
+    /// Sets a hardware breakpoint on the byte at `address`, recording which instructions access it
+    ///
+    /// This uses ptrace, so needs permission to trace the process. That usually means running as root,
+    /// having `CAP_SYS_PTRACE`, or setting `/proc/sys/kernel/yama/ptrace_scope` to 0.
+    /// The process can't already be traced by a debugger. Only threads which exist when the watch is set are watched
+    pub fn watch_address(&self, address: u64, kind: WatchKind) -> Result<WatchHandle> {
+        let hits = Arc::new(Mutex::new(vec![]));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let pid = self.pid;
+        let thread = {
+            let hits = hits.clone();
+            let stop = stop.clone();
+            // Every ptrace request has to come from the same thread, so the watch gets its own
+            std::thread::spawn(move || watch_threads(pid, address, kind, &hits, &stop, ready_tx))
+        };
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(WatchHandle {
+                hits,
+                stop,
+                thread: Some(thread),
+            }),
+            Ok(Err(err)) => {
+                let _ = thread.join();
+                Err(err)
+            }
+            Err(_) => Err(anyhow!("Watch thread exited before setting the breakpoint")),
+        }
+    }
+}
+
+/// Traces every thread of `pid`, setting the breakpoint in each and recording hits until `stop` is set
+///
+/// Whether the breakpoint was set is sent to `ready`
+fn watch_threads(
+    pid: Pid,
+    address: u64,
+    kind: WatchKind,
+    hits: &Mutex<Vec<u64>>,
+    stop: &AtomicBool,
+    ready: Sender<Result<()>>,
+) -> Result<()> {
+    let mut traced: Vec<Pid> = vec![];
+    let setup = (|| -> Result<()> {
+        for tid in thread_ids(pid)? {
+            ptrace::seize(tid, ptrace::Options::empty())?;
+            traced.push(tid);
+            ptrace::interrupt(tid)?;
+            wait_for_stop(tid)?;
+            set_breakpoint(tid, address, kind)?;
+            ptrace::cont(tid, None)?;
+        }
+        Ok(())
+    })();
+    if let Err(err) = setup {
+        detach_all(&traced);
+        let _ = ready.send(Err(err));
+        return Ok(());
+    }
+    let _ = ready.send(Ok(()));
+
+    let mut result = Ok(());
+    'watch: while !stop.load(Ordering::Relaxed) && !traced.is_empty() {
+        let mut any_stopped = false;
+        // Only our own tracees are waited for, so other children of this process aren't reaped
+        for tid in traced.clone() {
+            match waitpid(tid, Some(WaitPidFlag::__WALL | WaitPidFlag::WNOHANG)) {
+                Ok(WaitStatus::StillAlive) => {}
+                Ok(WaitStatus::Stopped(_, signal)) => {
+                    any_stopped = true;
+                    if signal == nix::sys::signal::Signal::SIGTRAP && take_breakpoint_hit(tid) {
+                        if let Ok(regs) = ptrace::getregs(tid) {
+                            if let Ok(mut hits) = hits.lock() {
+                                hits.push(regs.rip);
+                            }
+                        }
+                        let _ = ptrace::cont(tid, None);
+                    } else {
+                        // Not ours, pass the signal on
+                        let _ = ptrace::cont(tid, signal);
+                    }
+                }
+                Ok(WaitStatus::PtraceEvent(_, _, _)) => {
+                    any_stopped = true;
+                    let _ = ptrace::cont(tid, None);
+                }
+                // A thread which is no longer our tracee can't be waited for either
+                Ok(WaitStatus::Exited(_, _))
+                | Ok(WaitStatus::Signaled(_, _, _))
+                | Err(Errno::ECHILD) => {
+                    traced.retain(|traced_tid| *traced_tid != tid);
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    result = Err(err.into());
+                    break 'watch;
+                }
+            }
+        }
+        if !any_stopped {
+            std::thread::sleep(WATCH_POLL_INTERVAL);
+        }
+    }
+    detach_all(&traced);
+    result
+}
+
+/// Gets the ids of every thread in the process
+fn thread_ids(pid: Pid) -> Result<Vec<Pid>> {
+    Ok(std::fs::read_dir(format!("/proc/{}/task", pid))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<i32>().ok())
+        .map(Pid::from_raw)
+        .collect())
+}
+
+/// Waits for a traced thread to stop
+fn wait_for_stop(tid: Pid) -> Result<()> {
+    loop {
+        match waitpid(tid, Some(WaitPidFlag::__WALL))? {
+            WaitStatus::Stopped(_, _) | WaitStatus::PtraceEvent(_, _, _) => return Ok(()),
+            WaitStatus::Exited(_, _) | WaitStatus::Signaled(_, _, _) => {
+                return Err(anyhow!("Thread {} exited", tid))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Removes the breakpoint from each thread and stops tracing it. Threads which have exited are skipped
+fn detach_all(traced: &[Pid]) {
+    for tid in traced {
+        if ptrace::interrupt(*tid).is_ok() && wait_for_stop(*tid).is_ok() {
+            let _ = write_debug_register(*tid, 7, 0);
+            let _ = ptrace::detach(*tid, None);
+        }
+    }
+}
+
+/// Sets debug register 0 to break on `kind` accesses to the byte at `address`
+fn set_breakpoint(tid: Pid, address: u64, kind: WatchKind) -> Result<()> {
+    let access_bits: u64 = match kind {
+        WatchKind::Execute => 0b00,
+        WatchKind::Write => 0b01,
+        WatchKind::ReadWrite => 0b11,
+    };
+    write_debug_register(tid, 0, address)?;
+    // Locally enable breakpoint 0, with a length of 1 byte
+    write_debug_register(tid, 7, 1 | (access_bits << 16))
+}
+
+/// Checks whether the last trap was breakpoint 0, clearing the debug status for the next one
+fn take_breakpoint_hit(tid: Pid) -> bool {
+    match read_debug_register(tid, 6) {
+        Ok(status) => {
+            let _ = write_debug_register(tid, 6, 0);
+            status & 1 != 0
+        }
+        Err(_) => false,
+    }
+}
+
+fn read_debug_register(tid: Pid, register: usize) -> Result<u64> {
+    let offset = DEBUG_REGISTERS_OFFSET + register * std::mem::size_of::<u64>();
+    unsafe { *libc::__errno_location() = 0 };
+    let value = unsafe {
+        libc::ptrace(
+            libc::PTRACE_PEEKUSER,
+            tid.as_raw(),
+            offset as *mut libc::c_void,
+            std::ptr::null_mut::<libc::c_void>(),
+        )
+    };
+    // -1 is a valid register value, so errno has to be checked instead
+    match Errno::last() {
+        Errno::UnknownErrno => Ok(value as u64),
+        err => Err(err.into()),
+    }
+}
+
+fn write_debug_register(tid: Pid, register: usize, value: u64) -> Result<()> {
+    let offset = DEBUG_REGISTERS_OFFSET + register * std::mem::size_of::<u64>();
+    let result = unsafe {
+        libc::ptrace(
+            libc::PTRACE_POKEUSER,
+            tid.as_raw(),
+            offset as *mut libc::c_void,
+            value as *mut libc::c_void,
+        )
+    };
+    Errno::result(result)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::{fork, ForkResult};
+    use std::sync::atomic::AtomicU64;
+
+    /// Written to over and over by the child process. Forking keeps it at the same address in the child
+    static WATCHED: AtomicU64 = AtomicU64::new(0);
+
+    #[test]
+    #[ignore = "needs ptrace permission and hardware debug registers, which containers and some VMs lack"]
+    fn write_watch_records_writes_in_child_process() {
+        // The child only touches the atomic, as little else is safe after forking a multithreaded process
+        let child = match unsafe { fork() }.unwrap() {
+            ForkResult::Child => loop {
+                WATCHED.fetch_add(1, Ordering::Relaxed);
+                std::hint::spin_loop();
+            },
+            ForkResult::Parent { child } => child,
+        };
+        let process = LinuxProcess::attach(child.as_raw() as u32);
+        let hits = process
+            .watch_address(&WATCHED as *const AtomicU64 as u64, WatchKind::Write)
+            .and_then(|watch| {
+                std::thread::sleep(Duration::from_millis(100));
+                let hits = watch.hits();
+                watch.stop().map(|_| hits)
+            });
+        // Clean up the child before checking anything, so a failure doesn't leave it spinning
+        let _ = kill(child, Signal::SIGKILL);
+        let _ = waitpid(child, None);
+
+        let hits = hits.unwrap();
+        assert!(!hits.is_empty());
+        // Every write is made by the same instruction
+        assert!(hits.iter().all(|hit| *hit == hits[0]));
+    }
+}
@@ -0,0 +1,356 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use mach2::kern_return::KERN_SUCCESS;
+use mach2::message::mach_msg_type_number_t;
+use mach2::port::{mach_port_name_t, mach_port_t};
+use mach2::task::task_info;
+use mach2::task_info::{task_dyld_info, task_info_t, TASK_DYLD_INFO, TASK_DYLD_INFO_COUNT};
+use mach2::traps::{mach_task_self, task_for_pid};
+use mach2::vm::{mach_vm_read_overwrite, mach_vm_region, mach_vm_write};
+use mach2::vm_prot::{vm_prot_t, VM_PROT_EXECUTE, VM_PROT_READ, VM_PROT_WRITE};
+use mach2::vm_region::{vm_region_basic_info_64, vm_region_info_t, VM_REGION_BASIC_INFO_64};
+use mach2::vm_types::{mach_vm_address_t, mach_vm_size_t, natural_t};
+use nix::{errno::Errno, sys::signal::kill, unistd::Pid};
+
+use crate::{
+    util::merge_adjacent_regions, GenericMemoryRead, MemoryRead, MemoryRegion, MemoryWrite, Module,
+    ProcessStatus, Protection, ScannableMemoryRegions,
+};
+
+/// Contiguous writable regions are merged into regions of at most this many bytes
+const MAX_MERGED_REGION_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Offsets of `infoArrayCount` and `infoArray` in `dyld_all_image_infos`, from `<mach-o/dyld_images.h>`
+const IMAGE_INFOS_COUNT_OFFSET: u64 = 4;
+const IMAGE_INFOS_ARRAY_OFFSET: u64 = 8;
+/// Size of a `dyld_image_info`: the image's load address, file path and modification date
+const IMAGE_INFO_SIZE: usize = 24;
+
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const MACH_HEADER_64_SIZE: usize = 32;
+const LC_SEGMENT_64: u32 = 0x19;
+/// Size of a `segment_command_64` up to the end of `vmsize`
+const SEGMENT_COMMAND_64_VMSIZE_END: usize = 40;
+
+/// Image paths are read at most this many bytes at a time, so a read never crosses into an unmapped page
+const PATH_READ_CHUNK: u64 = 4096;
+const MAX_PATH_LEN: usize = 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MacProcess {
+    pid: i32,
+    task: mach_port_name_t,
+}
+
+impl MacProcess {
+    /// Gets the task port of the process with the given pid
+    ///
+    /// `task_for_pid` needs root, or the `com.apple.security.cs.debugger` entitlement.
+    /// Even then, processes protected by System Integrity Protection and hardened runtime apps without the
+    /// `com.apple.security.get-task-allow` entitlement can't be attached to
+    pub fn attach(pid: u32) -> Result<Self> {
+        let mut task: mach_port_name_t = 0;
+        let result = unsafe { task_for_pid(mach_task_self(), pid as i32, &mut task) };
+        if result != KERN_SUCCESS {
+            return Err(anyhow!(
+                "task_for_pid failed for pid {} (kern_return_t {}). Try running as root",
+                pid,
+                result
+            ));
+        }
+        Ok(Self {
+            pid: pid as i32,
+            task,
+        })
+    }
+}
+
+impl MemoryRead for MacProcess {
+    /// Reads memory. Unlike other platforms, the read fails if any of the memory isn't mapped
+    fn read_memory_bytes(&self, address: u64, bytes_to_read: usize) -> Result<Vec<u8>> {
+        let mut buffer = vec![0u8; bytes_to_read];
+        let mut bytes_read: mach_vm_size_t = 0;
+        let result = unsafe {
+            mach_vm_read_overwrite(
+                self.task,
+                address as mach_vm_address_t,
+                bytes_to_read as mach_vm_size_t,
+                buffer.as_mut_ptr() as mach_vm_address_t,
+                &mut bytes_read,
+            )
+        };
+        if result != KERN_SUCCESS {
+            return Err(anyhow!(
+                "Failed to read memory at 0x{:016x} from process (pid={}, kern_return_t {})",
+                address,
+                self.pid,
+                result
+            ));
+        }
+        buffer.truncate(bytes_read as usize);
+        Ok(buffer)
+    }
+}
+
+impl MemoryWrite for MacProcess {
+    fn write_memory_bytes(&self, address: u64, bytes: &[u8]) -> Result<()> {
+        let result = unsafe {
+            mach_vm_write(
+                self.task,
+                address as mach_vm_address_t,
+                bytes.as_ptr() as usize,
+                bytes.len() as mach_msg_type_number_t,
+            )
+        };
+        if result != KERN_SUCCESS {
+            return Err(anyhow!(
+                "Failed to write {} bytes to 0x{:016x} in process (pid={}, kern_return_t {})",
+                bytes.len(),
+                address,
+                self.pid,
+                result
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl MacProcess {
+    /// Gets the regions of the process with all of the `protection` flags, merged where adjacent
+    fn get_regions(&self, protection: vm_prot_t) -> Vec<MemoryRegion> {
+        let mut regions = Vec::new();
+        let mut address: mach_vm_address_t = 0;
+        loop {
+            let mut size: mach_vm_size_t = 0;
+            let mut info: vm_region_basic_info_64 = unsafe { std::mem::zeroed() };
+            let mut info_count = (std::mem::size_of::<vm_region_basic_info_64>()
+                / std::mem::size_of::<natural_t>())
+                as mach_msg_type_number_t;
+            let mut object_name: mach_port_t = 0;
+            // Finds the first region at or after `address`, updating it to the region's start
+            let result = unsafe {
+                mach_vm_region(
+                    self.task,
+                    &mut address,
+                    &mut size,
+                    VM_REGION_BASIC_INFO_64,
+                    &mut info as *mut vm_region_basic_info_64 as vm_region_info_t,
+                    &mut info_count,
+                    &mut object_name,
+                )
+            };
+            if result != KERN_SUCCESS {
+                // Past the last region
+                break;
+            }
+            if info.protection & protection == protection {
+                let mut region_protection = Protection::empty();
+                region_protection.set(Protection::READ, info.protection & VM_PROT_READ != 0);
+                region_protection.set(Protection::WRITE, info.protection & VM_PROT_WRITE != 0);
+                region_protection.set(Protection::EXECUTE, info.protection & VM_PROT_EXECUTE != 0);
+                regions.push(MemoryRegion {
+                    base_address: address,
+                    size,
+                    protection: region_protection,
+                });
+            }
+            address += size;
+        }
+
+        merge_adjacent_regions(regions, MAX_MERGED_REGION_SIZE)
+    }
+
+    /// Gets the images loaded by dyld, from the image list dyld keeps in the process
+    fn loaded_images(&self) -> Result<Vec<Module>> {
+        let mut dyld_info: task_dyld_info = unsafe { std::mem::zeroed() };
+        let mut info_count = TASK_DYLD_INFO_COUNT;
+        let result = unsafe {
+            task_info(
+                self.task,
+                TASK_DYLD_INFO,
+                &mut dyld_info as *mut task_dyld_info as task_info_t,
+                &mut info_count,
+            )
+        };
+        if result != KERN_SUCCESS {
+            return Err(anyhow!(
+                "task_info failed for pid {} (kern_return_t {})",
+                self.pid,
+                result
+            ));
+        }
+
+        let image_infos = dyld_info.all_image_info_addr;
+        let image_count: u32 = self.read_memory(image_infos + IMAGE_INFOS_COUNT_OFFSET)?;
+        let image_array: u64 = self.read_memory(image_infos + IMAGE_INFOS_ARRAY_OFFSET)?;
+        if image_array == 0 {
+            // dyld clears the array while it's changing it
+            return Err(anyhow!(
+                "dyld is updating the image list of pid {}",
+                self.pid
+            ));
+        }
+        let images = self.read_memory_bytes(image_array, image_count as usize * IMAGE_INFO_SIZE)?;
+
+        let mut modules: Vec<Module> = images
+            .chunks_exact(IMAGE_INFO_SIZE)
+            .filter_map(|image| {
+                let load_address = u64::from_le_bytes(image[0..8].try_into().unwrap());
+                let path_address = u64::from_le_bytes(image[8..16].try_into().unwrap());
+                let size = self.image_size(load_address).ok()?;
+                let path = self.read_c_string(path_address).unwrap_or_default();
+                let path = Path::new(&path);
+                Some(Module {
+                    name: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.to_string_lossy().into_owned()),
+                    base_address: load_address,
+                    size,
+                })
+            })
+            .collect();
+        modules.sort_by_key(|module| module.base_address);
+        Ok(modules)
+    }
+
+    /// Gets how many bytes the image with its Mach-O header at `header_address` spans, from its segments
+    ///
+    /// `__PAGEZERO` and `__LINKEDIT` are left out. Images in the dyld shared cache share one `__LINKEDIT`,
+    /// far away from the rest of the image
+    fn image_size(&self, header_address: u64) -> Result<u64> {
+        let u32_at = |bytes: &[u8], offset: usize| {
+            u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+        };
+        let u64_at = |bytes: &[u8], offset: usize| {
+            u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+        };
+
+        let header = self.read_memory_bytes(header_address, MACH_HEADER_64_SIZE)?;
+        if header.len() < MACH_HEADER_64_SIZE || u32_at(&header, 0) != MH_MAGIC_64 {
+            return Err(anyhow!(
+                "No 64-bit Mach-O header at 0x{:016x}",
+                header_address
+            ));
+        }
+        let command_count = u32_at(&header, 16);
+        let commands_size = u32_at(&header, 20) as usize;
+        let commands =
+            self.read_memory_bytes(header_address + MACH_HEADER_64_SIZE as u64, commands_size)?;
+
+        // Segment addresses are from before the image was slid, so only the distance between them is used
+        let mut span: Option<(u64, u64)> = None;
+        let mut offset = 0;
+        for _ in 0..command_count {
+            let Some(command) = commands.get(offset..) else {
+                break;
+            };
+            if command.len() < 8 {
+                break;
+            }
+            let command_size = u32_at(command, 4) as usize;
+            if u32_at(command, 0) == LC_SEGMENT_64 && command.len() >= SEGMENT_COMMAND_64_VMSIZE_END
+            {
+                let name = command[8..24]
+                    .split(|&byte| byte == 0)
+                    .next()
+                    .unwrap_or_default();
+                let start = u64_at(command, 24);
+                let end = start + u64_at(command, 32);
+                if name != b"__PAGEZERO" && name != b"__LINKEDIT" && end > start {
+                    span = Some(match span {
+                        Some((span_start, span_end)) => (span_start.min(start), span_end.max(end)),
+                        None => (start, end),
+                    });
+                }
+            }
+            if command_size == 0 {
+                break;
+            }
+            offset += command_size;
+        }
+        span.map(|(start, end)| end - start)
+            .ok_or_else(|| anyhow!("Image at 0x{:016x} has no segments", header_address))
+    }
+
+    /// Reads the NUL terminated string at `address`
+    fn read_c_string(&self, address: u64) -> Result<String> {
+        let mut bytes = Vec::new();
+        let mut next = address;
+        while bytes.len() < MAX_PATH_LEN {
+            // Reads fail if any of the memory isn't mapped, so don't read past the end of this chunk
+            let chunk_len = PATH_READ_CHUNK - next % PATH_READ_CHUNK;
+            let chunk = self.read_memory_bytes(next, chunk_len as usize)?;
+            if chunk.is_empty() {
+                break;
+            }
+            if let Some(end) = chunk.iter().position(|&byte| byte == 0) {
+                bytes.extend_from_slice(&chunk[..end]);
+                return Ok(String::from_utf8_lossy(&bytes).into_owned());
+            }
+            bytes.extend_from_slice(&chunk);
+            next += chunk.len() as u64;
+        }
+        Err(anyhow!("No string terminator found at 0x{:016x}", address))
+    }
+}
+
+impl ScannableMemoryRegions for MacProcess {
+    fn get_writable_regions(&self) -> Vec<MemoryRegion> {
+        self.get_regions(VM_PROT_READ | VM_PROT_WRITE)
+    }
+
+    fn get_readable_regions(&self) -> Vec<MemoryRegion> {
+        self.get_regions(VM_PROT_READ)
+    }
+
+    /// Gets the images loaded by dyld. Empty if dyld's image list can't be read
+    fn get_modules(&self) -> Vec<Module> {
+        self.loaded_images().unwrap_or_else(|err| {
+            tracing::warn!("Failed to list the images of pid {}: {:#}", self.pid, err);
+            vec![]
+        })
+    }
+}
+
+impl ProcessStatus for MacProcess {
+    fn is_alive(&self) -> bool {
+        // Signal 0 only checks the process exists. Permission errors still mean it exists
+        !matches!(kill(Pid::from_raw(self.pid), None), Err(Errno::ESRCH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_module;
+
+    /// Lives in the test executable's data, so it's inside the executable's image
+    static KNOWN: u32 = 0x600d_f00d;
+
+    #[test]
+    fn attach_reads_own_static() {
+        let process = MacProcess::attach(std::process::id()).unwrap();
+        let address = &KNOWN as *const u32 as u64;
+        let value: u32 = process.read_memory(address).unwrap();
+        assert_eq!(value, KNOWN);
+        assert!(process
+            .get_writable_regions()
+            .iter()
+            .any(|region| address >= region.base_address
+                && address - region.base_address < region.size));
+    }
+
+    #[test]
+    fn get_modules_names_own_executable() {
+        let process = MacProcess::attach(std::process::id()).unwrap();
+        let address = &KNOWN as *const u32 as u64;
+        let exe = std::env::current_exe().unwrap();
+        let exe_name = exe.file_name().unwrap().to_string_lossy();
+
+        let modules = process.get_modules();
+        let module = find_module(&modules, address).expect("KNOWN should be in an image");
+        assert_eq!(module.name, exe_name);
+    }
+}
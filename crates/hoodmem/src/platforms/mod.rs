@@ -2,4 +2,10 @@
 pub mod windows;
 
 #[cfg(target_os="linux")]
-pub mod linux;
\ No newline at end of file
+pub mod linux;
+
+#[cfg(target_os="macos")]
+pub mod macos;
+
+#[cfg(all(target_os="linux", target_arch="x86_64"))]
+pub mod linux_watch;
@@ -1,46 +1,107 @@
 use std::ffi::{c_char, CString};
+use std::ops::Range;
 
 use anyhow::Result;
 use windows::core::PCSTR;
 pub use windows::Win32::Foundation::HANDLE;
-use windows::Win32::Foundation::HWND;
-use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+use windows::Win32::Foundation::{HMODULE, HWND, STILL_ACTIVE};
+use windows::Win32::System::Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory};
 use windows::Win32::System::Memory::{
-    VirtualQueryEx, MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_EXECUTE_READWRITE,
-    PAGE_EXECUTE_WRITECOPY, PAGE_PROTECTION_FLAGS, PAGE_READWRITE, PAGE_WRITECOPY,
+    VirtualQueryEx, MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_EXECUTE, PAGE_EXECUTE_READ,
+    PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY, PAGE_PROTECTION_FLAGS, PAGE_READONLY,
+    PAGE_READWRITE, PAGE_WRITECOPY,
+};
+use windows::Win32::System::ProcessStatus::{
+    EnumProcessModules, GetModuleBaseNameW, GetModuleInformation, MODULEINFO,
 };
 use windows::Win32::System::SystemInformation::{GetSystemInfo, SYSTEM_INFO};
-use windows::Win32::System::Threading::{OpenProcess, PROCESS_ALL_ACCESS};
+use windows::Win32::System::Threading::{GetExitCodeProcess, OpenProcess, PROCESS_ALL_ACCESS};
 use windows::Win32::UI::WindowsAndMessaging::{FindWindowA, GetWindowThreadProcessId};
 
-use crate::{MemoryRead, MemoryRegion, ScannableMemoryRegions};
+use crate::{
+    MemoryRead, MemoryRegion, MemoryWrite, Module, ProcessStatus, Protection,
+    ScannableMemoryRegions,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct WinProcess {
     handle: HANDLE,
 }
 
+/// Reads which fail are split down to this many bytes, so one unreadable page doesn't lose the rest of a read
+const PAGE_SIZE: u64 = 4096;
+
 impl MemoryRead for WinProcess {
+    /// Reads memory, zero filling any pages which can't be read. Fails only if no page could be read
+    ///
+    /// Use [`WinProcess::read_memory_pages`] to find out which pages were zero filled
     fn read_memory_bytes(&self, address: u64, bytes_to_read: usize) -> Result<Vec<u8>> {
-        let mut buffer: Vec<u8> = Vec::with_capacity(bytes_to_read);
-        unsafe {
-            buffer.set_len(bytes_to_read);
+        let mut buffer: Vec<u8> = vec![0; bytes_to_read];
+        let whole_read = unsafe {
             ReadProcessMemory(
                 self.handle,
                 std::mem::transmute(address),
                 std::mem::transmute(buffer.as_mut_ptr()),
                 bytes_to_read,
                 None,
-            )?;
+            )
+        };
+        if let Err(err) = whole_read {
+            let (buffer, valid_pages) = self.read_memory_pages(address, bytes_to_read);
+            if !valid_pages.contains(&true) {
+                return Err(err.into());
+            }
+            return Ok(buffer);
         }
         Ok(buffer)
     }
+
+    fn read_memory_with_gaps(
+        &self,
+        address: u64,
+        bytes_to_read: usize,
+    ) -> Result<(Vec<u8>, Vec<Range<usize>>)> {
+        let (buffer, valid_pages) = self.read_memory_pages(address, bytes_to_read);
+        if !valid_pages.contains(&true) && bytes_to_read > 0 {
+            return Err(anyhow::anyhow!("0x{:016x} is not readable", address));
+        }
+        // Turn runs of invalid pages into ranges of the buffer, clamped to the read
+        let page_base = address - address % PAGE_SIZE;
+        let mut gaps: Vec<Range<usize>> = vec![];
+        for (page, valid) in valid_pages.iter().enumerate() {
+            if *valid {
+                continue;
+            }
+            let start = (page_base + page as u64 * PAGE_SIZE).max(address) - address;
+            let end =
+                (page_base + (page as u64 + 1) * PAGE_SIZE - address).min(bytes_to_read as u64);
+            match gaps.last_mut() {
+                Some(gap) if gap.end == start as usize => gap.end = end as usize,
+                _ => gaps.push(start as usize..end as usize),
+            }
+        }
+        Ok((buffer, gaps))
+    }
 }
 
-impl ScannableMemoryRegions for WinProcess {
-    fn get_writable_regions(&self) -> Vec<MemoryRegion> {
-        let page_protection: PAGE_PROTECTION_FLAGS =
-            PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+impl MemoryWrite for WinProcess {
+    fn write_memory_bytes(&self, address: u64, bytes: &[u8]) -> Result<()> {
+        unsafe {
+            WriteProcessMemory(
+                self.handle,
+                std::mem::transmute(address),
+                std::mem::transmute(bytes.as_ptr()),
+                bytes.len(),
+                None,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl WinProcess {
+    /// Gets the committed regions of the process with any of the `page_protection` flags
+    fn get_regions(&self, page_protection: PAGE_PROTECTION_FLAGS) -> Vec<MemoryRegion> {
         let system_info = get_system_info();
         let mut address: u64 =
             unsafe { std::mem::transmute(system_info.lpMinimumApplicationAddress) };
@@ -68,6 +129,7 @@ impl ScannableMemoryRegions for WinProcess {
                 regions.push(MemoryRegion {
                     base_address: memory_basic_info.BaseAddress as u64,
                     size: memory_basic_info.RegionSize as u64,
+                    protection: to_protection(memory_basic_info.Protect),
                 });
             }
             address = memory_basic_info.BaseAddress as u64 + memory_basic_info.RegionSize as u64;
@@ -76,7 +138,130 @@ impl ScannableMemoryRegions for WinProcess {
     }
 }
 
+impl ScannableMemoryRegions for WinProcess {
+    fn get_writable_regions(&self) -> Vec<MemoryRegion> {
+        self.get_regions(
+            PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY,
+        )
+    }
+
+    fn get_readable_regions(&self) -> Vec<MemoryRegion> {
+        self.get_regions(
+            PAGE_READONLY
+                | PAGE_READWRITE
+                | PAGE_WRITECOPY
+                | PAGE_EXECUTE_READ
+                | PAGE_EXECUTE_READWRITE
+                | PAGE_EXECUTE_WRITECOPY,
+        )
+    }
+
+    fn get_modules(&self) -> Vec<Module> {
+        let mut handles: Vec<HMODULE> = vec![HMODULE::default(); 1024];
+        let mut bytes_needed: u32 = 0;
+        let enumerated = unsafe {
+            EnumProcessModules(
+                self.handle,
+                handles.as_mut_ptr(),
+                (handles.len() * std::mem::size_of::<HMODULE>()) as u32,
+                &mut bytes_needed,
+            )
+        };
+        if enumerated.is_err() {
+            return vec![];
+        }
+        handles.truncate(bytes_needed as usize / std::mem::size_of::<HMODULE>());
+
+        let mut modules: Vec<Module> = handles
+            .into_iter()
+            .filter_map(|module_handle| {
+                let mut info: MODULEINFO = Default::default();
+                unsafe {
+                    GetModuleInformation(
+                        self.handle,
+                        module_handle,
+                        &mut info,
+                        std::mem::size_of::<MODULEINFO>() as u32,
+                    )
+                }
+                .ok()?;
+                let mut name = [0u16; 260];
+                let name_len =
+                    unsafe { GetModuleBaseNameW(self.handle, module_handle, &mut name) } as usize;
+                Some(Module {
+                    name: String::from_utf16_lossy(&name[..name_len]),
+                    base_address: info.lpBaseOfDll as u64,
+                    size: info.SizeOfImage as u64,
+                })
+            })
+            .collect();
+        modules.sort_by_key(|module| module.base_address);
+        modules
+    }
+}
+
+impl ProcessStatus for WinProcess {
+    fn is_alive(&self) -> bool {
+        let mut exit_code: u32 = 0;
+        match unsafe { GetExitCodeProcess(self.handle, &mut exit_code) } {
+            // A process which exits with STILL_ACTIVE as its exit code looks alive, but that is rare enough to ignore
+            Ok(()) => exit_code == STILL_ACTIVE.0 as u32,
+            Err(_) => false,
+        }
+    }
+}
+
 impl WinProcess {
+    /// Reads memory a page at a time where needed, zero filling pages which can't be read
+    ///
+    /// Also returns whether each page overlapping the read could be read, starting with the page containing `address`
+    pub fn read_memory_pages(&self, address: u64, bytes_to_read: usize) -> (Vec<u8>, Vec<bool>) {
+        let mut buffer: Vec<u8> = vec![0; bytes_to_read];
+        if bytes_to_read == 0 {
+            return (buffer, vec![]);
+        }
+        let page_base = address - address % PAGE_SIZE;
+        let page_count =
+            ((address + bytes_to_read as u64 - 1 - page_base) / PAGE_SIZE + 1) as usize;
+        let mut valid_pages = vec![false; page_count];
+        self.read_split(address, &mut buffer, page_base, &mut valid_pages);
+        (buffer, valid_pages)
+    }
+
+    /// Reads into `buffer`, splitting the read in half on a page boundary whenever it fails
+    fn read_split(
+        &self,
+        address: u64,
+        buffer: &mut [u8],
+        page_base: u64,
+        valid_pages: &mut [bool],
+    ) {
+        let first_page = (address - page_base) / PAGE_SIZE;
+        let last_page = (address + buffer.len() as u64 - 1 - page_base) / PAGE_SIZE;
+        let read = unsafe {
+            ReadProcessMemory(
+                self.handle,
+                std::mem::transmute(address),
+                std::mem::transmute(buffer.as_mut_ptr()),
+                buffer.len(),
+                None,
+            )
+        };
+        if read.is_ok() {
+            valid_pages[first_page as usize..=last_page as usize].fill(true);
+            return;
+        }
+        if first_page == last_page {
+            // A failed read may have partially written the buffer
+            buffer.fill(0);
+            return;
+        }
+        let split_address = page_base + (first_page + last_page + 1) / 2 * PAGE_SIZE;
+        let (low, high) = buffer.split_at_mut((split_address - address) as usize);
+        self.read_split(address, low, page_base, valid_pages);
+        self.read_split(split_address, high, page_base, valid_pages);
+    }
+
     pub fn attach(pid: u32) -> Result<Self> {
         unsafe {
             Ok(Self {
@@ -106,6 +291,32 @@ impl WinProcess {
     }
 }
 
+/// Converts Windows page protection flags to [`Protection`]
+fn to_protection(page_protection: PAGE_PROTECTION_FLAGS) -> Protection {
+    let has_any = |flags: PAGE_PROTECTION_FLAGS| (page_protection & flags).0 > 0;
+    let mut protection = Protection::empty();
+    protection.set(
+        Protection::READ,
+        has_any(
+            PAGE_READONLY
+                | PAGE_READWRITE
+                | PAGE_WRITECOPY
+                | PAGE_EXECUTE_READ
+                | PAGE_EXECUTE_READWRITE
+                | PAGE_EXECUTE_WRITECOPY,
+        ),
+    );
+    protection.set(
+        Protection::WRITE,
+        has_any(PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY),
+    );
+    protection.set(
+        Protection::EXECUTE,
+        has_any(PAGE_EXECUTE | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY),
+    );
+    protection
+}
+
 fn get_system_info() -> SYSTEM_INFO {
     let mut system_info: SYSTEM_INFO = Default::default();
     unsafe {
@@ -113,3 +324,35 @@ fn get_system_info() -> SYSTEM_INFO {
     }
     system_info
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{attach_external, GenericMemoryRead};
+
+    static KNOWN: u32 = 0x1234_5678;
+
+    #[test]
+    fn attach_external_reads_own_static() {
+        let process = attach_external(std::process::id()).unwrap();
+        assert!(process.is_alive());
+        let value: u32 = process.read_memory(&KNOWN as *const u32 as u64).unwrap();
+        assert_eq!(value, KNOWN);
+    }
+
+    #[test]
+    fn page_protection_maps_to_protection() {
+        assert_eq!(to_protection(PAGE_READONLY), Protection::READ);
+        assert_eq!(
+            to_protection(PAGE_WRITECOPY),
+            Protection::READ | Protection::WRITE
+        );
+        assert_eq!(to_protection(PAGE_EXECUTE), Protection::EXECUTE);
+        assert_eq!(
+            to_protection(PAGE_EXECUTE_READ),
+            Protection::READ | Protection::EXECUTE
+        );
+        assert_eq!(to_protection(PAGE_EXECUTE_READWRITE), Protection::all());
+        assert_eq!(to_protection(PAGE_PROTECTION_FLAGS(0)), Protection::empty());
+    }
+}
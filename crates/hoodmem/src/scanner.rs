@@ -1,14 +1,26 @@
+use crate::snapshot::Snapshot;
 use crate::util::*;
 use crate::*;
 use anyhow::Result;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::Path;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
 
 /// Scan filter used when diffing memory and updating scan results
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum ScanFilter<T> {
     Exact(T),
+    /// Equal to any of the values, such as a state which can be 1, 2 or 4
+    ExactAny(Vec<T>),
     /// Approximately equals, within a threshold
     Approximate(T, T),
     Increased,
@@ -25,16 +37,138 @@ pub enum ScanFilter<T> {
     ChangedByAtMost(T),
     UnchangedByAtLeast(T),
     UnchangedByAtMost(T),
+    /// Between a minimum and maximum value (inclusive). The bounds may be given in either order
+    Between(T, T),
+    NotEqual(T),
+    /// Equal to a value when both are rounded to the given number of decimal places. Only floats can match
+    RoundedTo(T, u32),
+    /// Within `tolerance` percentage points of `percent`% of `max`, such as health at about 75% of its maximum
+    PercentOf {
+        max: T,
+        percent: f64,
+        tolerance: f64,
+    },
+    /// A multiple of the value, such as a timer counting in steps of 5. Only integers can match.
+    /// Use [`ScanFilter::divisible_by`] to make one, which refuses a divisor of 0
+    DivisibleBy(T),
     Unknown,
 }
 
+/// Rounding used by [`ScanFilter::RoundedTo`]
+pub trait RoundTo: Sized {
+    /// Scales by `10^places` and rounds to the nearest whole number, or `None` if this type can't be rounded
+    fn round_to(self, places: u32) -> Option<Self>;
+}
+
+macro_rules! impl_round_to_float {
+    ($($t:ty),*) => {
+        $(impl RoundTo for $t {
+            fn round_to(self, places: u32) -> Option<Self> {
+                Some((self * (10 as $t).powi(places as i32)).round())
+            }
+        })*
+    };
+}
+
+macro_rules! impl_round_to_integer {
+    ($($t:ty),*) => {
+        $(impl RoundTo for $t {
+            fn round_to(self, _places: u32) -> Option<Self> {
+                None
+            }
+        })*
+    };
+}
+
+impl_round_to_float!(f32, f64);
+impl_round_to_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// Conversion used by [`ScanFilter::PercentOf`], so percentages can be compared without overflowing small integer types
+pub trait ToF64 {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_to_f64 {
+    ($($t:ty),*) => {
+        $(impl ToF64 for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_to_f64!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+/// Divisibility used by [`ScanFilter::DivisibleBy`]
+pub trait DivisibleBy: Sized {
+    /// Whether this is a multiple of `divisor`. Always false for floats, or a divisor of 0
+    fn divisible_by(self, divisor: Self) -> bool;
+}
+
+macro_rules! impl_divisible_by_integer {
+    ($($t:ty),*) => {
+        $(impl DivisibleBy for $t {
+            fn divisible_by(self, divisor: Self) -> bool {
+                // wrapping_rem gives 0 for MIN % -1, which overflows, and every number is a multiple of -1
+                divisor != 0 && self.wrapping_rem(divisor) == 0
+            }
+        })*
+    };
+}
+
+macro_rules! impl_divisible_by_float {
+    ($($t:ty),*) => {
+        $(impl DivisibleBy for $t {
+            fn divisible_by(self, _divisor: Self) -> bool {
+                false
+            }
+        })*
+    };
+}
+
+impl_divisible_by_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
+impl_divisible_by_float!(f32, f64);
+
+impl<T> ScanFilter<T> {
+    /// Whether this filter narrows down the first scan, which has no previous values to compare with
+    pub fn filters_first_scan(&self) -> bool {
+        matches!(
+            self,
+            ScanFilter::Exact(_)
+                | ScanFilter::ExactAny(_)
+                | ScanFilter::Between(_, _)
+                | ScanFilter::NotEqual(_)
+                | ScanFilter::RoundedTo(_, _)
+                | ScanFilter::PercentOf { .. }
+                | ScanFilter::DivisibleBy(_)
+        )
+    }
+}
+
 impl<T> ScanFilter<T>
 where
-    T: Copy + PartialOrd + PartialEq + std::ops::Sub<Output = T> + std::ops::Add<Output = T>,
+    T: Copy
+        + PartialOrd
+        + PartialEq
+        + std::ops::Sub<Output = T>
+        + std::ops::Add<Output = T>
+        + RoundTo
+        + ToF64
+        + DivisibleBy,
 {
+    /// Makes a [`ScanFilter::DivisibleBy`] filter, failing if `divisor` is 0
+    pub fn divisible_by(divisor: T) -> Result<Self> {
+        if divisor.to_f64() == 0.0 {
+            anyhow::bail!("Cannot scan for values divisible by 0");
+        }
+        Ok(ScanFilter::DivisibleBy(divisor))
+    }
+
     pub fn matches(&self, new_t: &T, old_t: &T) -> bool {
         match self {
             ScanFilter::Exact(new_value) => *new_value == *new_t,
+            ScanFilter::ExactAny(values) => values.contains(new_t),
             ScanFilter::Approximate(new_value, threshold) => {
                 (if *new_t > *new_value {
                     *new_t - *new_value
@@ -80,21 +214,175 @@ where
                     *old_t - *new_t
                 }) <= *diff
             }
+            ScanFilter::Between(min, max) => {
+                let (min, max) = if *min > *max {
+                    (*max, *min)
+                } else {
+                    (*min, *max)
+                };
+                *new_t >= min && *new_t <= max
+            }
+            ScanFilter::NotEqual(value) => *new_t != *value,
+            ScanFilter::RoundedTo(value, places) => {
+                match (new_t.round_to(*places), value.round_to(*places)) {
+                    (Some(new_rounded), Some(value_rounded)) => new_rounded == value_rounded,
+                    _ => false,
+                }
+            }
+            ScanFilter::PercentOf {
+                max,
+                percent,
+                tolerance,
+            } => {
+                let max = max.to_f64();
+                if max == 0.0 {
+                    return false;
+                }
+                (new_t.to_f64() / max * 100.0 - percent).abs() <= *tolerance
+            }
+            ScanFilter::DivisibleBy(divisor) => new_t.divisible_by(*divisor),
             ScanFilter::Unknown => true,
         }
     }
 }
 
+/// Filter for three contiguous `f32`s, such as an x, y, z position
+///
+/// Each component must be within `tolerance` of its value. `None` components match anything
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vec3Filter {
+    pub components: [Option<f32>; 3],
+    pub tolerance: f32,
+}
+
+impl Vec3Filter {
+    /// Checks whether `value` matches each known component
+    pub fn matches(&self, value: [f32; 3]) -> bool {
+        self.components.iter().zip(value).all(|(expected, actual)| {
+            expected.map_or(true, |expected| (actual - expected).abs() <= self.tolerance)
+        })
+    }
+}
+
+/// Text encoding used when scanning for strings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StringEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Ascii,
+}
+
+impl StringEncoding {
+    /// Encodes `text` into the bytes it would be stored as in memory
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        match self {
+            StringEncoding::Utf8 => Ok(text.as_bytes().to_vec()),
+            StringEncoding::Utf16Le => Ok(text
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect()),
+            StringEncoding::Ascii => {
+                if !text.is_ascii() {
+                    anyhow::bail!("'{}' is not a valid ASCII string", text);
+                }
+                Ok(text.as_bytes().to_vec())
+            }
+        }
+    }
+}
+
+/// Regions larger than this are read and scanned in chunks of this size
+const SCAN_CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+
+/// How far each chunk reads into the next one, so values and patterns straddling a chunk boundary are still found
+const CHUNK_OVERLAP: u64 = 256;
+
+/// Hits closer together than this are re-read with a single read when refreshing values
+const REFRESH_MERGE_GAP: u64 = 4096;
+
+/// How many regions each rayon thread reads per batch when starting a new scan. Cancelling is checked between batches
+const READ_BATCH_PER_THREAD: usize = 4;
+
+/// Value history isn't recorded while there are more hits than this, as early scans can have millions
+const HISTORY_MAX_HITS: usize = 10_000;
+
+/// Regions larger than this are skipped by default, as they are almost always huge reservations rather than real data
+const DEFAULT_MAX_REGION_BYTES: u64 = 512 * 1024 * 1024;
+
+/// How many scans can be undone. Each keeps the hits from before it, and the snapshots it replaced
+const MAX_UNDO_SCANS: usize = 5;
+
+/// Snapshots kept by undo entries other than the latest are dropped, oldest first, once they add up to more than this
+const DEFAULT_MAX_UNDO_BYTES: usize = 256 * 1024 * 1024;
+
+/// The one byte x86 NOP instruction
+const X86_NOP: u8 = 0x90;
+
+/// Written at the start of session files, before the format version
+const SESSION_MAGIC: &[u8; 4] = b"HMSS";
+
+/// Version of the session file format. Sessions are encoded with bincode, which can't skip or default missing fields,
+/// so this must be bumped whenever anything saved in a session changes
+const SESSION_FORMAT_VERSION: u32 = 1;
+
+/// A value being watched for changes with [`Scanner::watch_value`]
+struct ValueWatch {
+    address: u64,
+    size: usize,
+    /// The bytes last read from `address`, if it could be read
+    last: Option<Vec<u8>>,
+    /// Sends the new bytes as a value to the subscriber. Returns false once the subscriber has gone away
+    notify: Box<dyn FnMut(&Vec<u8>) -> bool + Send>,
+}
+
+/// The results from before a scan, so it can be undone with [`Scanner::undo_scan`]
+struct ScanUndo {
+    regions: BTreeMap<MemoryRegion, RegionUndo>,
+    is_new_scan: bool,
+}
+
+/// The hits and snapshots of a region from before a scan
+struct RegionUndo {
+    hit_offsets: Option<Vec<u64>>,
+    hit_size: u64,
+    /// Shared with the results until the scan replaces them, so they aren't copied
+    buffer: Option<Arc<Vec<u8>>>,
+    prev_buffer: Option<Arc<Vec<u8>>>,
+    /// Set once the snapshots have been dropped to save memory. Only the hits are restored then
+    snapshots_dropped: bool,
+}
+
 /// Region scan results
 ///
 /// Will store entire regions of memory
+#[derive(Serialize, Deserialize)]
 pub struct RegionResults {
     /// Region base address
     region: MemoryRegion,
-    /// Offsets of current hits within this region
+    /// Offsets of current hits within this region, in ascending order
+    ///
+    /// `None` once there is a snapshot means every aligned offset is a hit, as after a first scan which didn't filter.
+    /// Those hits aren't stored, as they would take up to 8 times as much memory as the snapshot
     hit_offsets: Option<Vec<u64>>,
-    /// The last snapshot of this memory region (prev values)
-    buffer: Option<Vec<u8>>,
+    /// The last snapshot of this memory region (prev values). Shared with undo entries
+    buffer: Option<Arc<Vec<u8>>>,
+    /// The snapshot of this memory region before `buffer`
+    prev_buffer: Option<Arc<Vec<u8>>>,
+    /// Values re-read by [`Scanner::refresh_values`] since the last scan. Shown instead of `buffer`, which scans still compare with
+    #[serde(skip)]
+    live_buffer: Option<Vec<u8>>,
+    /// Byte order values in this region are read with
+    endianness: Endianness,
+    /// Extra bytes read past the end of the region, so values starting in the region can extend beyond it
+    overlap: u64,
+    /// Size in bytes of the value or pattern found at each hit
+    hit_size: u64,
+    /// Hits must start at an address which is a multiple of this. 0 and 1 both allow any address
+    alignment: u64,
+    /// Added with [`Scanner::add_manual_address`] rather than found by a scan. The address was chosen on purpose,
+    /// so it is kept whatever the scanner's alignment
+    manual: bool,
 }
 
 impl RegionResults {
@@ -104,65 +392,194 @@ impl RegionResults {
             region,
             hit_offsets: None,
             buffer: None,
+            prev_buffer: None,
+            live_buffer: None,
+            endianness: Endianness::default(),
+            overlap: 0,
+            hit_size: 0,
+            alignment: 1,
+            manual: false,
         }
     }
 
-    pub fn get_results<T: Copy + Send + Sync>(&self) -> Option<Vec<(u64, T)>> {
-        let size_of_t = std::mem::size_of::<T>() as u64;
-        if let Some(offsets) = self.hit_offsets.as_ref() {
-            if let Some(buffer) = self.buffer.as_ref() {
-                return Some(
-                    offsets
-                        .into_par_iter()
-                        // Ensure we don't read any results outside the buffer
-                        .filter(|offset| **offset + size_of_t <= buffer.len() as u64)
-                        .map(|offset| {
-                            (
-                                *offset + self.region.base_address,
-                                read_from_buffer::<T>(buffer, *offset),
-                            )
-                        })
-                        .collect(),
-                );
+    /// Sets the byte order values in this region are read with
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    /// Sets the alignment of addresses values are found at by later scans
+    pub fn set_alignment(&mut self, alignment: u64) {
+        self.alignment = alignment;
+    }
+
+    /// Gets the latest known memory of this region, refreshed since the last scan if it has been
+    fn display_buffer(&self) -> Option<&Vec<u8>> {
+        self.live_buffer.as_ref().or_else(|| self.buffer.as_deref())
+    }
+
+    /// Gets the first offset and number of hits when every aligned offset is a hit
+    ///
+    /// Hits start in the region, and have to fit in the snapshot
+    fn every_aligned_offset(&self) -> (u64, usize) {
+        let alignment = self.alignment.max(1);
+        let buffer_len = self.buffer.as_ref().map_or(0, |buffer| buffer.len() as u64);
+        let end = self
+            .region
+            .size
+            .min((buffer_len + 1).saturating_sub(self.hit_size.max(1)));
+        let first = (alignment - self.region.base_address % alignment) % alignment;
+        if first >= end {
+            (first, 0)
+        } else {
+            (first, ((end - first - 1) / alignment + 1) as usize)
+        }
+    }
+
+    /// Gets the offsets of every hit. They are only stored once a scan has filtered this region
+    fn hits(&self) -> Cow<'_, [u64]> {
+        match &self.hit_offsets {
+            Some(hit_offsets) => Cow::Borrowed(hit_offsets),
+            None if self.buffer.is_some() => {
+                let (first, count) = self.every_aligned_offset();
+                let alignment = self.alignment.max(1);
+                Cow::Owned((0..count as u64).map(|i| first + i * alignment).collect())
             }
+            None => Cow::Borrowed(&[]),
         }
-        None
+    }
+
+    /// Gets the hit offsets to narrow down without scanning, storing every aligned offset first if they weren't stored
+    fn hit_offsets_mut(&mut self) -> &mut Vec<u64> {
+        if self.hit_offsets.is_none() {
+            self.hit_offsets = Some(self.hits().into_owned());
+        }
+        self.hit_offsets.as_mut().unwrap()
+    }
+
+    /// Drops hits overlapping any of `gaps`, ranges of the last read which couldn't be read and were zero filled
+    fn drop_hits_in_gaps(&mut self, gaps: &[Range<usize>]) {
+        if gaps.is_empty() {
+            return;
+        }
+        let hit_size = self.hit_size.max(1);
+        self.hit_offsets_mut().retain(|offset| {
+            !gaps
+                .iter()
+                .any(|gap| *offset < gap.end as u64 && offset + hit_size > gap.start as u64)
+        });
+    }
+
+    pub fn get_results<T: Copy + Send + Sync>(&self) -> Option<Vec<(u64, T)>> {
+        let endianness = self.endianness;
+        let buffer = self.display_buffer()?;
+        Some(
+            self.hits()
+                .par_iter()
+                // Results which don't fit in the buffer are skipped
+                .filter_map(|offset| {
+                    Some((
+                        *offset + self.region.base_address,
+                        try_read_from_buffer_endian::<T>(buffer, *offset, endianness)?,
+                    ))
+                })
+                .collect(),
+        )
+    }
+
+    /// Gets results as `(address, current value, previous value)`
+    ///
+    /// The current value comes from the last snapshot, or refresh since it, and the previous value from the snapshot before it.
+    /// If there has only been one snapshot so far, the previous value is the value found by that scan
+    pub fn get_results_with_prev<T: Copy + Send + Sync>(&self) -> Option<Vec<(u64, T, T)>> {
+        let endianness = self.endianness;
+        let buffer = self.display_buffer()?;
+        let prev_buffer = self.prev_buffer.as_ref().or(self.buffer.as_ref())?;
+        Some(
+            self.hits()
+                .par_iter()
+                // Results which don't fit in either buffer are skipped
+                .filter_map(|offset| {
+                    Some((
+                        *offset + self.region.base_address,
+                        try_read_from_buffer_endian::<T>(buffer, *offset, endianness)?,
+                        try_read_from_buffer_endian::<T>(prev_buffer, *offset, endianness)?,
+                    ))
+                })
+                .collect(),
+        )
     }
 
     pub fn print<T: std::fmt::Debug + Copy>(&self) {
-        let results_count = if self.hit_offsets.is_some() {
-            self.hit_offsets.as_ref().unwrap().len()
-        } else {
-            0
-        };
+        let endianness = self.endianness;
+        let results_count = self.hit_count();
         println!(
             "[Region 0x{:016x} - 0x{:016x}] {} Results:",
             self.region.base_address,
             self.region.base_address + self.region.size,
             results_count
         );
-        if let Some(offsets) = &self.hit_offsets {
-            for offset in offsets.iter().take(1) {
-                if let Some(buffer) = &self.buffer {
-                    println!(
-                        "0x{:016x} = {:#?}",
-                        *offset + self.region.base_address,
-                        read_from_buffer::<T>(buffer, *offset)
-                    );
-                }
+        for offset in self.hits().iter().take(1) {
+            let value = self
+                .display_buffer()
+                .and_then(|buffer| try_read_from_buffer_endian::<T>(buffer, *offset, endianness));
+            if let Some(value) = value {
+                println!(
+                    "0x{:016x} = {:#?}",
+                    *offset + self.region.base_address,
+                    value
+                );
             }
         }
         println!("-----------------------------------------------------------------------------")
     }
 
+    /// Number of hits in this region so far
+    pub fn hit_count(&self) -> usize {
+        match &self.hit_offsets {
+            Some(hit_offsets) => hit_offsets.len(),
+            None if self.buffer.is_some() => self.every_aligned_offset().1,
+            None => 0,
+        }
+    }
+
+    /// Whether `offset` into this region is a hit. Hit offsets are kept in ascending order, so this is a binary search
+    pub fn is_hit(&self, offset: u64) -> bool {
+        match &self.hit_offsets {
+            Some(hit_offsets) => hit_offsets.binary_search(&offset).is_ok(),
+            None if self.buffer.is_some() => {
+                let (first, count) = self.every_aligned_offset();
+                let alignment = self.alignment.max(1);
+                offset >= first
+                    && (offset - first) % alignment == 0
+                    && (offset - first) / alignment < count as u64
+            }
+            None => false,
+        }
+    }
+
     /// Clear these results for the next scan
     pub fn clear(&mut self) {
         self.hit_offsets = None;
         self.buffer = None;
+        self.prev_buffer = None;
+        self.live_buffer = None;
+    }
+
+    /// Stores `region_buf` as the latest snapshot, keeping the current one as the previous snapshot
+    fn store_buffer(&mut self, region_buf: Vec<u8>) {
+        self.live_buffer = None;
+        if self.hit_offsets.as_ref().is_none() || self.hit_offsets.as_ref().unwrap().len() > 0 {
+            // Only keep track of previous values if we have hits, or haven't scanned yet
+            self.prev_buffer = self.buffer.replace(Arc::new(region_buf));
+        } else {
+            // Hit offsets length is 0
+            self.buffer = None;
+            self.prev_buffer = None;
+        }
     }
 
     /// Updates results given a buffer of this regions new memory, and a filter
-    pub fn update_results<T>(&mut self, region_buf: Vec<u8>, filter: ScanFilter<T>)
+    pub fn update_results<T>(&mut self, region_buf: Vec<u8>, filter: &ScanFilter<T>)
     where
         T: Copy
             + Send
@@ -170,33 +587,76 @@ impl RegionResults {
             + PartialOrd
             + PartialEq
             + std::ops::Sub<Output = T>
-            + std::ops::Add<Output = T>,
+            + std::ops::Add<Output = T>
+            + RoundTo
+            + ToF64
+            + DivisibleBy,
+    {
+        self.update_results_with(region_buf, filter.filters_first_scan(), |new, old| {
+            filter.matches(new, old)
+        })
+    }
+
+    /// Updates results given a buffer of this regions new memory, keeping hits where `matches(new value, old value)` is true
+    ///
+    /// There are no old values on the first scan. If `filter_first_scan` is set, `matches` is given the new value as both,
+    /// otherwise every aligned value is a hit
+    pub fn update_results_with<T, F>(
+        &mut self,
+        region_buf: Vec<u8>,
+        filter_first_scan: bool,
+        matches: F,
+    ) where
+        T: Copy + Send + Sync,
+        F: Fn(&T, &T) -> bool + Sync,
     {
         let size_of_t = std::mem::size_of::<T>() as u64;
+        let endianness = self.endianness;
+        let alignment = self.alignment.max(1);
+        let base_address = self.region.base_address;
+        self.hit_size = size_of_t;
+        if self.region.size + self.overlap < size_of_t {
+            // Nothing of this type can fit in this region
+            self.hit_offsets = Some(vec![]);
+            self.buffer = None;
+            self.prev_buffer = None;
+            return;
+        }
+        // Every offset a T can start at within the region. Offsets where it wouldn't fit in the buffer are filtered out below
+        let scan_range = 0..self.region.size;
         if self.buffer.is_none() {
             // There was no previous buffer, this must be the first scan
-            match filter {
-                // At least filter on exact value first scans (known initial value)
-                ScanFilter::Exact(_) => {
-                    // New exact value scan
-                    let scan_range = 0..(self.region.size as u64 - std::mem::size_of::<T>() as u64);
+            match filter_first_scan {
+                true => {
+                    // New known value scan
                     self.hit_offsets = Some(
                         scan_range
                             .into_par_iter()
-                            // Ensure we don't read any results outside the buffer
-                            .filter(|offset| *offset + size_of_t <= region_buf.len() as u64)
-                            .map(|offset| (offset, read_from_buffer::<T>(&region_buf, offset)))
-                            .filter(|(_, val)| filter.matches(val, val))
+                            .filter(|offset| (base_address + *offset) % alignment == 0)
+                            // Values which don't fit in the buffer can't be hits
+                            .filter_map(|offset| {
+                                Some((
+                                    offset,
+                                    try_read_from_buffer_endian::<T>(
+                                        &region_buf,
+                                        offset,
+                                        endianness,
+                                    )?,
+                                ))
+                            })
+                            .filter(|(_, val)| matches(val, val))
                             .map(|(addr, _)| addr)
                             .collect(),
                     );
                 }
-                _ => {}
+                false => {
+                    // No initial value to filter on, so every aligned value is a hit. The buffer is the baseline for the next scan
+                    self.hit_offsets = None;
+                }
             }
         } else {
             // Subsequent scans. We have access to previous values here
-            let scan_range = 0..(self.region.size as u64 - std::mem::size_of::<T>() as u64);
-
+            let prev_buffer = self.buffer.as_ref().unwrap();
             if self.hit_offsets.is_some() {
                 // We have existing hits, filter on them
                 self.hit_offsets = Some(
@@ -204,16 +664,16 @@ impl RegionResults {
                         .as_ref()
                         .unwrap()
                         .into_par_iter()
-                        // Ensure we don't read any results outside the buffer
-                        .filter(|offset| **offset + size_of_t <= region_buf.len() as u64)
-                        .map(|offset| {
-                            (
+                        .filter(|offset| (base_address + **offset) % alignment == 0)
+                        // Values which don't fit in either buffer can't be hits. Reads may be short
+                        .filter_map(|offset| {
+                            Some((
                                 offset,
-                                read_from_buffer::<T>(&region_buf, *offset),
-                                read_from_buffer(self.buffer.as_ref().unwrap(), *offset),
-                            )
+                                try_read_from_buffer_endian::<T>(&region_buf, *offset, endianness)?,
+                                try_read_from_buffer_endian::<T>(prev_buffer, *offset, endianness)?,
+                            ))
                         })
-                        .filter(|(_, val, prev)| filter.matches(val, prev))
+                        .filter(|(_, val, prev)| matches(val, prev))
                         .map(|(addr, _, _)| *addr)
                         .collect(),
                 );
@@ -222,71 +682,515 @@ impl RegionResults {
                 self.hit_offsets = Some(
                     scan_range
                         .into_par_iter()
-                        // Ensure we don't read any results outside the buffer
-                        .filter(|offset| *offset + size_of_t <= region_buf.len() as u64)
-                        .map(|offset| {
-                            (
+                        .filter(|offset| (base_address + *offset) % alignment == 0)
+                        // Values which don't fit in either buffer can't be hits. Reads may be short
+                        .filter_map(|offset| {
+                            Some((
                                 offset,
-                                read_from_buffer::<T>(&region_buf, offset),
-                                read_from_buffer::<T>(self.buffer.as_ref().unwrap(), offset),
-                            )
+                                try_read_from_buffer_endian::<T>(&region_buf, offset, endianness)?,
+                                try_read_from_buffer_endian::<T>(prev_buffer, offset, endianness)?,
+                            ))
                         })
-                        .filter(|(_, val, prev)| filter.matches(val, prev))
+                        .filter(|(_, val, prev)| matches(val, prev))
                         .map(|(addr, _, _)| addr)
                         .collect(),
                 )
             }
         }
-        if self.hit_offsets.as_ref().is_none() || self.hit_offsets.as_ref().unwrap().len() > 0 {
-            // Only keep track of previous values if we have hits, or haven't scanned yet
-            self.buffer = Some(region_buf)
+        self.store_buffer(region_buf);
+    }
+
+    /// Updates results given a buffer of this regions new memory, and a filter for 3 contiguous floats
+    pub fn update_results_vec3(&mut self, region_buf: Vec<u8>, filter: &Vec3Filter) {
+        let hit_size = 3 * std::mem::size_of::<f32>() as u64;
+        self.hit_size = hit_size;
+        let base_address = self.region.base_address;
+        let alignment = self.alignment.max(1);
+        let endianness = self.endianness;
+        let matches = |offset: u64| {
+            (base_address + offset) % alignment == 0
+                && read_vec3(&region_buf, offset, endianness)
+                    .is_some_and(|value| filter.matches(value))
+        };
+        if let Some(hit_offsets) = self.hit_offsets.as_ref() {
+            // Narrow down existing hits
+            self.hit_offsets = Some(
+                hit_offsets
+                    .into_par_iter()
+                    .filter(|offset| matches(**offset))
+                    .map(|offset| *offset)
+                    .collect(),
+            );
         } else {
-            // Hit offsets length is 0
-            self.buffer = None
+            // First scan, check every offset in the region
+            let scan_range = 0..self.region.size;
+            self.hit_offsets = Some(
+                scan_range
+                    .into_par_iter()
+                    .filter(|offset| matches(*offset))
+                    .collect(),
+            );
+        }
+        self.store_buffer(region_buf);
+    }
+
+    /// Updates results given a buffer of this regions new memory, and an array of bytes pattern
+    ///
+    /// `None` entries in the pattern are wildcards which match any byte
+    pub fn update_results_aob(&mut self, region_buf: Vec<u8>, pattern: &[Option<u8>]) {
+        let pattern_len = pattern.len() as u64;
+        self.hit_size = pattern_len;
+        if let Some(hit_offsets) = self.hit_offsets.as_ref() {
+            // Narrow down existing hits
+            self.hit_offsets = Some(
+                hit_offsets
+                    .into_par_iter()
+                    .filter(|offset| aob_matches(&region_buf, **offset, pattern))
+                    .map(|offset| *offset)
+                    .collect(),
+            );
+        } else {
+            // First scan, check every offset in the region
+            let scan_range = 0..(region_buf.len() as u64 + 1)
+                .saturating_sub(pattern_len)
+                .min(self.region.size);
+            self.hit_offsets = Some(
+                scan_range
+                    .into_par_iter()
+                    .filter(|offset| aob_matches(&region_buf, *offset, pattern))
+                    .collect(),
+            );
+        }
+        self.store_buffer(region_buf);
+    }
+}
+
+/// Splits a region into chunks of at most [`SCAN_CHUNK_SIZE`], each with how far it may read into the next chunk
+fn split_into_chunks(region: MemoryRegion) -> Vec<(MemoryRegion, u64)> {
+    let region_end = region.base_address + region.size;
+    (region.base_address..region_end)
+        .step_by(SCAN_CHUNK_SIZE as usize)
+        .map(|base_address| {
+            let size = SCAN_CHUNK_SIZE.min(region_end - base_address);
+            let overlap = CHUNK_OVERLAP.min(region_end - (base_address + size));
+            (
+                MemoryRegion {
+                    base_address,
+                    size,
+                    protection: region.protection,
+                },
+                overlap,
+            )
+        })
+        .collect()
+}
+
+/// Reads the 3 `f32`s at `offset` in `buffer`, or `None` if they don't fit
+fn read_vec3(buffer: &[u8], offset: u64, endianness: Endianness) -> Option<[f32; 3]> {
+    let size_of_f32 = std::mem::size_of::<f32>() as u64;
+    let component =
+        |i: u64| try_read_from_buffer_endian::<f32>(buffer, offset + i * size_of_f32, endianness);
+    Some([component(0)?, component(1)?, component(2)?])
+}
+
+/// Checks whether `pattern` matches `buffer` at `offset`
+fn aob_matches(buffer: &[u8], offset: u64, pattern: &[Option<u8>]) -> bool {
+    let offset = offset as usize;
+    if offset + pattern.len() > buffer.len() {
+        return false;
+    }
+    buffer[offset..offset + pattern.len()]
+        .iter()
+        .zip(pattern)
+        .all(|(byte, expected)| expected.map_or(true, |expected| *byte == expected))
+}
+
+/// Bytes overwritten by a patch, so they can be written back by [`Scanner::restore_patch`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchHandle {
+    pub address: u64,
+    /// The bytes at `address` before they were patched
+    pub original: Vec<u8>,
+}
+
+/// A chain of pointers which resolves to some address
+///
+/// Resolving reads a pointer `base_module_offset` bytes into `module`, adds the first offset, reads a pointer there,
+/// and so on. The last offset is added to the final pointer to produce the resolved address. Chains start in a
+/// module's static memory, so they still resolve after the process restarts and the module loads somewhere else
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PointerChain {
+    /// Name of the module the first pointer is stored in
+    pub module: String,
+    /// Offset of the first pointer from the base of `module`
+    pub base_module_offset: u64,
+    pub offsets: Vec<u64>,
+}
+
+impl std::fmt::Display for PointerChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}+0x{:x}]", self.module, self.base_module_offset)?;
+        for offset in self.offsets.iter() {
+            write!(f, " -> +0x{:x}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+/// Progress of an in-flight scan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// Number of regions which have been scanned (or skipped) so far
+    pub regions_done: usize,
+    /// Number of regions this scan will go through
+    pub regions_total: usize,
+    /// Number of bytes of memory read so far
+    pub bytes_scanned: u64,
+}
+
+impl ScanProgress {
+    /// Gets how far along the scan is, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.regions_total == 0 {
+            return 1.0;
+        }
+        self.regions_done as f32 / self.regions_total as f32
+    }
+}
+
+/// How a cancellable scan ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanOutcome {
+    Completed,
+    /// Stopped early because it was cancelled. The results are left as they were before the scan
+    Cancelled,
+}
+
+/// Statistics about a completed scan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanStats {
+    /// How long the scan took, including reading memory
+    pub duration: Duration,
+    /// Number of bytes of memory read
+    pub bytes_scanned: u64,
+    /// Number of regions which were read and scanned. Regions which couldn't be read or had no hits left aren't counted
+    pub regions_scanned: usize,
+    /// Number of results before the scan. `None` for the first scan, which starts from all of memory
+    pub results_before: Option<usize>,
+    /// Number of results after the scan
+    pub results_after: usize,
+}
+
+impl std::fmt::Display for ScanStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Scanned {} bytes in {} regions in {:.2?}. ",
+            self.bytes_scanned, self.regions_scanned, self.duration
+        )?;
+        match self.results_before {
+            Some(results_before) => {
+                write!(f, "{} -> {} results", results_before, self.results_after)
+            }
+            None => write!(f, "{} results", self.results_after),
         }
     }
 }
 
 pub struct Scanner {
     process: Arc<dyn Process>,
-    pub results: HashMap<MemoryRegion, RegionResults>,
+    /// Results for each region, in address order
+    ///
+    /// Changing the hits of these directly leaves [`Scanner::count_results`] out of date
+    pub results: BTreeMap<MemoryRegion, RegionResults>,
+    /// Total hits across every region, kept up to date as results change so it can be counted cheaply
+    total_hits: usize,
     is_new_scan: bool,
+    /// Only memory in `[start, end)` is scanned, if set
+    scan_bounds: Option<(u64, u64)>,
+    /// Byte order values are read with
+    endianness: Endianness,
+    /// Alignment of the addresses scans find values at
+    alignment: u64,
+    /// How many values are kept for each hit in `history`. 0 disables history
+    history_capacity: usize,
+    /// The bytes of each hit after each scan and refresh, oldest first
+    history: HashMap<u64, VecDeque<Vec<u8>>>,
+    /// Hits from before each of the last few scans, oldest first
+    undo_stack: VecDeque<ScanUndo>,
+    /// Statistics about the last scan, if it completed
+    last_scan_stats: Option<ScanStats>,
+    /// Regions larger than this many bytes are skipped by new scans
+    max_region_bytes: u64,
+    /// Bytes of snapshots the undo entries before the latest can keep
+    max_undo_bytes: usize,
+    /// Values to send to subscribers when they change. Independent of the results
+    watches: Vec<ValueWatch>,
+    /// Patches written by [`Scanner::nop_patch`] which haven't been restored yet
+    patches: Vec<PatchHandle>,
 }
 
 impl Scanner {
+    /// Group name used by [`Scanner::get_results_grouped`] for results outside every module
+    pub const NO_MODULE: &'static str = "<anonymous>";
+
     pub fn new(process: Arc<dyn Process>) -> Self {
         Self {
             process,
-            results: HashMap::new(),
+            results: BTreeMap::new(),
+            total_hits: 0,
             is_new_scan: true,
+            scan_bounds: None,
+            endianness: Endianness::default(),
+            alignment: 1,
+            history_capacity: 0,
+            history: HashMap::new(),
+            undo_stack: VecDeque::new(),
+            last_scan_stats: None,
+            max_region_bytes: DEFAULT_MAX_REGION_BYTES,
+            max_undo_bytes: DEFAULT_MAX_UNDO_BYTES,
+            watches: vec![],
+            patches: vec![],
         }
     }
 
-    /// Count the number of results so far
-    pub fn count_results(&self) -> Option<usize> {
+    /// Sets the byte order values are read with, for both scanning and getting results
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+        self.results
+            .values_mut()
+            .for_each(|results| results.set_endianness(endianness));
+    }
+
+    /// Only finds values at addresses which are a multiple of `alignment`, which is faster and avoids misaligned hits
+    ///
+    /// Defaults to 1, allowing any address. Applies to existing results from the next scan, except manually added addresses
+    pub fn set_alignment(&mut self, alignment: u64) {
+        self.alignment = alignment.max(1);
+        let alignment = self.alignment;
+        self.results
+            .values_mut()
+            .filter(|results| !results.manual)
+            .for_each(|results| results.set_alignment(alignment));
+    }
+
+    /// Keeps the last `capacity` values of each hit, recorded after every scan and [`Scanner::refresh_values`]
+    ///
+    /// Nothing is recorded while there are too many hits to track, such as after a first unknown value scan.
+    /// A capacity of 0 disables history
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history_capacity = capacity;
+        if capacity == 0 {
+            self.history.clear();
+        }
+        for values in self.history.values_mut() {
+            // Drop the oldest values past the new capacity
+            values.drain(..values.len().saturating_sub(capacity));
+        }
+    }
+
+    /// Gets the recorded values of the hit at `address`, oldest first
+    pub fn value_history<T: Copy>(&self, address: u64) -> Vec<T> {
+        let size_of_t = std::mem::size_of::<T>();
+        self.history
+            .get(&address)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter(|bytes| bytes.len() >= size_of_t)
+                    .map(|bytes| read_from_buffer_endian::<T>(bytes, 0, self.endianness))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Appends the current value of each hit to its history, dropping the history of addresses which are no longer hits
+    fn record_history(&mut self) {
+        if self.history_capacity == 0 {
+            return;
+        }
+        if self.is_new_scan || self.total_hits > HISTORY_MAX_HITS {
+            self.history.clear();
+            return;
+        }
+        let mut history = HashMap::with_capacity(self.total_hits);
+        for results in self.results.values() {
+            let Some(buffer) = results.display_buffer() else {
+                continue;
+            };
+            for offset in results.hits().iter() {
+                let start = *offset as usize;
+                let end = (start + results.hit_size.max(1) as usize).min(buffer.len());
+                let address = results.region.base_address + offset;
+                let mut values = self.history.remove(&address).unwrap_or_default();
+                if values.len() >= self.history_capacity {
+                    values.pop_front();
+                }
+                values.push_back(buffer[start..end].to_vec());
+                history.insert(address, values);
+            }
+        }
+        self.history = history;
+    }
+
+    /// Restricts scanning to memory in `[start, end)`
+    ///
+    /// The bounds are applied when a new scan starts. Subsequent scans narrow down the memory chosen by the first scan
+    pub fn set_scan_bounds(&mut self, start: u64, end: u64) {
+        self.scan_bounds = Some((start, end));
+    }
+
+    /// Removes results whose address is outside `[start, end)`, such as to keep only results in one module
+    ///
+    /// Unlike scan bounds, this applies to the current results and doesn't read memory. It can be undone like a scan
+    pub fn filter_results_by_address(&mut self, start: u64, end: u64) -> Result<()> {
         if self.is_new_scan {
-            return None;
+            anyhow::bail!("There are no results to filter yet");
+        }
+        self.push_undo();
+        for results in self.results.values_mut() {
+            let base_address = results.region.base_address;
+            if results.hit_offsets.is_some() || results.buffer.is_some() {
+                results
+                    .hit_offsets_mut()
+                    .retain(|offset| (start..end).contains(&(base_address + offset)));
+            }
+        }
+        self.total_hits = self
+            .results
+            .values()
+            .map(|results| results.hit_count())
+            .sum();
+        self.history
+            .retain(|address, _| (start..end).contains(address));
+        Ok(())
+    }
+
+    /// Removes results whose value, read as a 64-bit pointer, doesn't point into readable memory of the process
+    ///
+    /// Values come from the last scan, and are checked against the regions the process has now. Useful for narrowing
+    /// down results to pointers before walking them. It can be undone like a scan
+    pub fn filter_pointers(&mut self) -> Result<()> {
+        if self.is_new_scan {
+            anyhow::bail!("There are no results to filter yet");
+        }
+        let mut regions = self.process.get_readable_regions();
+        regions.sort();
+        let is_readable_address = |address: u64| {
+            // Only the last region starting at or before the address can contain it
+            let index = regions.partition_point(|region| region.base_address <= address);
+            index > 0 && address - regions[index - 1].base_address < regions[index - 1].size
+        };
+
+        self.push_undo();
+        for results in self.results.values_mut() {
+            let endianness = results.endianness;
+            if results.buffer.is_some() {
+                results.hit_offsets_mut();
+            }
+            // Borrowed field by field rather than with `display_buffer`, so the hits can be changed
+            let buffer = results.live_buffer.as_ref().or(results.buffer.as_deref());
+            let (Some(hit_offsets), Some(buffer)) = (results.hit_offsets.as_mut(), buffer) else {
+                continue;
+            };
+            hit_offsets.retain(|offset| {
+                try_read_from_buffer_endian::<u64>(buffer, *offset, endianness)
+                    .is_some_and(is_readable_address)
+            });
         }
-        let hit_offsets: Vec<&Vec<u64>> = self
+        self.total_hits = self
             .results
             .values()
+            .map(|results| results.hit_count())
+            .sum();
+        let history = std::mem::take(&mut self.history);
+        self.history = history
             .into_iter()
-            .map(|result| result.hit_offsets.as_ref())
-            .filter(|hit_offsets| hit_offsets.is_some())
-            .map(|hit_offsets| hit_offsets.unwrap())
+            .filter(|(address, _)| self.is_hit(*address))
             .collect();
+        Ok(())
+    }
+
+    /// Skips regions larger than `max_region_bytes` from the next new scan. Defaults to 512 MiB
+    ///
+    /// Processes can map enormous regions which are mostly never touched, and reading them takes a long time
+    pub fn set_max_region_bytes(&mut self, max_region_bytes: u64) {
+        self.max_region_bytes = max_region_bytes;
+    }
+
+    /// Limits the bytes of snapshots kept to undo scans other than the last. Defaults to 256 MiB
+    ///
+    /// Once over the limit, the oldest undo entries only keep their hits. Undoing them restores the hits with the latest
+    /// snapshot of each region, reading regions again which no longer have one
+    pub fn set_max_undo_bytes(&mut self, max_undo_bytes: usize) {
+        self.max_undo_bytes = max_undo_bytes;
+        self.limit_undo_snapshots();
+    }
+
+    /// Allows scanning all writable memory again, from the next new scan
+    pub fn clear_scan_bounds(&mut self) {
+        self.scan_bounds = None;
+    }
+
+    /// Gets the process this scanner is bound to
+    pub fn process(&self) -> Arc<dyn Process> {
+        self.process.clone()
+    }
+
+    /// Gets the writable regions of the process, clamped to the scan bounds, without regions over the size limit, and split into chunks
+    ///
+    /// Each chunk is returned with how many bytes past its end should also be read
+    fn regions_to_scan(&self) -> Vec<(MemoryRegion, u64)> {
+        let regions = self.process.get_writable_regions();
+        let regions = match self.scan_bounds {
+            Some((start, end)) => regions
+                .into_iter()
+                .filter_map(|region| {
+                    let base_address = region.base_address.max(start);
+                    let region_end = (region.base_address + region.size).min(end);
+                    if base_address < region_end {
+                        Some(MemoryRegion {
+                            base_address,
+                            size: region_end - base_address,
+                            protection: region.protection,
+                        })
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            None => regions,
+        };
+        regions
+            .into_iter()
+            .filter(|region| {
+                let too_large = region.size > self.max_region_bytes;
+                if too_large {
+                    tracing::warn!(
+                        "Skipping {} byte region at 0x{:016x}, which is larger than the {} byte limit",
+                        region.size,
+                        region.base_address,
+                        self.max_region_bytes
+                    );
+                }
+                !too_large
+            })
+            .flat_map(split_into_chunks)
+            .collect()
+    }
+
+    /// Gets statistics about the last scan. `None` if there hasn't been a scan, or the last one was cancelled
+    pub fn last_scan_stats(&self) -> Option<ScanStats> {
+        self.last_scan_stats
+    }
 
-        if hit_offsets.len() == 0 {
+    /// Count the number of results so far
+    pub fn count_results(&self) -> Option<usize> {
+        if self.is_new_scan {
             // We have not yet scanned anything
             return None;
         }
-
-        Some(
-            hit_offsets
-                .iter()
-                .map(|hit_offsets| hit_offsets.len())
-                .sum(),
-        )
+        Some(self.total_hits)
     }
 
     /// Gets all scan results
@@ -303,21 +1207,210 @@ impl Scanner {
             .collect()
     }
 
-    /// Gets first `n` scan results
-    pub fn get_first_results<T>(&self, n: usize) -> Vec<(u64, T)>
+    /// Whether `address` is still one of the scan results, such as a manually added address which has survived scans
+    pub fn is_hit(&self, address: u64) -> bool {
+        // Only regions starting at or before the address can contain it
+        let last_candidate = MemoryRegion {
+            base_address: address,
+            size: u64::MAX,
+            protection: Protection::all(),
+        };
+        // Scanned regions don't overlap, so none before the nearest one which doesn't contain the address can either.
+        // Manually added addresses can overlap them, so the search carries on past those
+        self.results
+            .range(..=last_candidate)
+            .rev()
+            .take_while(|(region, results)| {
+                results.manual || address - region.base_address < region.size
+            })
+            .filter(|(region, _)| address - region.base_address < region.size)
+            .any(|(region, results)| results.is_hit(address - region.base_address))
+    }
+
+    /// Gets the scan results within `region`, without reading the results of any other region
+    ///
+    /// Large regions are scanned in chunks, so `region` doesn't have to match a scanned region exactly.
+    /// Returns `None` if no scanned region overlaps `region`
+    pub fn get_results_for_region<T>(&self, region: &MemoryRegion) -> Option<Vec<(u64, T)>>
     where
         T: Copy + Send + Sync,
     {
-        self.results
-            .values()
-            .into_iter()
-            .map(|results| results.get_results::<T>())
-            .filter(|results| results.is_some())
-            .flat_map(|results| results.unwrap())
+        let region_end = region.base_address + region.size;
+        let mut overlapping = self
+            .results
+            .iter()
+            .filter(|(scanned, _)| {
+                scanned.base_address < region_end
+                    && region.base_address < scanned.base_address + scanned.size
+            })
+            .peekable();
+        overlapping.peek()?;
+        Some(
+            overlapping
+                .filter_map(|(_, results)| results.get_results::<T>())
+                .flatten()
+                .filter(|(address, _)| *address >= region.base_address && *address < region_end)
+                .collect(),
+        )
+    }
+
+    /// Groups result addresses into clusters where each address is less than `window` bytes after the previous one
+    ///
+    /// Fields of the same struct are usually close together, so large clusters hint at where structs are.
+    /// Clusters are ordered largest first, then by address
+    pub fn cluster_results(&self, window: u64) -> Vec<Vec<u64>> {
+        let mut addresses: Vec<u64> = Vec::with_capacity(self.total_hits);
+        for results in self.results.values() {
+            let base_address = results.region.base_address;
+            addresses.extend(results.hits().iter().map(|offset| base_address + offset));
+        }
+        addresses.sort_unstable();
+        let mut clusters: Vec<Vec<u64>> = vec![];
+        for address in addresses {
+            match clusters.last_mut() {
+                Some(cluster) if address - cluster[cluster.len() - 1] < window => {
+                    cluster.push(address)
+                }
+                _ => clusters.push(vec![address]),
+            }
+        }
+        // Stable, so clusters of the same size stay in address order
+        clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.len()));
+        clusters
+    }
+
+    /// Gets all scan results as another type of the same size as the values scanned for, such as `i32` after scanning for `u32`
+    ///
+    /// Fails if the size of `T` differs from the size of the scanned values, since the hits wouldn't line up
+    pub fn get_results_as<T>(&self) -> Result<Vec<(u64, T)>>
+    where
+        T: Copy + Send + Sync,
+    {
+        let size_of_t = std::mem::size_of::<T>() as u64;
+        if let Some(hit_size) = self
+            .results
+            .values()
+            .filter(|results| results.hit_count() > 0)
+            .map(|results| results.hit_size)
+            .find(|hit_size| *hit_size != size_of_t)
+        {
+            anyhow::bail!(
+                "Results are {} byte values, so can't be read as {} byte values",
+                hit_size,
+                size_of_t
+            );
+        }
+        Ok(self.get_results::<T>())
+    }
+
+    /// Gets all scan results as `(address, current value, previous value)`
+    pub fn get_results_with_prev<T>(&self) -> Vec<(u64, T, T)>
+    where
+        T: Copy + Send + Sync,
+    {
+        self.results
+            .values()
+            .into_iter()
+            .map(|results| results.get_results_with_prev::<T>())
+            .filter(|results| results.is_some())
+            .flat_map(|results| results.unwrap())
+            .collect()
+    }
+
+    /// Gets all scan results, grouped by the name of the module each address falls inside
+    ///
+    /// Results outside every module are grouped under [`Scanner::NO_MODULE`]
+    pub fn get_results_grouped<T>(&self) -> HashMap<String, Vec<(u64, T)>>
+    where
+        T: Copy + Send + Sync,
+    {
+        let modules = self.process.get_modules();
+        let mut grouped: HashMap<String, Vec<(u64, T)>> = HashMap::new();
+        for (address, value) in self.get_results::<T>() {
+            let name = match find_module(&modules, address) {
+                Some(module) => module.name.clone(),
+                None => Self::NO_MODULE.to_string(),
+            };
+            grouped.entry(name).or_default().push((address, value));
+        }
+        grouped
+    }
+
+    /// Gets the modules mapped into the process being scanned
+    pub fn modules(&self) -> Vec<Module> {
+        self.process.get_modules()
+    }
+
+    /// Gets first `n` scan results
+    pub fn get_first_results<T>(&self, n: usize) -> Vec<(u64, T)>
+    where
+        T: Copy + Send + Sync,
+    {
+        self.results
+            .values()
+            .into_iter()
+            .map(|results| results.get_results::<T>())
+            .filter(|results| results.is_some())
+            .flat_map(|results| results.unwrap())
             .take(n)
             .collect()
     }
 
+    /// Gets up to `limit` results, starting from the result at index `offset`, ordered by address
+    ///
+    /// Unlike getting a range of results, the results before `offset` are skipped without being read
+    pub fn results_page<T>(&self, offset: usize, limit: usize) -> Vec<(u64, T)>
+    where
+        T: Copy + Send + Sync,
+    {
+        let regions = self
+            .results
+            .values()
+            .filter(|results| results.display_buffer().is_some());
+
+        let mut to_skip = offset;
+        let mut page = Vec::with_capacity(limit);
+        for results in regions {
+            if page.len() >= limit {
+                break;
+            }
+            let hit_count = results.hit_count();
+            if to_skip >= hit_count {
+                to_skip -= hit_count;
+                continue;
+            }
+            let buffer = results.display_buffer().unwrap();
+            // Results which don't fit in the buffer are skipped
+            let read = |offset: u64| {
+                Some((
+                    offset + results.region.base_address,
+                    try_read_from_buffer_endian::<T>(buffer, offset, results.endianness)?,
+                ))
+            };
+            let remaining = limit - page.len();
+            match &results.hit_offsets {
+                Some(hit_offsets) => page.extend(
+                    hit_offsets[to_skip..]
+                        .iter()
+                        .filter_map(|offset| read(*offset))
+                        .take(remaining),
+                ),
+                None => {
+                    // Every aligned offset is a hit, so the skipped ones are never made
+                    let (first, count) = results.every_aligned_offset();
+                    let alignment = results.alignment.max(1);
+                    page.extend(
+                        (to_skip as u64..count as u64)
+                            .filter_map(|i| read(first + i * alignment))
+                            .take(remaining),
+                    );
+                }
+            }
+            to_skip = 0;
+        }
+        page
+    }
+
     /// Gets the result at a given index
     /// Warning: This is bad for performance, prefer getting a range of results
     pub fn get_nth_result<T>(&self, n: usize) -> Option<(u64, T)>
@@ -349,10 +1442,403 @@ impl Scanner {
             .collect()
     }
 
+    /// Adds a known address to the results, as if a scan had found a `T` there
+    ///
+    /// The address is narrowed down by later scans like any other result, and is removed by a new scan
+    pub fn add_manual_address<T: Copy>(&mut self, address: u64) -> Result<()> {
+        let size_of_t = std::mem::size_of::<T>() as u64;
+        let memory = self
+            .process
+            .read_memory_bytes(address, size_of_t as usize)?;
+        if (memory.len() as u64) < size_of_t {
+            anyhow::bail!("Failed to read a value at 0x{:016x}", address);
+        }
+        let region = MemoryRegion {
+            base_address: address,
+            size: size_of_t,
+            protection: Protection::empty(),
+        };
+        let mut results = RegionResults::new(region);
+        results.set_endianness(self.endianness);
+        results.hit_offsets = Some(vec![0]);
+        results.hit_size = size_of_t;
+        results.buffer = Some(Arc::new(memory));
+        results.manual = true;
+        self.total_hits += 1;
+        if let Some(replaced) = self.results.insert(region, results) {
+            self.total_hits -= replaced.hit_count();
+        }
+        Ok(())
+    }
+
+    /// Starts a new scan whose only result is the `T` at a known address
+    ///
+    /// Unlike `add_manual_address`, later scans narrow down this address instead of scanning all memory again
+    pub fn seed_address<T: Copy>(&mut self, address: u64) -> Result<()> {
+        self.new_scan();
+        self.add_manual_address::<T>(address)?;
+        self.is_new_scan = false;
+        Ok(())
+    }
+
+    /// Takes a snapshot of all writable memory within the scan bounds, independent of the scan results
+    pub fn snapshot(&self) -> Result<Snapshot> {
+        let regions: Vec<MemoryRegion> = self
+            .regions_to_scan()
+            .into_iter()
+            .map(|(region, _)| region)
+            .collect();
+        Snapshot::capture(self.process.as_ref(), &regions)
+    }
+
+    /// Re-reads the memory at each hit, so results reflect the current values in memory without narrowing them down
+    ///
+    /// Only the memory around hits is read, which is much cheaper than an `Unchanged` scan.
+    /// Memory which can no longer be read keeps its last known values. The next scan still compares with the values found by the last scan
+    pub fn refresh_values(&mut self) -> Result<()> {
+        for results in self.results.values_mut() {
+            let Some(buffer) = &results.buffer else {
+                continue;
+            };
+            // The snapshot is left alone, as the next scan compares with it
+            let live_buffer = results.live_buffer.get_or_insert_with(|| buffer.to_vec());
+            let hit_size = results.hit_size.max(1);
+            // Group nearby hits into spans of `[start, end)` offsets, each read with one read
+            let mut spans: Vec<(u64, u64)> = vec![];
+            match &results.hit_offsets {
+                Some(hit_offsets) => {
+                    for offset in hit_offsets {
+                        let end = (*offset + hit_size).min(buffer.len() as u64);
+                        match spans.last_mut() {
+                            Some((span_start, span_end))
+                                if *offset >= *span_start
+                                    && *offset <= *span_end + REFRESH_MERGE_GAP =>
+                            {
+                                *span_end = end.max(*span_end);
+                            }
+                            _ => spans.push((*offset, end)),
+                        }
+                    }
+                }
+                // Every aligned offset is a hit, so the whole snapshot is read again
+                None => spans.push((0, buffer.len() as u64)),
+            }
+            for (start, end) in spans {
+                if start >= end {
+                    continue;
+                }
+                if let Ok(memory) = self
+                    .process
+                    .read_memory_bytes(results.region.base_address + start, (end - start) as usize)
+                {
+                    live_buffer[start as usize..start as usize + memory.len()]
+                        .copy_from_slice(&memory);
+                }
+            }
+        }
+        self.record_history();
+        self.check_watches();
+        Ok(())
+    }
+
+    /// Sends `(address, value)` to `tx` whenever the value at `address` changes, checked on each [`Scanner::refresh_values`]
+    ///
+    /// The value at `address` when this is called is the first value changes are compared against.
+    /// The watch is removed once `tx` is disconnected, or with [`Scanner::unwatch_value`]
+    pub fn watch_value<T>(&mut self, address: u64, tx: Sender<(u64, T)>)
+    where
+        T: Copy + Send + 'static,
+    {
+        let size = std::mem::size_of::<T>();
+        let endianness = self.endianness;
+        self.watches.push(ValueWatch {
+            address,
+            size,
+            last: self
+                .process
+                .read_memory_bytes(address, size)
+                .ok()
+                .filter(|bytes| bytes.len() == size),
+            notify: Box::new(move |bytes| {
+                tx.send((address, read_from_buffer_endian::<T>(bytes, 0, endianness)))
+                    .is_ok()
+            }),
+        });
+    }
+
+    /// Stops watching `address` for changes
+    pub fn unwatch_value(&mut self, address: u64) {
+        self.watches.retain(|watch| watch.address != address);
+    }
+
+    /// Notifies the subscriber of each watched value which has changed since it was last checked
+    fn check_watches(&mut self) {
+        let process = self.process.clone();
+        self.watches.retain_mut(|watch| {
+            let Ok(bytes) = process.read_memory_bytes(watch.address, watch.size) else {
+                return true;
+            };
+            if bytes.len() != watch.size || watch.last.as_ref() == Some(&bytes) {
+                return true;
+            }
+            let subscribed = (watch.notify)(&bytes);
+            watch.last = Some(bytes);
+            subscribed
+        });
+    }
+
+    /// Reads `len` bytes of the scanned process starting at `address`, such as to look at the memory around a result
+    pub fn read_region(&self, address: u64, len: usize) -> Result<Vec<u8>> {
+        self.process.read_memory_bytes(address, len)
+    }
+
+    /// Writes `value` to `address` in the scanned process
+    pub fn write_result<T: Copy>(&self, address: u64, value: T) -> Result<()> {
+        self.process.write_memory(address, value)
+    }
+
+    /// Writes `bytes` to `address` in the scanned process, such as to patch instructions with NOPs
+    ///
+    /// The bytes are read back afterwards, and it fails if they don't match. Code is usually mapped read-only,
+    /// in which case the write fails on platforms which respect page protection
+    pub fn write_bytes(&self, address: u64, bytes: &[u8]) -> Result<()> {
+        self.process.write_memory_bytes(address, bytes)?;
+        let written = self.process.read_memory_bytes(address, bytes.len())?;
+        if written != bytes {
+            anyhow::bail!(
+                "Wrote {} bytes to 0x{:016x}, but different bytes were read back",
+                bytes.len(),
+                address
+            );
+        }
+        Ok(())
+    }
+
+    /// Overwrites `len` bytes at `address` with x86 NOP instructions, returning a handle to restore them with
+    ///
+    /// `len` should cover whole instructions, or the bytes after the patch decode as garbage.
+    /// [`crate::disasm::whole_instructions_len`] rounds a length up to whole instructions
+    pub fn nop_patch(&mut self, address: u64, len: usize) -> Result<PatchHandle> {
+        if len == 0 {
+            anyhow::bail!("Expected at least one byte to patch");
+        }
+        let original = self.process.read_memory_bytes(address, len)?;
+        if original.len() != len {
+            anyhow::bail!("Failed to read {} bytes at 0x{:016x}", len, address);
+        }
+        self.write_bytes(address, &vec![X86_NOP; len])?;
+        let handle = PatchHandle { address, original };
+        self.patches.push(handle.clone());
+        Ok(handle)
+    }
+
+    /// Writes back the bytes a patch overwrote
+    pub fn restore_patch(&mut self, handle: &PatchHandle) -> Result<()> {
+        self.write_bytes(handle.address, &handle.original)?;
+        self.patches.retain(|patch| patch != handle);
+        Ok(())
+    }
+
+    /// Restores every patch which hasn't been restored yet, newest first so overlapping patches unwind correctly
+    ///
+    /// Patches which fail to restore are kept, and the first error is returned
+    pub fn restore_all_patches(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for handle in std::mem::take(&mut self.patches).into_iter().rev() {
+            if let Err(err) = self.write_bytes(handle.address, &handle.original) {
+                self.patches.insert(0, handle);
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+
+    /// Gets the patches which haven't been restored yet, oldest first
+    pub fn active_patches(&self) -> &[PatchHandle] {
+        &self.patches
+    }
+
+    /// Saves the scan results to `path`, so the session can be resumed later with [`Scanner::load_session`]
+    pub fn save_session(&self, path: &Path) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(SESSION_MAGIC)?;
+        writer.write_all(&SESSION_FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(
+            writer,
+            &(
+                &self.results,
+                self.is_new_scan,
+                self.scan_bounds,
+                self.endianness,
+                self.alignment,
+            ),
+        )?;
+        Ok(())
+    }
+
+    /// Loads a session saved with [`Scanner::save_session`], bound to `process`
+    ///
+    /// `process` should be a fresh attachment to the same process the session was saved from. Sessions saved by a
+    /// different version of the format can't be loaded
+    pub fn load_session(path: &Path, process: Arc<dyn Process>) -> Result<Scanner> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != SESSION_MAGIC {
+            anyhow::bail!("Not a session file, or one saved before session files were versioned");
+        }
+        let version = u32::from_le_bytes(header[4..].try_into().unwrap());
+        if version != SESSION_FORMAT_VERSION {
+            anyhow::bail!(
+                "Session file format version {} isn't supported, expected version {}",
+                version,
+                SESSION_FORMAT_VERSION
+            );
+        }
+        let (results, is_new_scan, scan_bounds, endianness, alignment): (
+            BTreeMap<MemoryRegion, RegionResults>,
+            bool,
+            Option<(u64, u64)>,
+            Endianness,
+            u64,
+        ) = bincode::deserialize_from(reader)?;
+        let total_hits = results.values().map(|results| results.hit_count()).sum();
+        Ok(Self {
+            process,
+            results,
+            total_hits,
+            is_new_scan,
+            scan_bounds,
+            endianness,
+            alignment,
+            history_capacity: 0,
+            history: HashMap::new(),
+            undo_stack: VecDeque::new(),
+            last_scan_stats: None,
+            max_region_bytes: DEFAULT_MAX_REGION_BYTES,
+            max_undo_bytes: DEFAULT_MAX_UNDO_BYTES,
+            watches: vec![],
+            patches: vec![],
+        })
+    }
+
     /// Clears all results and initializes the scanner for the first scan
     pub fn new_scan(&mut self) {
         self.results.clear();
+        self.total_hits = 0;
         self.is_new_scan = true;
+        self.history.clear();
+        self.undo_stack.clear();
+    }
+
+    /// Restores the hits and values from before the last scan
+    ///
+    /// Only the last few scans can be undone
+    pub fn undo_scan(&mut self) -> Result<()> {
+        let Some(undo) = self.undo_stack.pop_back() else {
+            anyhow::bail!("No scans to undo");
+        };
+        self.restore_undo(undo);
+        self.history.clear();
+        Ok(())
+    }
+
+    /// Puts the results back how they were when `undo` was saved
+    fn restore_undo(&mut self, undo: ScanUndo) {
+        if undo.is_new_scan {
+            self.results.clear();
+        } else {
+            // Drop regions added since, such as manually added addresses
+            self.results
+                .retain(|region, _| undo.regions.contains_key(region));
+            for (region, region_undo) in undo.regions {
+                if let Some(results) = self.results.get_mut(&region) {
+                    results.hit_offsets = region_undo.hit_offsets;
+                    results.hit_size = region_undo.hit_size;
+                    results.live_buffer = None;
+                    if !region_undo.snapshots_dropped {
+                        results.buffer = region_undo.buffer;
+                        results.prev_buffer = region_undo.prev_buffer;
+                    } else if results.buffer.is_none() {
+                        // The scan dropped this region's snapshot, so the restored hits need a new one
+                        results.buffer = self
+                            .process
+                            .read_memory_bytes(
+                                region.base_address,
+                                (region.size + results.overlap) as usize,
+                            )
+                            .ok()
+                            .map(Arc::new);
+                    }
+                }
+            }
+        }
+        self.total_hits = self
+            .results
+            .values()
+            .map(|results| results.hit_count())
+            .sum();
+        self.is_new_scan = undo.is_new_scan;
+    }
+
+    /// Saves the current hits and snapshots, so the next scan can be undone
+    fn push_undo(&mut self) {
+        if self.undo_stack.len() >= MAX_UNDO_SCANS {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(ScanUndo {
+            regions: self
+                .results
+                .iter()
+                .map(|(region, results)| {
+                    (
+                        *region,
+                        RegionUndo {
+                            hit_offsets: results.hit_offsets.clone(),
+                            hit_size: results.hit_size,
+                            buffer: results.buffer.clone(),
+                            prev_buffer: results.prev_buffer.clone(),
+                            snapshots_dropped: false,
+                        },
+                    )
+                })
+                .collect(),
+            is_new_scan: self.is_new_scan,
+        });
+        self.limit_undo_snapshots();
+    }
+
+    /// Drops snapshots from the oldest undo entries until those before the latest keep at most `max_undo_bytes`
+    ///
+    /// The latest entry keeps its snapshots, as cancelling a scan restores it
+    fn limit_undo_snapshots(&mut self) {
+        let older = self.undo_stack.len().saturating_sub(1);
+        for oldest in 0..older {
+            if self.undo_bytes_retained(older) <= self.max_undo_bytes {
+                break;
+            }
+            for region_undo in self.undo_stack[oldest].regions.values_mut() {
+                region_undo.buffer = None;
+                region_undo.prev_buffer = None;
+                region_undo.snapshots_dropped = true;
+            }
+        }
+    }
+
+    /// Bytes of snapshots kept by the first `entries` undo entries. Snapshots shared by several entries are counted once
+    fn undo_bytes_retained(&self, entries: usize) -> usize {
+        let mut counted = HashSet::new();
+        self.undo_stack
+            .iter()
+            .take(entries)
+            .flat_map(|undo| undo.regions.values())
+            .flat_map(|region_undo| [&region_undo.buffer, &region_undo.prev_buffer])
+            .flatten()
+            .filter(|buffer| counted.insert(Arc::as_ptr(buffer)))
+            .map(|buffer| buffer.len())
+            .sum()
     }
 
     /// Narrows down `results` (initally None, which means everything) based on the given value
@@ -365,44 +1851,2036 @@ impl Scanner {
             + PartialOrd
             + PartialEq
             + std::ops::Sub<Output = T>
-            + std::ops::Add<Output = T>,
+            + std::ops::Add<Output = T>
+            + RoundTo
+            + ToF64
+            + DivisibleBy,
     {
-        println!("Performing scan with filter: {:?}", filter);
-        let regions = self.process.get_writable_regions();
+        self.scan_with_progress(filter, |_| {})
+    }
+
+    /// Same as `scan`, but stops early once `cancel` is set
+    ///
+    /// See `scan_cancellable_with_progress` for the state the scanner is left in when cancelled
+    pub fn scan_cancellable<T>(
+        &mut self,
+        filter: ScanFilter<T>,
+        cancel: &AtomicBool,
+    ) -> Result<ScanOutcome>
+    where
+        T: Copy
+            + std::fmt::Debug
+            + Send
+            + Sync
+            + PartialOrd
+            + PartialEq
+            + std::ops::Sub<Output = T>
+            + std::ops::Add<Output = T>
+            + RoundTo
+            + ToF64
+            + DivisibleBy,
+    {
+        self.scan_cancellable_with_progress(filter, cancel, |_| {})
+    }
+
+    /// Same as `scan`, but calls `progress` each time a region has been scanned
+    pub fn scan_with_progress<T>(
+        &mut self,
+        filter: ScanFilter<T>,
+        progress: impl Fn(ScanProgress),
+    ) -> Result<()>
+    where
+        T: Copy
+            + std::fmt::Debug
+            + Send
+            + Sync
+            + PartialOrd
+            + PartialEq
+            + std::ops::Sub<Output = T>
+            + std::ops::Add<Output = T>
+            + RoundTo
+            + ToF64
+            + DivisibleBy,
+    {
+        self.scan_cancellable_with_progress(filter, &AtomicBool::new(false), progress)?;
+        Ok(())
+    }
+
+    /// Same as `scan`, but calls `progress` each time a region has been scanned, and stops early once `cancel` is set
+    ///
+    /// `cancel` is checked between regions. A cancelled scan puts the results back how they were before it, as if it
+    /// never happened, and returns [`ScanOutcome::Cancelled`]
+    pub fn scan_cancellable_with_progress<T>(
+        &mut self,
+        filter: ScanFilter<T>,
+        cancel: &AtomicBool,
+        progress: impl Fn(ScanProgress),
+    ) -> Result<ScanOutcome>
+    where
+        T: Copy
+            + std::fmt::Debug
+            + Send
+            + Sync
+            + PartialOrd
+            + PartialEq
+            + std::ops::Sub<Output = T>
+            + std::ops::Add<Output = T>
+            + RoundTo
+            + ToF64
+            + DivisibleBy,
+    {
+        tracing::debug!(?filter, "Performing scan");
+        self.scan_regions(
+            |region_results, region_memory| region_results.update_results(region_memory, &filter),
+            &progress,
+            cancel,
+        )
+    }
+
+    /// Narrows down `results` to values where `predicate(new value, old value)` is true, for filters `ScanFilter` can't express
+    ///
+    /// The predicate is called from many threads at once, in no particular order, so it must be `Sync` and shouldn't
+    /// depend on anything but its arguments. On the first scan there are no old values, so it is given the new value as both
+    pub fn scan_predicate<T, F>(&mut self, predicate: F) -> Result<()>
+    where
+        T: Copy + Send + Sync,
+        F: Fn(&T, &T) -> bool + Sync,
+    {
+        self.scan_regions(
+            |region_results, region_memory| {
+                region_results.update_results_with(region_memory, true, &predicate)
+            },
+            &|_| {},
+            &AtomicBool::new(false),
+        )?;
+        Ok(())
+    }
+
+    /// Narrows down `results` to values equal to the value currently at `reference`, such as to find copies of a value
+    ///
+    /// The reference is read again on every call, so later calls follow it as it changes. Unless `reference` is
+    /// outside the scanned memory, it is one of the results too
+    pub fn scan_same_as<T>(&mut self, reference: u64) -> Result<()>
+    where
+        T: Copy
+            + std::fmt::Debug
+            + Send
+            + Sync
+            + PartialOrd
+            + PartialEq
+            + std::ops::Sub<Output = T>
+            + std::ops::Add<Output = T>
+            + RoundTo
+            + ToF64
+            + DivisibleBy,
+    {
+        let memory = self
+            .process
+            .read_memory_bytes(reference, std::mem::size_of::<T>())?;
+        let value = try_read_from_buffer_endian::<T>(&memory, 0, self.endianness)
+            .ok_or_else(|| anyhow::anyhow!("Failed to read the value at 0x{:016x}", reference))?;
+        self.scan(ScanFilter::Exact(value))
+    }
+
+    /// Narrows down `results` to addresses matching the given array of bytes pattern
+    ///
+    /// `None` entries in the pattern are wildcards. See `util::parse_aob`
+    pub fn scan_aob(&mut self, pattern: &[Option<u8>]) -> Result<()> {
+        if pattern.is_empty() {
+            anyhow::bail!("AOB pattern cannot be empty");
+        }
+        tracing::debug!(?pattern, "Performing AOB scan");
+        self.scan_regions(
+            |region_results, region_memory| {
+                region_results.update_results_aob(region_memory, pattern)
+            },
+            &|_| {},
+            &AtomicBool::new(false),
+        )?;
+        Ok(())
+    }
+
+    /// Narrows down `results` to addresses of 3 contiguous floats matching `filter`, such as a position
+    pub fn scan_vec3(&mut self, filter: &Vec3Filter) -> Result<()> {
+        if filter
+            .components
+            .iter()
+            .all(|component| component.is_none())
+        {
+            anyhow::bail!("At least one component of a vector scan must be known");
+        }
+        self.scan_regions(
+            |region_results, region_memory| {
+                region_results.update_results_vec3(region_memory, filter)
+            },
+            &|_| {},
+            &AtomicBool::new(false),
+        )?;
+        Ok(())
+    }
+
+    /// Gets all results of a vector scan as `(address, [x, y, z])`
+    pub fn get_results_vec3(&self) -> Vec<(u64, [f32; 3])> {
+        let results = self.get_results::<[f32; 3]>();
+        if self.endianness == Endianness::native() {
+            return results;
+        }
+        // Byte swapping the whole array swaps each component, but also reverses their order
+        results
+            .into_iter()
+            .map(|(address, [z, y, x])| (address, [x, y, z]))
+            .collect()
+    }
+
+    /// Narrows down `results` to addresses where the given string is stored with the given encoding
+    ///
+    /// Matches never straddle two separate regions
+    pub fn scan_string(&mut self, needle: &str, encoding: StringEncoding) -> Result<()> {
+        let needle_bytes = encoding.encode(needle)?;
+        if needle_bytes.is_empty() {
+            anyhow::bail!("Cannot scan for an empty string");
+        }
+        tracing::debug!(?encoding, needle, "Performing string scan");
+        let pattern: Vec<Option<u8>> = needle_bytes.into_iter().map(Some).collect();
+        self.scan_regions(
+            |region_results, region_memory| {
+                region_results.update_results_aob(region_memory, &pattern)
+            },
+            &|_| {},
+            &AtomicBool::new(false),
+        )?;
+        Ok(())
+    }
+
+    /// Finds pointer chains of at most `max_depth` pointers which resolve to `target`
+    ///
+    /// Each pointer in a chain may point at most `max_offset` bytes before the next address in the chain.
+    /// Only 8-byte aligned pointers into writable memory are considered, and they are found in the same memory a new
+    /// scan would read, within the scan bounds and region size limit. Chains end at the first pointer stored in a module
+    pub fn pointer_scan(
+        &self,
+        target: u64,
+        max_depth: usize,
+        max_offset: u64,
+    ) -> Vec<PointerChain> {
+        let pointer_size = std::mem::size_of::<u64>() as u64;
+        let mut regions = self.process.get_writable_regions();
+        regions.sort();
+        let is_writable_address = |address: u64| {
+            // Only the last region starting at or before the address can contain it
+            let index = regions.partition_point(|region| region.base_address <= address);
+            index > 0 && address - regions[index - 1].base_address < regions[index - 1].size
+        };
+
+        // Reverse map of (pointer value, address it is stored at), sorted by pointer value
+        let process = self.process.as_ref();
+        let mut pointers: Vec<(u64, u64)> = self
+            .regions_to_scan()
+            .par_iter()
+            .filter_map(|(region, overlap)| {
+                process
+                    .read_memory_bytes(region.base_address, (region.size + overlap) as usize)
+                    .ok()
+                    .map(|buffer| (region, buffer))
+            })
+            .flat_map_iter(|(region, buffer)| {
+                // Pointers starting in the overlap belong to the next chunk
+                let first_offset =
+                    region.base_address.next_multiple_of(pointer_size) - region.base_address;
+                (first_offset..region.size)
+                    .step_by(pointer_size as usize)
+                    .filter_map(|offset| {
+                        Some((
+                            try_read_from_buffer::<u64>(&buffer, offset)?,
+                            region.base_address + offset,
+                        ))
+                    })
+                    .filter(|(value, _)| is_writable_address(*value))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        pointers.par_sort_unstable();
+
+        let mut modules = self.process.get_modules();
+        modules.sort_by_key(|module| module.base_address);
+        let mut chains = Vec::new();
+        find_pointer_chains(
+            &pointers,
+            &modules,
+            target,
+            max_depth,
+            max_offset,
+            &mut vec![],
+            &mut chains,
+        );
+        chains
+    }
+
+    /// Walks a pointer chain, returning the address it currently resolves to
+    pub fn resolve_chain(&self, chain: &PointerChain) -> Result<u64> {
+        let mut address = resolve_module_offset(
+            self.process.as_ref(),
+            &chain.module,
+            chain.base_module_offset,
+        )?;
+        for offset in chain.offsets.iter() {
+            let pointer: u64 = self.process.read_memory(address)?;
+            address = pointer + offset;
+        }
+        Ok(address)
+    }
+
+    /// Reads the memory of each region that still needs scanning and passes it to `update`
+    ///
+    /// `progress` is called once per region. Stops before the next region once `cancel` is set, and undoes
+    /// the regions scanned so far
+    fn scan_regions<F>(
+        &mut self,
+        mut update: F,
+        progress: &dyn Fn(ScanProgress),
+        cancel: &AtomicBool,
+    ) -> Result<ScanOutcome>
+    where
+        F: FnMut(&mut RegionResults, Vec<u8>),
+    {
+        self.push_undo();
+        let started = Instant::now();
+        let results_before = self.count_results();
+        let mut regions_scanned = 0;
+        self.last_scan_stats = None;
+        let regions: Vec<(MemoryRegion, u64)> = if self.is_new_scan {
+            self.regions_to_scan()
+        } else {
+            self.results
+                .iter()
+                .map(|(region, results)| (*region, results.overlap))
+                .collect()
+        };
+        let _span =
+            tracing::info_span!("scan", new_scan = self.is_new_scan, regions = regions.len())
+                .entered();
+        let mut scan_progress = ScanProgress {
+            regions_done: 0,
+            regions_total: regions.len(),
+            bytes_scanned: 0,
+        };
         if self.is_new_scan {
-            // Deal with new scans
-            for region in regions.iter() {
-                let region_memory = self
-                    .process
-                    .read_memory_bytes(region.base_address, region.size as usize);
-                if let Ok(region_memory) = region_memory {
-                    self.results.insert(*region, RegionResults::new(*region));
-                    self.results
-                        .get_mut(region)
-                        .unwrap()
-                        .update_results(region_memory, filter);
+            // Deal with new scans. Reading is mostly syscalls, so batches of regions are read in parallel
+            let batch_size = rayon::current_num_threads() * READ_BATCH_PER_THREAD;
+            for batch in regions.chunks(batch_size) {
+                if cancel.load(Ordering::Relaxed) {
+                    return Ok(self.cancel_scan());
+                }
+                let process = self.process.as_ref();
+                let batch_memory: Vec<Result<(Vec<u8>, Vec<Range<usize>>)>> = batch
+                    .par_iter()
+                    .map(|(region, overlap)| {
+                        process.read_memory_with_gaps(
+                            region.base_address,
+                            (region.size + overlap) as usize,
+                        )
+                    })
+                    .collect();
+                for ((region, overlap), region_memory) in batch.iter().zip(batch_memory) {
+                    if let Ok((region_memory, gaps)) = region_memory {
+                        scan_progress.bytes_scanned += region_memory.len() as u64;
+                        regions_scanned += 1;
+                        let mut results = RegionResults::new(*region);
+                        results.set_endianness(self.endianness);
+                        results.set_alignment(self.alignment);
+                        results.overlap = *overlap;
+                        self.results.insert(*region, results);
+                        let results = self.results.get_mut(region).unwrap();
+                        update(results, region_memory);
+                        results.drop_hits_in_gaps(&gaps);
+                        self.total_hits += results.hit_count();
+                    }
+                    scan_progress.regions_done += 1;
+                    progress(scan_progress);
                 }
             }
         } else {
             // Filter existing results
-            for region in &regions {
-                if let Some(region_results) = self.results.get_mut(&region) {
+            for (region, overlap) in &regions {
+                if cancel.load(Ordering::Relaxed) {
+                    return Ok(self.cancel_scan());
+                }
+                if let Some(region_results) = self.results.get_mut(region) {
                     if region_results.hit_offsets.as_ref().is_none()
                         || region_results.hit_offsets.as_ref().unwrap().len() > 0
                     {
                         // Only bother to update memory of things with no hit results yet, or with hit results of length > 0
-                        let region_memory = self
-                            .process
-                            .read_memory_bytes(region.base_address, region.size as usize);
-                        if let Ok(region_memory) = region_memory {
-                            region_results.update_results(region_memory, filter);
+                        let region_memory = self.process.read_memory_with_gaps(
+                            region.base_address,
+                            (region.size + overlap) as usize,
+                        );
+                        if let Ok((region_memory, gaps)) = region_memory {
+                            scan_progress.bytes_scanned += region_memory.len() as u64;
+                            regions_scanned += 1;
+                            let hits_before = region_results.hit_count();
+                            update(region_results, region_memory);
+                            region_results.drop_hits_in_gaps(&gaps);
+                            self.total_hits =
+                                self.total_hits - hits_before + region_results.hit_count();
                         }
                     }
                 }
+                scan_progress.regions_done += 1;
+                progress(scan_progress);
             }
         }
 
         self.is_new_scan = false;
-        Ok(())
+        self.record_history();
+        self.last_scan_stats = Some(ScanStats {
+            duration: started.elapsed(),
+            bytes_scanned: scan_progress.bytes_scanned,
+            regions_scanned,
+            results_before,
+            results_after: self.total_hits,
+        });
+        tracing::info!(
+            results = self.total_hits,
+            regions_scanned,
+            bytes_scanned = scan_progress.bytes_scanned,
+            "Scan finished"
+        );
+        Ok(ScanOutcome::Completed)
+    }
+
+    /// Undoes the scan in progress, which has only scanned some regions
+    fn cancel_scan(&mut self) -> ScanOutcome {
+        tracing::info!("Scan cancelled");
+        if let Some(undo) = self.undo_stack.pop_back() {
+            self.restore_undo(undo);
+        }
+        ScanOutcome::Cancelled
+    }
+}
+
+/// Depth first search for pointers leading to `target`, appending complete chains to `chains`
+///
+/// `pointers` must be sorted by pointer value, and `modules` by base address. `offsets` holds the offsets found so far,
+/// from the target backwards. Pointers stored in a module complete a chain, anything else is followed further back
+fn find_pointer_chains(
+    pointers: &[(u64, u64)],
+    modules: &[Module],
+    target: u64,
+    depth_remaining: usize,
+    max_offset: u64,
+    offsets: &mut Vec<u64>,
+    chains: &mut Vec<PointerChain>,
+) {
+    if depth_remaining == 0 {
+        return;
+    }
+    let lowest_value = target.saturating_sub(max_offset);
+    let start = pointers.partition_point(|(value, _)| *value < lowest_value);
+    let end = pointers.partition_point(|(value, _)| *value <= target);
+    for (value, stored_at) in pointers[start..end].iter() {
+        offsets.push(target - value);
+        // Only the last module starting at or before the address can contain it
+        let index = modules.partition_point(|module| module.base_address <= *stored_at);
+        if index > 0 && modules[index - 1].contains(*stored_at) {
+            let module = &modules[index - 1];
+            chains.push(PointerChain {
+                module: module.name.clone(),
+                base_module_offset: *stored_at - module.base_address,
+                offsets: offsets.iter().rev().copied().collect(),
+            });
+            offsets.pop();
+            continue;
+        }
+        find_pointer_chains(
+            pointers,
+            modules,
+            *stored_at,
+            depth_remaining - 1,
+            max_offset,
+            offsets,
+            chains,
+        );
+        offsets.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockProcess;
+
+    /// Base address of the first region of test processes
+    const BASE: u64 = 0x10000;
+
+    fn scanner_for(process: MockProcess) -> (Arc<MockProcess>, Scanner) {
+        let process = Arc::new(process);
+        (process.clone(), Scanner::new(process))
+    }
+
+    fn addresses<T>(results: Vec<(u64, T)>) -> Vec<u64> {
+        results.into_iter().map(|(address, _)| address).collect()
+    }
+
+    #[test]
+    fn aob_scan_matches_wildcards_and_narrows() {
+        let mut memory = vec![0u8; 64];
+        memory[4..8].copy_from_slice(&[0x48, 0x8b, 0x01, 0xc3]);
+        memory[20..24].copy_from_slice(&[0x48, 0x8b, 0x02, 0xc3]);
+        memory[40..44].copy_from_slice(&[0x48, 0x8b, 0x03, 0xc4]);
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+
+        scanner
+            .scan_aob(&util::parse_aob("48 8B ?? C3").unwrap())
+            .unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u8>()),
+            vec![BASE + 4, BASE + 20]
+        );
+
+        // Re-running narrows down the existing hits
+        process.set_bytes(BASE + 23, &[0xc4]);
+        scanner
+            .scan_aob(&util::parse_aob("48 8B ?? C3").unwrap())
+            .unwrap();
+        assert_eq!(addresses(scanner.get_results::<u8>()), vec![BASE + 4]);
+    }
+
+    #[test]
+    fn aob_scan_rejects_empty_pattern() {
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 16]));
+        assert!(scanner.scan_aob(&[]).is_err());
+    }
+
+    #[test]
+    fn string_scan_finds_multi_byte_utf16() {
+        let needle = StringEncoding::Utf16Le.encode("日本é").unwrap();
+        assert_eq!(needle.len(), 6);
+        let mut memory = vec![0u8; 32];
+        memory[10..16].copy_from_slice(&needle);
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+
+        scanner
+            .scan_string("日本é", StringEncoding::Utf16Le)
+            .unwrap();
+        assert_eq!(addresses(scanner.get_results::<u8>()), vec![BASE + 10]);
+        // The UTF-8 bytes of the same string aren't there
+        scanner.new_scan();
+        scanner.scan_string("日本é", StringEncoding::Utf8).unwrap();
+        assert_eq!(scanner.count_results(), Some(0));
+    }
+
+    #[test]
+    fn string_scan_does_not_match_across_regions() {
+        let mut first = vec![0u8; 16];
+        first[13..16].copy_from_slice(b"hel");
+        let mut second = vec![0u8; 16];
+        second[0..2].copy_from_slice(b"lo");
+        second[6..11].copy_from_slice(b"hello");
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, first)
+                .with_region(BASE + 16, second),
+        );
+
+        scanner.scan_string("hello", StringEncoding::Ascii).unwrap();
+        assert_eq!(addresses(scanner.get_results::<u8>()), vec![BASE + 22]);
+    }
+
+    #[test]
+    fn string_scan_narrows_existing_hits() {
+        let mut memory = vec![0u8; 32];
+        memory[0..4].copy_from_slice(b"name");
+        memory[16..20].copy_from_slice(b"name");
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+
+        scanner.scan_string("name", StringEncoding::Utf8).unwrap();
+        assert_eq!(scanner.count_results(), Some(2));
+        process.set_bytes(BASE + 16, b"nope");
+        scanner.scan_string("name", StringEncoding::Utf8).unwrap();
+        assert_eq!(addresses(scanner.get_results::<u8>()), vec![BASE]);
+    }
+
+    /// A module with a static pointer to a struct on the heap, which points to another struct holding a sentinel
+    ///
+    /// The chain to the sentinel is `[game+0x10] -> +0x20 -> +0x8`
+    fn pointer_chain_process() -> (MockProcess, u64) {
+        const MODULE: u64 = 0x400000;
+        const HEAP: u64 = 0x800000;
+        let mut module = vec![0u8; 0x100];
+        module[0x10..0x18].copy_from_slice(&(HEAP + 0x100).to_le_bytes());
+        let mut heap = vec![0u8; 0x1000];
+        heap[0x120..0x128].copy_from_slice(&(HEAP + 0x400).to_le_bytes());
+        heap[0x408..0x40c].copy_from_slice(&0xdeadbeefu32.to_le_bytes());
+        let process = MockProcess::new()
+            .with_region(MODULE, module)
+            .with_region(HEAP, heap)
+            .with_module("game", MODULE, 0x100);
+        (process, HEAP + 0x408)
+    }
+
+    #[test]
+    fn pointer_scan_finds_two_hop_chain_from_module() {
+        let (process, target) = pointer_chain_process();
+        let (process, scanner) = scanner_for(process);
+
+        let chains = scanner.pointer_scan(target, 3, 0x40);
+        assert_eq!(
+            chains,
+            vec![PointerChain {
+                module: "game".to_string(),
+                base_module_offset: 0x10,
+                offsets: vec![0x20, 0x8],
+            }]
+        );
+        assert_eq!(chains[0].to_string(), "[game+0x10] -> +0x20 -> +0x8");
+        let resolved = scanner.resolve_chain(&chains[0]).unwrap();
+        assert_eq!(resolved, target);
+        assert_eq!(
+            GenericMemoryRead::<u32>::read_memory(process.as_ref(), resolved).unwrap(),
+            0xdeadbeef
+        );
+    }
+
+    #[test]
+    fn pointer_scan_only_roots_chains_in_modules() {
+        let (process, target) = pointer_chain_process();
+        let (_, scanner) = scanner_for(process);
+        // The heap pointer alone is one hop, but isn't in a module so isn't a chain
+        assert!(scanner.pointer_scan(target, 1, 0x40).is_empty());
+        // The module pointer is out of range with a smaller offset
+        assert!(scanner.pointer_scan(target, 3, 0x10).is_empty());
+    }
+
+    #[test]
+    fn pointer_scan_respects_region_size_limit() {
+        let (process, target) = pointer_chain_process();
+        let (_, mut scanner) = scanner_for(process);
+        // The heap is skipped, so nothing points at the sentinel
+        scanner.set_max_region_bytes(0x100);
+        assert!(scanner.pointer_scan(target, 3, 0x40).is_empty());
+    }
+
+    #[test]
+    fn resolve_chain_fails_for_unloaded_module() {
+        let (process, _) = pointer_chain_process();
+        let (_, scanner) = scanner_for(process);
+        let chain = PointerChain {
+            module: "other".to_string(),
+            base_module_offset: 0x10,
+            offsets: vec![0x20],
+        };
+        assert!(scanner.resolve_chain(&chain).is_err());
+    }
+
+    #[test]
+    fn between_includes_both_bounds() {
+        let filter = ScanFilter::Between(10u32, 20);
+        assert!(filter.matches(&10, &0));
+        assert!(filter.matches(&20, &0));
+        assert!(filter.matches(&15, &0));
+        assert!(!filter.matches(&9, &0));
+        assert!(!filter.matches(&21, &0));
+    }
+
+    #[test]
+    fn between_accepts_reversed_bounds() {
+        let filter = ScanFilter::Between(2.5f32, -2.5);
+        assert!(filter.matches(&-2.5, &0.0));
+        assert!(filter.matches(&2.5, &0.0));
+        assert!(!filter.matches(&2.6, &0.0));
+        let filter = ScanFilter::Between(7i8, 7);
+        assert!(filter.matches(&7, &0));
+        assert!(!filter.matches(&6, &0));
+    }
+
+    #[test]
+    fn not_equal_only_rejects_the_value() {
+        let filter = ScanFilter::NotEqual(5i32);
+        assert!(!filter.matches(&5, &0));
+        assert!(filter.matches(&4, &5));
+        assert!(filter.matches(&-5, &5));
+    }
+
+    #[test]
+    fn region_smaller_than_value_has_no_results() {
+        let region = MemoryRegion {
+            base_address: BASE,
+            size: 2,
+            protection: Protection::READ | Protection::WRITE,
+        };
+        let mut results = RegionResults::new(region);
+        results.update_results(vec![0; 2], &ScanFilter::Exact(0u64));
+        assert_eq!(results.hit_count(), 0);
+
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 2]));
+        scanner.scan(ScanFilter::Exact(0u64)).unwrap();
+        assert_eq!(scanner.count_results(), Some(0));
+        scanner.scan(ScanFilter::Unchanged::<u64>).unwrap();
+        assert_eq!(scanner.count_results(), Some(0));
+    }
+
+    #[test]
+    fn scan_progress_fires_once_per_region() {
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![0; 16])
+                .with_region(BASE + 0x100, vec![0; 32])
+                .with_region(BASE + 0x200, vec![0; 8]),
+        );
+        let reports = std::sync::Mutex::new(vec![]);
+        scanner
+            .scan_with_progress(ScanFilter::Exact(1u32), |progress| {
+                reports.lock().unwrap().push(progress)
+            })
+            .unwrap();
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(
+            reports
+                .iter()
+                .map(|progress| progress.regions_done)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(reports.iter().all(|progress| progress.regions_total == 3));
+        assert_eq!(reports[2].bytes_scanned, 56);
+        assert_eq!(reports[2].fraction(), 1.0);
+    }
+
+    #[test]
+    fn undo_restores_first_scan_results_and_values() {
+        let (process, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![0; 16])
+                .with_region(BASE + 0x100, vec![0; 16]),
+        );
+        process.set_value(BASE + 4, 5u32);
+        process.set_value(BASE + 0x108, 5u32);
+        scanner.set_alignment(4);
+        scanner.scan(ScanFilter::Exact(5u32)).unwrap();
+        let first = scanner.get_results::<u32>();
+        assert_eq!(first, vec![(BASE + 4, 5), (BASE + 0x108, 5)]);
+
+        // The second region has no hits left after this, so its snapshot is dropped from the results
+        process.set_value(BASE + 4, 7u32);
+        process.set_value(BASE + 0x108, 6u32);
+        scanner.scan(ScanFilter::Increased::<u32>).unwrap();
+        scanner.scan(ScanFilter::Exact(7u32)).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE + 4, 7)]);
+
+        scanner.undo_scan().unwrap();
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.get_results::<u32>(), first);
+        assert_eq!(scanner.count_results(), Some(2));
+
+        // Later scans compare against the restored values
+        scanner.scan(ScanFilter::Increased::<u32>).unwrap();
+        assert_eq!(
+            scanner.get_results::<u32>(),
+            vec![(BASE + 4, 7), (BASE + 0x108, 6)]
+        );
+    }
+
+    #[test]
+    fn undo_drops_old_snapshots_over_the_limit() {
+        let (process, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![0; 64])
+                .with_region(BASE + 0x100, vec![0; 64]),
+        );
+        scanner.set_alignment(4);
+        scanner.scan(ScanFilter::Unknown::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(32));
+
+        // The second region has no hits left, so only the undo entry keeps its snapshot
+        process.set_value(BASE, 1u32);
+        scanner.scan(ScanFilter::Changed::<u32>).unwrap();
+        scanner.scan(ScanFilter::Unchanged::<u32>).unwrap();
+        let older = scanner.undo_stack.len() - 1;
+        assert_eq!(scanner.undo_bytes_retained(older), 128);
+
+        scanner.set_max_undo_bytes(0);
+        assert_eq!(scanner.undo_bytes_retained(older), 0);
+        assert!(scanner
+            .undo_stack
+            .back()
+            .unwrap()
+            .regions
+            .values()
+            .all(|undo| !undo.snapshots_dropped));
+
+        // The hits still come back, with the second region read again
+        scanner.undo_scan().unwrap();
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.count_results(), Some(32));
+        assert_eq!(scanner.get_results::<u32>().len(), 32);
+        assert_eq!(scanner.get_results::<u32>()[0], (BASE, 1));
+    }
+
+    /// A process with more regions than a new scan reads in one batch, each holding a `u32` 1 at its start
+    fn many_regions_process() -> (MockProcess, usize) {
+        let region_count = rayon::current_num_threads() * READ_BATCH_PER_THREAD + 1;
+        let process = (0..region_count as u64).fold(MockProcess::new(), |process, i| {
+            process.with_region(BASE + i * 0x100, vec![1, 0, 0, 0, 0, 0, 0, 0])
+        });
+        (process, region_count)
+    }
+
+    #[test]
+    fn cancelled_first_scan_leaves_no_results() {
+        let (process, _) = many_regions_process();
+        let (_, mut scanner) = scanner_for(process);
+        let cancel = AtomicBool::new(false);
+        let outcome = scanner
+            .scan_cancellable_with_progress(ScanFilter::Exact(1u32), &cancel, |_| {
+                cancel.store(true, Ordering::Relaxed)
+            })
+            .unwrap();
+        assert_eq!(outcome, ScanOutcome::Cancelled);
+        assert_eq!(scanner.count_results(), None);
+        assert!(scanner.get_results::<u32>().is_empty());
+        assert!(scanner.last_scan_stats().is_none());
+        assert!(scanner.undo_scan().is_err());
+
+        // The next scan starts from scratch
+        cancel.store(false, Ordering::Relaxed);
+        let outcome = scanner
+            .scan_cancellable(ScanFilter::Exact(1u32), &cancel)
+            .unwrap();
+        assert_eq!(outcome, ScanOutcome::Completed);
+        assert_eq!(scanner.count_results(), Some(many_regions_process().1));
+    }
+
+    #[test]
+    fn cancelled_scan_keeps_results_from_before_it() {
+        let (process, region_count) = many_regions_process();
+        let (process, mut scanner) = scanner_for(process);
+        scanner.scan(ScanFilter::Exact(1u32)).unwrap();
+        let before = scanner.get_results::<u32>();
+        assert_eq!(before.len(), region_count);
+
+        // Every value changes, but only the first region is scanned before cancelling
+        for i in 0..region_count as u64 {
+            process.set_value(BASE + i * 0x100, 2u32);
+        }
+        let cancel = AtomicBool::new(false);
+        let regions_done = std::sync::Mutex::new(0);
+        let outcome = scanner
+            .scan_cancellable_with_progress(ScanFilter::Unchanged::<u32>, &cancel, |progress| {
+                *regions_done.lock().unwrap() = progress.regions_done;
+                cancel.store(true, Ordering::Relaxed)
+            })
+            .unwrap();
+        assert_eq!(outcome, ScanOutcome::Cancelled);
+        assert_eq!(*regions_done.lock().unwrap(), 1);
+        assert_eq!(scanner.get_results::<u32>(), before);
+        assert_eq!(scanner.count_results(), Some(region_count));
+
+        // Only the first scan can be undone, as the cancelled one never happened
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.count_results(), None);
+        assert!(scanner.undo_scan().is_err());
+    }
+
+    #[test]
+    fn scan_bounds_exclude_results_outside_them() {
+        let mut memory = vec![0u8; 0x100];
+        for offset in [0x0, 0x40, 0x80, 0xfc] {
+            memory[offset..offset + 4].copy_from_slice(&7u32.to_le_bytes());
+        }
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, memory)
+                .with_region(BASE + 0x1000, 7u32.to_le_bytes().to_vec()),
+        );
+
+        // The bounds cut the first region in two and leave out the second entirely
+        scanner.set_scan_bounds(BASE + 0x40, BASE + 0x84);
+        scanner.scan(ScanFilter::Exact(7u32)).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u32>()),
+            vec![BASE + 0x40, BASE + 0x80]
+        );
+        scanner.scan(ScanFilter::Unchanged::<u32>).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u32>()),
+            vec![BASE + 0x40, BASE + 0x80]
+        );
+
+        // A value straddling the end of the bounds isn't matched
+        scanner.set_scan_bounds(BASE + 0x40, BASE + 0x83);
+        scanner.new_scan();
+        scanner.scan(ScanFilter::Exact(7u32)).unwrap();
+        assert_eq!(addresses(scanner.get_results::<u32>()), vec![BASE + 0x40]);
+
+        scanner.clear_scan_bounds();
+        scanner.new_scan();
+        scanner.scan(ScanFilter::Exact(7u32)).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u32>()),
+            vec![BASE, BASE + 0x40, BASE + 0x80, BASE + 0xfc, BASE + 0x1000]
+        );
+    }
+
+    #[test]
+    fn results_with_prev_pair_last_two_scans() {
+        let mut memory = vec![0u8; 16];
+        memory[0..4].copy_from_slice(&10u32.to_le_bytes());
+        memory[8..12].copy_from_slice(&10u32.to_le_bytes());
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+
+        scanner.scan(ScanFilter::Exact(10u32)).unwrap();
+        // There is no previous value until a second scan
+        assert_eq!(
+            scanner.get_results_with_prev::<u32>(),
+            vec![(BASE, 10, 10), (BASE + 8, 10, 10)]
+        );
+
+        process.set_value(BASE, 7u32);
+        process.set_value(BASE + 8, 12u32);
+        scanner.scan(ScanFilter::Changed::<u32>).unwrap();
+        assert_eq!(
+            scanner.get_results_with_prev::<u32>(),
+            vec![(BASE, 7, 10), (BASE + 8, 12, 10)]
+        );
+
+        process.set_value(BASE, 3u32);
+        scanner.scan(ScanFilter::Decreased::<u32>).unwrap();
+        assert_eq!(scanner.get_results_with_prev::<u32>(), vec![(BASE, 3, 7)]);
+    }
+
+    #[test]
+    fn reloaded_session_continues_like_the_original() {
+        let mut memory = vec![0u8; 32];
+        for (i, value) in [50u32, 50, 40, 50].into_iter().enumerate() {
+            memory[i * 8..i * 8 + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.scan(ScanFilter::Exact(50u32)).unwrap();
+
+        let path = std::env::temp_dir().join(format!("hoodmem-session-{}.bin", std::process::id()));
+        scanner.save_session(&path).unwrap();
+        let mut reloaded = Scanner::load_session(&path, process.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.get_results::<u32>(), scanner.get_results::<u32>());
+
+        process.set_value(BASE + 8, 45u32);
+        process.set_value(BASE + 24, 49u32);
+        scanner.scan(ScanFilter::Decreased::<u32>).unwrap();
+        reloaded.scan(ScanFilter::Decreased::<u32>).unwrap();
+        assert_eq!(
+            reloaded.get_results_with_prev::<u32>(),
+            scanner.get_results_with_prev::<u32>()
+        );
+        assert_eq!(
+            addresses(reloaded.get_results::<u32>()),
+            vec![BASE + 8, BASE + 24]
+        );
+        assert_eq!(reloaded.count_results(), Some(2));
+    }
+
+    #[test]
+    fn load_rejects_unversioned_and_other_format_versions() {
+        let (process, _) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 8]));
+        let path = std::env::temp_dir().join(format!("hoodmem-format-{}.bin", std::process::id()));
+
+        // Files saved before versioning start straight away with the results
+        std::fs::write(&path, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0]).unwrap();
+        let err = Scanner::load_session(&path, process.clone()).err().unwrap();
+        assert!(err.to_string().contains("Not a session file"), "{}", err);
+
+        let mut newer = SESSION_MAGIC.to_vec();
+        newer.extend((SESSION_FORMAT_VERSION + 1).to_le_bytes());
+        std::fs::write(&path, newer).unwrap();
+        let err = Scanner::load_session(&path, process).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.to_string().contains("isn't supported"), "{}", err);
+    }
+
+    #[test]
+    fn write_result_changes_the_scanned_value() {
+        let (process, mut scanner) =
+            scanner_for(MockProcess::new().with_region(BASE, 100u32.to_le_bytes().to_vec()));
+        scanner.scan(ScanFilter::Exact(100u32)).unwrap();
+
+        scanner.write_result(BASE, 999u32).unwrap();
+        let value: u32 = process.read_memory(BASE).unwrap();
+        assert_eq!(value, 999);
+        scanner.scan(ScanFilter::Changed::<u32>).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE, 999)]);
+
+        // Writing past the end of the region fails rather than writing part of the value
+        assert!(scanner.write_result(BASE + 2, 0u32).is_err());
+        let value: u32 = process.read_memory(BASE).unwrap();
+        assert_eq!(value, 999);
+        assert!(scanner.write_result(BASE + 0x1000, 0u32).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn rounded_to_compares_at_the_given_places() {
+        assert!(ScanFilter::RoundedTo(3.14f32, 2).matches(&3.14159, &0.0));
+        assert!(!ScanFilter::RoundedTo(3.14f32, 4).matches(&3.14159, &0.0));
+        assert!(ScanFilter::RoundedTo(3.1416f64, 4).matches(&3.14159, &0.0));
+        assert!(!ScanFilter::RoundedTo(3.14f64, 2).matches(&3.146, &0.0));
+        // Integers can't be rounded, so never match
+        assert!(!ScanFilter::RoundedTo(3u32, 2).matches(&3, &0));
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn rounded_scan_finds_imprecise_floats() {
+        let mut memory = vec![0u8; 12];
+        memory[0..4].copy_from_slice(&3.14159f32.to_le_bytes());
+        memory[4..8].copy_from_slice(&3.15f32.to_le_bytes());
+        memory[8..12].copy_from_slice(&3.1f32.to_le_bytes());
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+
+        scanner.scan(ScanFilter::RoundedTo(3.14f32, 2)).unwrap();
+        assert_eq!(addresses(scanner.get_results::<f32>()), vec![BASE]);
+        scanner.new_scan();
+        scanner.scan(ScanFilter::RoundedTo(3.1f32, 1)).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<f32>()),
+            vec![BASE, BASE + 8]
+        );
+    }
+
+    #[test]
+    fn big_endian_values_only_match_in_big_endian_mode() {
+        let mut memory = vec![0u8; 16];
+        memory[4..8].copy_from_slice(&0x01020304u32.to_be_bytes());
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+
+        scanner.scan(ScanFilter::Exact(0x01020304u32)).unwrap();
+        assert_eq!(scanner.count_results(), Some(0));
+
+        scanner.new_scan();
+        scanner.set_endianness(Endianness::Big);
+        scanner.scan(ScanFilter::Exact(0x01020304u32)).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE + 4, 0x01020304)]);
+
+        // Comparisons with the previous scan read both values big-endian
+        process.set_bytes(BASE + 4, &0x01020305u32.to_be_bytes());
+        scanner.scan(ScanFilter::Increased::<u32>).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE + 4, 0x01020305)]);
+    }
+
+    #[test]
+    fn values_on_chunk_boundaries_are_found_once() {
+        let chunk = SCAN_CHUNK_SIZE as usize;
+        let mut memory = vec![0u8; chunk + 64];
+        // One value straddles the boundary, and one starts exactly on it
+        memory[chunk - 2..chunk + 2].copy_from_slice(&0xdeadbeefu32.to_le_bytes());
+        memory[chunk + 8..chunk + 12].copy_from_slice(&0xdeadbeefu32.to_le_bytes());
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+
+        scanner.scan(ScanFilter::Exact(0xdeadbeefu32)).unwrap();
+        let boundary = BASE + SCAN_CHUNK_SIZE;
+        assert_eq!(
+            addresses(scanner.get_results::<u32>()),
+            vec![boundary - 2, boundary + 8]
+        );
+
+        process.set_value(boundary - 2, 1u32);
+        scanner.scan(ScanFilter::Unchanged::<u32>).unwrap();
+        assert_eq!(
+            scanner.get_results::<u32>(),
+            vec![(boundary + 8, 0xdeadbeef)]
+        );
+    }
+
+    #[test]
+    fn short_reads_scan_the_memory_that_was_read() {
+        let mut memory = vec![0u8; 64];
+        memory[8..12].copy_from_slice(&9u32.to_le_bytes());
+        memory[40..44].copy_from_slice(&9u32.to_le_bytes());
+        // Reads of the region stop at byte 32, as if its end was unmapped after it was enumerated
+        let (process, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, memory)
+                .with_unreadable(BASE + 32, 32),
+        );
+        assert_eq!(process.read_memory_bytes(BASE, 64).unwrap().len(), 32);
+
+        scanner.scan(ScanFilter::Exact(9u32)).unwrap();
+        assert_eq!(addresses(scanner.get_results::<u32>()), vec![BASE + 8]);
+
+        // The next scan can read less again
+        process.set_unreadable(BASE + 4, 28);
+        scanner.scan(ScanFilter::Unchanged::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(0));
+    }
+
+    #[test]
+    fn refresh_shows_live_values_but_scans_compare_with_last_scan() {
+        let (process, mut scanner) =
+            scanner_for(MockProcess::new().with_region(BASE, 10u32.to_le_bytes().to_vec()));
+        scanner.scan(ScanFilter::Exact(10u32)).unwrap();
+
+        process.set_value(BASE, 15u32);
+        scanner.refresh_values().unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE, 15)]);
+        assert_eq!(scanner.results_page::<u32>(0, 10), vec![(BASE, 15)]);
+        assert_eq!(scanner.get_results_with_prev::<u32>(), vec![(BASE, 15, 10)]);
+
+        // 15 is unchanged since the refresh, but increased since the last scan
+        scanner.scan(ScanFilter::Increased::<u32>).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE, 15)]);
+        process.set_value(BASE, 12u32);
+        scanner.refresh_values().unwrap();
+        scanner.scan(ScanFilter::Decreased::<u32>).unwrap();
+        assert_eq!(scanner.get_results_with_prev::<u32>(), vec![(BASE, 12, 15)]);
+
+        // Undoing shows the values found by the scan before, not refreshed ones
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE, 15)]);
+    }
+
+    #[test]
+    fn results_page_matches_flat_results_at_random_offsets() {
+        // Regions of different sizes, every offset of which is a hit
+        let process = (0..20u64).fold(MockProcess::new(), |process, i| {
+            process.with_region(BASE + i * 0x1000, vec![0; 16 * (i as usize % 7)])
+        });
+        let (_, mut scanner) = scanner_for(process);
+        scanner.scan(ScanFilter::Exact(0u8)).unwrap();
+        let total = scanner.count_results().unwrap();
+        assert!(total > 0);
+
+        // A fixed LCG, so failures can be reproduced
+        let mut seed = 0x2545f4914f6cdd1du64;
+        let mut next = |bound: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % bound
+        };
+        for _ in 0..200 {
+            let offset = next(total + 10);
+            let limit = next(40) + 1;
+            assert_eq!(
+                scanner.results_page::<u8>(offset, limit),
+                scanner.get_results_range::<u8>(offset, offset + limit - 1),
+                "page of {} at {}",
+                limit,
+                offset
+            );
+        }
+        assert!(scanner.results_page::<u8>(0, 0).is_empty());
+    }
+
+    #[test]
+    fn results_come_out_in_address_order() {
+        // Regions are added out of order, and a manual address lands between them
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE + 0x3000, vec![0; 8])
+                .with_region(BASE, vec![0; 8])
+                .with_region(BASE + 0x1000, vec![0; 8])
+                .with_protected_region(BASE + 0x2000, vec![0; 8], Protection::READ),
+        );
+        scanner.set_alignment(4);
+        scanner.scan(ScanFilter::Exact(0u32)).unwrap();
+        scanner.add_manual_address::<u32>(BASE + 0x2004).unwrap();
+
+        let results = addresses(scanner.get_results::<u32>());
+        assert_eq!(
+            results,
+            vec![
+                BASE,
+                BASE + 4,
+                BASE + 0x1000,
+                BASE + 0x1004,
+                BASE + 0x2004,
+                BASE + 0x3000,
+                BASE + 0x3004
+            ]
+        );
+        for _ in 0..5 {
+            assert_eq!(addresses(scanner.get_results::<u32>()), results);
+        }
+    }
+
+    #[test]
+    fn unknown_first_scan_then_increased_keeps_increased_values() {
+        let mut memory = vec![0u8; 16];
+        for (i, value) in [5u32, 6, 7, 8].into_iter().enumerate() {
+            memory[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.set_alignment(4);
+
+        scanner.scan(ScanFilter::Unknown::<u32>).unwrap();
+        // Every aligned offset is a hit, but they aren't stored
+        assert_eq!(scanner.count_results(), Some(4));
+        assert!(scanner
+            .results
+            .values()
+            .all(|results| results.hit_offsets.is_none()));
+        assert_eq!(
+            scanner.get_results::<u32>(),
+            vec![(BASE, 5), (BASE + 4, 6), (BASE + 8, 7), (BASE + 12, 8)]
+        );
+        assert_eq!(
+            scanner.results_page::<u32>(1, 2),
+            vec![(BASE + 4, 6), (BASE + 8, 7)]
+        );
+
+        process.set_value(BASE + 4, 60u32);
+        process.set_value(BASE + 8, 1u32);
+        process.set_value(BASE + 12, 9u32);
+        scanner.scan(ScanFilter::Increased::<u32>).unwrap();
+        assert_eq!(
+            scanner.get_results_with_prev::<u32>(),
+            vec![(BASE + 4, 60, 6), (BASE + 12, 9, 8)]
+        );
+        assert_eq!(scanner.count_results(), Some(2));
+
+        // Undoing goes back to every offset being a hit
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.count_results(), Some(4));
+    }
+
+    #[test]
+    fn every_aligned_offset_counts_values_which_fit() {
+        // The region starts 2 bytes past a multiple of 4, and a value at its last offset wouldn't fit
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE + 2, vec![0; 17]));
+        scanner.set_alignment(4);
+        scanner.scan(ScanFilter::Unknown::<u32>).unwrap();
+        let expected = vec![BASE + 4, BASE + 8, BASE + 12];
+        assert_eq!(scanner.count_results(), Some(expected.len()));
+        assert_eq!(addresses(scanner.get_results::<u32>()), expected);
+        assert_eq!(addresses(scanner.results_page::<u32>(0, 10)), expected);
+        let results = scanner.results.values().next().unwrap();
+        assert!(results.is_hit(2));
+        assert!(results.is_hit(10));
+        assert!(!results.is_hit(4));
+        assert!(!results.is_hit(14));
+
+        // Without alignment, every offset where a u32 fits is a hit
+        scanner.set_alignment(1);
+        scanner.new_scan();
+        scanner.scan(ScanFilter::Unknown::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(14));
+        assert_eq!(scanner.results_page::<u32>(13, 5).len(), 1);
+    }
+
+    #[test]
+    fn manual_address_reads_back_live_value() {
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 16]));
+        process.set_value(BASE + 6, 1234u16);
+
+        scanner.add_manual_address::<u16>(BASE + 6).unwrap();
+        assert_eq!(scanner.get_results::<u16>(), vec![(BASE + 6, 1234)]);
+
+        process.set_value(BASE + 6, 4321u16);
+        scanner.refresh_values().unwrap();
+        assert_eq!(scanner.get_results::<u16>(), vec![(BASE + 6, 4321)]);
+        scanner.write_result(BASE + 6, 7u16).unwrap();
+        scanner.refresh_values().unwrap();
+        assert_eq!(scanner.get_results::<u16>(), vec![(BASE + 6, 7)]);
+
+        assert!(scanner.add_manual_address::<u16>(BASE + 0x1000).is_err());
+    }
+
+    #[test]
+    fn manual_address_is_narrowed_down_with_scan_results() {
+        let mut memory = vec![0u8; 16];
+        memory[0..4].copy_from_slice(&5u32.to_le_bytes());
+        let (process, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, memory)
+                .with_protected_region(BASE + 0x1000, vec![0; 4], Protection::READ),
+        );
+        scanner.scan(ScanFilter::Exact(5u32)).unwrap();
+        // Read-only memory isn't scanned, but can still be added
+        scanner.add_manual_address::<u32>(BASE + 0x1000).unwrap();
+        assert_eq!(scanner.count_results(), Some(2));
+
+        process.set_value(BASE + 0x1000, 1u32);
+        scanner.scan(ScanFilter::Changed::<u32>).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE + 0x1000, 1)]);
+    }
+
+    #[test]
+    fn results_are_grouped_by_module() {
+        let mut memory = vec![0u8; 0x30];
+        for offset in [0x0, 0x10, 0x20] {
+            memory[offset..offset + 4].copy_from_slice(&3u32.to_le_bytes());
+        }
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, memory)
+                .with_module("game", BASE, 0x10)
+                .with_module("libc.so.6", BASE + 0x10, 0x10),
+        );
+        scanner.scan(ScanFilter::Exact(3u32)).unwrap();
+
+        let grouped = scanner.get_results_grouped::<u32>();
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(grouped["game"], vec![(BASE, 3)]);
+        assert_eq!(grouped["libc.so.6"], vec![(BASE + 0x10, 3)]);
+        assert_eq!(grouped[Scanner::NO_MODULE], vec![(BASE + 0x20, 3)]);
+    }
+
+    #[test]
+    fn zero_filled_pages_are_not_hits() {
+        let process = MockProcess::new()
+            .with_region(BASE, vec![0; 64])
+            .with_zero_filled(BASE + 16, 16);
+        let expected: Vec<u64> = (0..16)
+            .chain(32..64)
+            .step_by(4)
+            .map(|offset| BASE + offset)
+            .collect();
+        for filter in [ScanFilter::Exact(0u32), ScanFilter::Unknown] {
+            let (_, mut scanner) = scanner_for(
+                MockProcess::new()
+                    .with_region(BASE, vec![0; 64])
+                    .with_zero_filled(BASE + 16, 16),
+            );
+            scanner.set_alignment(4);
+            scanner.scan(filter).unwrap();
+            assert_eq!(addresses(scanner.get_results::<u32>()), expected);
+            assert_eq!(scanner.count_results(), Some(expected.len()));
+        }
+
+        // Values which only partly overlap a gap aren't hits either
+        let (_, mut scanner) = scanner_for(process);
+        scanner.scan(ScanFilter::Exact(0u32)).unwrap();
+        let results = addresses(scanner.get_results::<u32>());
+        assert!(results.contains(&(BASE + 12)));
+        assert!(!results.contains(&(BASE + 13)));
+        assert!(!results.contains(&(BASE + 31)));
+        assert!(results.contains(&(BASE + 32)));
+        scanner.scan(ScanFilter::Unchanged::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(16 - 3 + 61 - 32));
+    }
+
+    #[test]
+    fn alignment_only_finds_aligned_offsets() {
+        // Values at every offset from 1 to 9, in a region which doesn't start aligned
+        let mut memory = vec![0u8; 32];
+        for offset in 1..10 {
+            memory[offset] = 7;
+        }
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE + 1, memory));
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        assert_eq!(scanner.count_results(), Some(9));
+
+        // Existing hits are narrowed to aligned ones by the next scan
+        scanner.set_alignment(4);
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u8>()),
+            vec![BASE + 4, BASE + 8]
+        );
+
+        process.set_bytes(BASE + 1, &[7; 31]);
+        scanner.new_scan();
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        let results = addresses(scanner.get_results::<u8>());
+        assert_eq!(results.len(), 7);
+        assert!(results.iter().all(|address| address % 4 == 0));
+    }
+
+    #[test]
+    fn parallel_reads_match_sequential_scan() {
+        // Many small regions with values scattered through them, one of which can't be read
+        let process = (0..200u64).fold(MockProcess::new(), |process, i| {
+            let memory = (0..256u64).map(|j| ((i * 31 + j * 7) % 13) as u8).collect();
+            process.with_region(BASE + i * 0x1000, memory)
+        });
+        let process = Arc::new(process.with_unreadable(BASE + 0x5000, 256));
+        let mut parallel = Scanner::new(process.clone());
+        let mut sequential = Scanner::new(process);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+
+        parallel.scan(ScanFilter::Exact(3u8)).unwrap();
+        pool.install(|| sequential.scan(ScanFilter::Exact(3u8)))
+            .unwrap();
+        let results = parallel.get_results::<u8>();
+        assert!(!results.is_empty());
+        assert!(!results
+            .iter()
+            .any(|(address, _)| (BASE + 0x5000..BASE + 0x5100).contains(address)));
+        assert_eq!(results, sequential.get_results::<u8>());
+        assert_eq!(parallel.count_results(), sequential.count_results());
+    }
+
+    #[test]
+    fn cached_count_matches_recount() {
+        let recount = |scanner: &Scanner| -> usize {
+            scanner
+                .results
+                .values()
+                .map(|results| results.hit_count())
+                .sum()
+        };
+        let (process, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, (0..64).collect())
+                .with_region(BASE + 0x1000, vec![5; 64]),
+        );
+        scanner.set_alignment(4);
+        scanner.scan(ScanFilter::Unknown::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(recount(&scanner)));
+
+        process.set_value(BASE + 8, 0u32);
+        process.set_value(BASE + 0x1000, 0u32);
+        scanner.scan(ScanFilter::Decreased::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(2));
+        assert_eq!(scanner.count_results(), Some(recount(&scanner)));
+
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.count_results(), Some(recount(&scanner)));
+        scanner.scan(ScanFilter::Unchanged::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(recount(&scanner)));
+        scanner
+            .filter_results_by_address(BASE, BASE + 0x100)
+            .unwrap();
+        assert_eq!(scanner.count_results(), Some(recount(&scanner)));
+        assert_eq!(scanner.count_results(), Some(15));
+    }
+
+    #[test]
+    fn percent_of_matches_within_tolerance() {
+        let filter = ScanFilter::PercentOf {
+            max: 200u8,
+            percent: 75.0,
+            tolerance: 2.0,
+        };
+        assert!(filter.matches(&150, &0));
+        assert!(filter.matches(&146, &0));
+        assert!(filter.matches(&154, &0));
+        assert!(!filter.matches(&145, &0));
+        assert!(!filter.matches(&155, &0));
+        // Percentages of small integers don't overflow
+        assert!(!filter.matches(&255, &0));
+
+        let filter = ScanFilter::PercentOf {
+            max: 200.0f32,
+            percent: 75.0,
+            tolerance: 2.0,
+        };
+        assert!(filter.matches(&150.0, &0.0));
+        assert!(filter.matches(&153.9, &0.0));
+        assert!(!filter.matches(&154.1, &0.0));
+
+        // Nothing is a percentage of 0
+        let filter = ScanFilter::PercentOf {
+            max: 0i32,
+            percent: 0.0,
+            tolerance: 2.0,
+        };
+        assert!(!filter.matches(&0, &0));
+    }
+
+    #[test]
+    fn percent_of_scan_finds_value() {
+        let mut memory = vec![0u8; 16];
+        memory[4..8].copy_from_slice(&150u32.to_le_bytes());
+        memory[8..12].copy_from_slice(&100u32.to_le_bytes());
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.set_alignment(4);
+        scanner
+            .scan(ScanFilter::PercentOf {
+                max: 200u32,
+                percent: 75.0,
+                tolerance: 2.0,
+            })
+            .unwrap();
+        assert_eq!(addresses(scanner.get_results::<u32>()), vec![BASE + 4]);
+    }
+
+    #[test]
+    fn exact_any_matches_each_value() {
+        let filter = ScanFilter::ExactAny(vec![1u8, 2, 4]);
+        assert!(filter.matches(&1, &0));
+        assert!(filter.matches(&4, &0));
+        assert!(!filter.matches(&3, &0));
+        assert!(!ScanFilter::ExactAny(Vec::<u8>::new()).matches(&0, &0));
+
+        let (process, mut scanner) =
+            scanner_for(MockProcess::new().with_region(BASE, vec![0, 1, 2, 3, 4, 5]));
+        scanner.scan(ScanFilter::ExactAny(vec![1u8, 2, 4])).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u8>()),
+            vec![BASE + 1, BASE + 2, BASE + 4]
+        );
+        // Later scans narrow down to values which are still in the set
+        process.set_bytes(BASE + 2, &[3]);
+        scanner.scan(ScanFilter::ExactAny(vec![1u8, 2, 4])).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u8>()),
+            vec![BASE + 1, BASE + 4]
+        );
+    }
+
+    #[test]
+    fn history_is_capped_and_chronological() {
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 8]));
+        scanner.enable_history(3);
+        scanner.set_alignment(4);
+        process.set_value(BASE, 1u32);
+        scanner.scan(ScanFilter::Exact(1u32)).unwrap();
+        for value in 2..=5u32 {
+            process.set_value(BASE, value);
+            scanner.refresh_values().unwrap();
+        }
+        assert_eq!(scanner.value_history::<u32>(BASE), vec![3, 4, 5]);
+
+        // Scans record values too, and addresses which are no longer hits lose their history
+        process.set_value(BASE, 6u32);
+        scanner.scan(ScanFilter::Increased::<u32>).unwrap();
+        assert_eq!(scanner.value_history::<u32>(BASE), vec![4, 5, 6]);
+        scanner.scan(ScanFilter::Increased::<u32>).unwrap();
+        assert!(scanner.value_history::<u32>(BASE).is_empty());
+
+        // Shrinking the capacity drops the oldest values
+        scanner.new_scan();
+        scanner.scan(ScanFilter::Exact(6u32)).unwrap();
+        process.set_value(BASE, 7u32);
+        scanner.refresh_values().unwrap();
+        scanner.enable_history(1);
+        assert_eq!(scanner.value_history::<u32>(BASE), vec![7]);
+        scanner.enable_history(0);
+        assert!(scanner.value_history::<u32>(BASE).is_empty());
+    }
+
+    #[test]
+    fn scan_stats_count_regions_and_bytes_read() {
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![1; 16])
+                .with_region(BASE + 0x1000, vec![0; 32])
+                .with_region(BASE + 0x2000, vec![1; 64])
+                .with_unreadable(BASE + 0x2000, 64),
+        );
+        assert!(scanner.last_scan_stats().is_none());
+        scanner.scan(ScanFilter::Exact(1u8)).unwrap();
+        let stats = scanner.last_scan_stats().unwrap();
+        // The unreadable region isn't counted
+        assert_eq!(stats.regions_scanned, 2);
+        assert_eq!(stats.bytes_scanned, 48);
+        assert_eq!(stats.results_before, None);
+        assert_eq!(stats.results_after, 16);
+
+        // Regions without hits aren't read again
+        scanner.scan(ScanFilter::Exact(1u8)).unwrap();
+        let stats = scanner.last_scan_stats().unwrap();
+        assert_eq!(stats.regions_scanned, 1);
+        assert_eq!(stats.bytes_scanned, 16);
+        assert_eq!(stats.results_before, Some(16));
+        assert_eq!(stats.results_after, 16);
+    }
+
+    #[test]
+    fn results_read_as_same_size_type() {
+        let mut memory = vec![0u8; 16];
+        memory[0..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        memory[8..12].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.set_alignment(4);
+        scanner
+            .scan(ScanFilter::Between(0xFFFF_0000u32, u32::MAX))
+            .unwrap();
+
+        assert_eq!(
+            scanner.get_results_as::<i32>().unwrap(),
+            vec![(BASE, -1), (BASE + 8, -2)]
+        );
+        assert_eq!(scanner.get_results_as::<f32>().unwrap().len(), 2);
+        assert!(scanner.get_results_as::<i16>().is_err());
+        assert!(scanner.get_results_as::<i64>().is_err());
+    }
+
+    /// Gets the bytes of 3 contiguous floats
+    fn vec3_bytes(value: [f32; 3]) -> Vec<u8> {
+        value
+            .iter()
+            .flat_map(|component| component.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn vec3_scan_matches_exact_triplet() {
+        let mut memory = vec![0u8; 48];
+        memory[4..16].copy_from_slice(&vec3_bytes([1.0, 2.0, 3.0]));
+        memory[28..40].copy_from_slice(&vec3_bytes([1.0, 2.0, 3.5]));
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.set_alignment(4);
+        let filter = Vec3Filter {
+            components: [Some(1.0), Some(2.0), Some(3.0)],
+            tolerance: 0.001,
+        };
+        scanner.scan_vec3(&filter).unwrap();
+        assert_eq!(
+            scanner.get_results_vec3(),
+            vec![(BASE + 4, [1.0, 2.0, 3.0])]
+        );
+
+        // A looser tolerance finds both
+        scanner.new_scan();
+        scanner
+            .scan_vec3(&Vec3Filter {
+                tolerance: 0.5,
+                ..filter
+            })
+            .unwrap();
+        assert_eq!(
+            addresses(scanner.get_results_vec3()),
+            vec![BASE + 4, BASE + 28]
+        );
+    }
+
+    #[test]
+    fn vec3_scan_matches_known_components() {
+        let mut memory = vec![0u8; 48];
+        memory[4..16].copy_from_slice(&vec3_bytes([7.0, 2.0, 3.0]));
+        memory[28..40].copy_from_slice(&vec3_bytes([-4.0, 2.0, 9.0]));
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.set_alignment(4);
+        let filter = Vec3Filter {
+            components: [None, Some(2.0), None],
+            tolerance: 0.001,
+        };
+        scanner.scan_vec3(&filter).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results_vec3()),
+            vec![BASE + 4, BASE + 28]
+        );
+
+        // Later scans narrow down the hits
+        process.set_bytes(BASE + 4, &vec3_bytes([7.0, 5.0, 3.0]));
+        scanner.scan_vec3(&filter).unwrap();
+        assert_eq!(
+            scanner.get_results_vec3(),
+            vec![(BASE + 28, [-4.0, 2.0, 9.0])]
+        );
+
+        assert!(!filter.matches([0.0, 2.1, 0.0]));
+        assert!(scanner
+            .scan_vec3(&Vec3Filter {
+                components: [None; 3],
+                tolerance: 0.001,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn read_region_reads_around_address() {
+        let (_, scanner) = scanner_for(MockProcess::new().with_region(BASE, (0..32).collect()));
+        assert_eq!(scanner.read_region(BASE + 4, 4).unwrap(), vec![4, 5, 6, 7]);
+        assert!(scanner.read_region(BASE + 0x1000, 4).is_err());
+    }
+
+    #[test]
+    fn address_filter_keeps_hits_in_range() {
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![7; 8])
+                .with_region(BASE + 0x1000, vec![7; 8]),
+        );
+        assert!(scanner.filter_results_by_address(BASE, BASE + 4).is_err());
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        scanner
+            .filter_results_by_address(BASE + 4, BASE + 0x1002)
+            .unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u8>()),
+            vec![
+                BASE + 4,
+                BASE + 5,
+                BASE + 6,
+                BASE + 7,
+                BASE + 0x1000,
+                BASE + 0x1001
+            ]
+        );
+        assert_eq!(scanner.count_results(), Some(6));
+
+        // It is undone like a scan
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.count_results(), Some(16));
+    }
+
+    #[test]
+    fn cluster_results_groups_nearby_addresses() {
+        let mut memory = vec![0u8; 0x1000];
+        // A small cluster, then a larger one far away
+        for offset in [0x10, 0x18, 0x800, 0x804, 0x810] {
+            memory[offset] = 7;
+        }
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, memory)
+                .with_region(BASE + 0x1000, vec![0, 7]),
+        );
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        assert_eq!(
+            scanner.cluster_results(0x10),
+            vec![
+                vec![BASE + 0x800, BASE + 0x804, BASE + 0x810],
+                vec![BASE + 0x10, BASE + 0x18],
+                vec![BASE + 0x1001],
+            ]
+        );
+        // Every result is its own cluster when the window is too small
+        assert_eq!(scanner.cluster_results(1).len(), 6);
+    }
+
+    #[test]
+    fn regions_over_size_limit_are_skipped() {
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![7; 0x100])
+                .with_region(BASE + 0x1000, vec![7; 0x10000]),
+        );
+        scanner.set_max_region_bytes(0x100);
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        assert_eq!(scanner.count_results(), Some(0x100));
+        assert!(scanner
+            .get_results::<u8>()
+            .iter()
+            .all(|(address, _)| *address < BASE + 0x100));
+        assert_eq!(scanner.last_scan_stats().unwrap().bytes_scanned, 0x100);
+
+        // The limit applies from the next new scan
+        scanner.set_max_region_bytes(0x10000);
+        scanner.new_scan();
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        assert_eq!(scanner.count_results(), Some(0x10100));
+    }
+
+    #[test]
+    fn watch_sends_one_notification_per_change() {
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 8]));
+        let (tx, rx) = std::sync::mpsc::channel();
+        scanner.watch_value::<u32>(BASE + 4, tx);
+
+        // Nothing changed yet
+        scanner.refresh_values().unwrap();
+        assert!(rx.try_recv().is_err());
+
+        for value in [5u32, 5, 6, 6, 6, 5] {
+            process.set_value(BASE + 4, value);
+            scanner.refresh_values().unwrap();
+        }
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![(BASE + 4, 5), (BASE + 4, 6), (BASE + 4, 5)]
+        );
+
+        scanner.unwatch_value(BASE + 4);
+        process.set_value(BASE + 4, 7u32);
+        scanner.refresh_values().unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn watch_is_dropped_once_receiver_disconnects() {
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 8]));
+        let (tx, rx) = std::sync::mpsc::channel::<(u64, u32)>();
+        scanner.watch_value(BASE, tx);
+        drop(rx);
+        process.set_value(BASE, 1u32);
+        scanner.refresh_values().unwrap();
+        assert!(scanner.watches.is_empty());
+    }
+
+    #[test]
+    fn divisible_by_matches_multiples_of_integers() {
+        let filter = ScanFilter::divisible_by(5u32).unwrap();
+        assert!(filter.matches(&0, &0));
+        assert!(filter.matches(&25, &0));
+        assert!(!filter.matches(&26, &0));
+        let filter = ScanFilter::divisible_by(-4i32).unwrap();
+        assert!(filter.matches(&-8, &0));
+        assert!(filter.matches(&12, &0));
+        assert!(!filter.matches(&-6, &0));
+        assert!(ScanFilter::divisible_by(0u8).is_err());
+        // Floats never match, even when constructed directly
+        assert!(!ScanFilter::DivisibleBy(2.0f32).matches(&4.0, &0.0));
+        // The most negative value divided by -1 overflows, which must not panic
+        assert!(ScanFilter::divisible_by(-1i8)
+            .unwrap()
+            .matches(&i8::MIN, &0));
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn scan_logs_result_count() {
+        let (_, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![7, 0, 7, 7]));
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        assert!(logs_contain("Scan finished"));
+        assert!(logs_contain("results=3"));
+        assert!(logs_contain("regions_scanned=1"));
+    }
+
+    #[test]
+    fn region_results_are_subset_of_all_results() {
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![7, 0, 7, 0, 0, 0, 0, 7])
+                .with_region(BASE + 0x1000, vec![0, 7, 7, 0, 0, 7, 0, 0]),
+        );
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        let all = scanner.get_results::<u8>();
+
+        let second = MemoryRegion {
+            base_address: BASE + 0x1000,
+            size: 8,
+            protection: Protection::empty(),
+        };
+        let expected: Vec<(u64, u8)> = all
+            .iter()
+            .copied()
+            .filter(|(address, _)| *address >= BASE + 0x1000)
+            .collect();
+        assert_eq!(
+            scanner.get_results_for_region::<u8>(&second),
+            Some(expected)
+        );
+
+        // Part of a region only gets the hits within that part
+        let part = MemoryRegion {
+            base_address: BASE + 2,
+            size: 4,
+            protection: Protection::empty(),
+        };
+        assert_eq!(
+            scanner.get_results_for_region::<u8>(&part),
+            Some(vec![(BASE + 2, 7)])
+        );
+
+        let unscanned = MemoryRegion {
+            base_address: BASE + 0x2000,
+            size: 8,
+            protection: Protection::empty(),
+        };
+        assert_eq!(scanner.get_results_for_region::<u8>(&unscanned), None);
+    }
+
+    #[test]
+    fn is_hit_checks_owning_region() {
+        let (process, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, vec![7, 0, 0, 7])
+                .with_region(BASE + 0x1000, vec![7, 0, 7, 0]),
+        );
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+
+        for address in [BASE, BASE + 3, BASE + 0x1000, BASE + 0x1002] {
+            assert!(scanner.is_hit(address), "{:#x} should be a hit", address);
+        }
+        // Misses inside regions, before the first, between the two, and just past the last
+        for address in [
+            BASE + 1,
+            BASE + 0x1001,
+            BASE - 1,
+            BASE + 4,
+            BASE + 0xfff,
+            BASE + 0x1004,
+        ] {
+            assert!(
+                !scanner.is_hit(address),
+                "{:#x} shouldn't be a hit",
+                address
+            );
+        }
+
+        // A manual address overlapping a region doesn't hide the region's hits
+        scanner.add_manual_address::<u16>(BASE + 2).unwrap();
+        assert!(scanner.is_hit(BASE + 2));
+        assert!(scanner.is_hit(BASE + 3));
+        assert!(!scanner.is_hit(BASE + 1));
+
+        // Addresses which don't survive narrowing stop being hits
+        process.set_bytes(BASE + 3, &[1]);
+        scanner.scan(ScanFilter::Exact(7u8)).unwrap();
+        assert!(!scanner.is_hit(BASE + 3));
+        assert!(scanner.is_hit(BASE));
+    }
+
+    #[test]
+    fn scan_predicate_keeps_even_increasing_values() {
+        let values = [2u32, 3, 4, 6, 8, 10];
+        let memory: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.set_alignment(4);
+
+        // The first scan has no old values, so only the new value is checked
+        scanner
+            .scan_predicate(|new: &u32, _old: &u32| new % 2 == 0)
+            .unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u32>()),
+            vec![BASE, BASE + 8, BASE + 12, BASE + 16, BASE + 20]
+        );
+
+        // Increased and even, increased but odd, decreased, unchanged
+        process.set_value(BASE, 12u32);
+        process.set_value(BASE + 8, 5u32);
+        process.set_value(BASE + 12, 2u32);
+        process.set_value(BASE + 20, 20u32);
+        scanner
+            .scan_predicate(|new: &u32, old: &u32| new % 2 == 0 && new > old)
+            .unwrap();
+        assert_eq!(
+            scanner.get_results::<u32>(),
+            vec![(BASE, 12), (BASE + 20, 20)]
+        );
+    }
+
+    #[test]
+    fn filter_pointers_keeps_pointers_into_readable_memory() {
+        const READ_ONLY: u64 = 0x50000;
+        let values = [
+            BASE + 8,
+            READ_ONLY,
+            0,
+            0xdead_beef,
+            BASE + 0x1004,
+            BASE + 48,
+        ];
+        let memory: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let (_, mut scanner) = scanner_for(
+            MockProcess::new()
+                .with_region(BASE, memory)
+                .with_region(BASE + 0x1000, vec![0; 8])
+                .with_protected_region(READ_ONLY, vec![0; 8], Protection::READ),
+        );
+        assert!(scanner.filter_pointers().is_err());
+
+        scanner.set_alignment(8);
+        scanner.scan(ScanFilter::<u64>::Changed).unwrap();
+        scanner.filter_pointers().unwrap();
+        // Null, a non-pointer, and just past the end of a region are dropped. Read-only memory is still readable
+        assert_eq!(
+            scanner.get_results::<u64>(),
+            vec![
+                (BASE, BASE + 8),
+                (BASE + 8, READ_ONLY),
+                (BASE + 32, BASE + 0x1004)
+            ]
+        );
+
+        // It's undone like a scan
+        scanner.undo_scan().unwrap();
+        assert_eq!(scanner.count_results(), Some(values.len() + 1));
+    }
+
+    #[test]
+    fn scan_same_as_keeps_copies_of_reference() {
+        let values = [5u32, 42, 7, 42, 9, 42];
+        let memory: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, memory));
+        scanner.set_alignment(4);
+
+        scanner.scan_same_as::<u32>(BASE + 4).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u32>()),
+            vec![BASE + 4, BASE + 12, BASE + 20]
+        );
+
+        // The reference is read again, so only copies which follow it survive
+        process.set_value(BASE + 4, 43u32);
+        process.set_value(BASE + 20, 43u32);
+        scanner.scan_same_as::<u32>(BASE + 4).unwrap();
+        assert_eq!(
+            addresses(scanner.get_results::<u32>()),
+            vec![BASE + 4, BASE + 20]
+        );
+
+        assert!(scanner.scan_same_as::<u32>(BASE + 0x1000).is_err());
+    }
+
+    #[test]
+    fn write_bytes_lands_exactly_and_reads_back() {
+        let (process, scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0xcc; 8]));
+        scanner.write_bytes(BASE + 2, &[0x90, 0x90, 0x90]).unwrap();
+        assert_eq!(
+            process.read_memory_bytes(BASE, 8).unwrap(),
+            vec![0xcc, 0xcc, 0x90, 0x90, 0x90, 0xcc, 0xcc, 0xcc]
+        );
+        assert!(scanner.write_bytes(BASE + 0x1000, &[0x90]).is_err());
+    }
+
+    #[test]
+    fn nop_patch_round_trips_through_restore() {
+        let code = vec![0x48, 0x89, 0xc8, 0x90, 0xc3];
+        let (process, mut scanner) =
+            scanner_for(MockProcess::new().with_region(BASE, code.clone()));
+
+        let handle = scanner.nop_patch(BASE, 3).unwrap();
+        assert_eq!(
+            handle,
+            PatchHandle {
+                address: BASE,
+                original: vec![0x48, 0x89, 0xc8],
+            }
+        );
+        assert_eq!(
+            process.read_memory_bytes(BASE, 5).unwrap(),
+            vec![0x90, 0x90, 0x90, 0x90, 0xc3]
+        );
+        assert_eq!(scanner.active_patches(), &[handle.clone()]);
+
+        scanner.restore_patch(&handle).unwrap();
+        assert_eq!(process.read_memory_bytes(BASE, 5).unwrap(), code);
+        assert!(scanner.active_patches().is_empty());
+
+        assert!(scanner.nop_patch(BASE, 0).is_err());
+        assert!(scanner.nop_patch(BASE + 0x1000, 2).is_err());
+        assert!(scanner.active_patches().is_empty());
+    }
+
+    #[test]
+    fn restore_all_patches_unwinds_overlapping_patches() {
+        let code = vec![1, 2, 3, 4, 5, 6];
+        let (process, mut scanner) =
+            scanner_for(MockProcess::new().with_region(BASE, code.clone()));
+        scanner.nop_patch(BASE, 3).unwrap();
+        // Overlaps the first patch, so its saved bytes are partly NOPs
+        scanner.nop_patch(BASE + 2, 3).unwrap();
+        assert_eq!(
+            process.read_memory_bytes(BASE, 6).unwrap(),
+            vec![0x90, 0x90, 0x90, 0x90, 0x90, 6]
+        );
+
+        scanner.restore_all_patches().unwrap();
+        assert_eq!(process.read_memory_bytes(BASE, 6).unwrap(), code);
+        assert!(scanner.active_patches().is_empty());
+    }
+
+    #[test]
+    fn seeded_address_is_kept_whatever_the_alignment() {
+        let (process, mut scanner) = scanner_for(MockProcess::new().with_region(BASE, vec![0; 16]));
+        process.set_value(BASE + 2, 5u32);
+        scanner.seed_address::<u32>(BASE + 2).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE + 2, 5)]);
+        // Scans normally only find aligned values, but the seeded address was chosen on purpose
+        scanner.set_alignment(4);
+
+        process.set_value(BASE + 2, 6u32);
+        scanner.scan(ScanFilter::Changed::<u32>).unwrap();
+        assert_eq!(scanner.get_results::<u32>(), vec![(BASE + 2, 6)]);
+
+        // Once it stops changing, it's narrowed down like any other result
+        scanner.scan(ScanFilter::Changed::<u32>).unwrap();
+        assert_eq!(scanner.count_results(), Some(0));
     }
 }
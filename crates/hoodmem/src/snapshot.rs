@@ -0,0 +1,146 @@
+use crate::*;
+use std::collections::BTreeMap;
+
+/// A copy of memory regions at a point in time, which can be compared to memory later
+///
+/// Unlike scan results, snapshots are never narrowed down. Any two snapshots of the same regions can be compared
+pub struct Snapshot {
+    regions: BTreeMap<MemoryRegion, Vec<u8>>,
+}
+
+impl Snapshot {
+    /// Reads `regions` from `process`. Regions which can't be read are left out
+    pub fn capture(process: &dyn Process, regions: &[MemoryRegion]) -> Result<Self> {
+        let regions: BTreeMap<MemoryRegion, Vec<u8>> = regions
+            .iter()
+            .filter_map(|region| {
+                process
+                    .read_memory_bytes(region.base_address, region.size as usize)
+                    .ok()
+                    .map(|memory| (*region, memory))
+            })
+            .collect();
+        if regions.is_empty() {
+            anyhow::bail!("Failed to read any memory for the snapshot");
+        }
+        Ok(Self { regions })
+    }
+
+    /// Gets the regions in this snapshot
+    pub fn regions(&self) -> Vec<MemoryRegion> {
+        self.regions.keys().copied().collect()
+    }
+
+    /// Gets the total number of bytes in this snapshot
+    pub fn size(&self) -> u64 {
+        self.regions
+            .values()
+            .map(|memory| memory.len() as u64)
+            .sum()
+    }
+
+    /// Compares this snapshot with the current memory of `process`
+    ///
+    /// Returns each changed range of bytes as `(address, bytes in this snapshot, current bytes)`.
+    /// Fails if none of the regions can be read any more
+    pub fn diff(&self, process: &dyn Process) -> Result<Vec<(u64, Vec<u8>, Vec<u8>)>> {
+        let current = Snapshot::capture(process, &self.regions())?;
+        Ok(self.diff_snapshot(&current))
+    }
+
+    /// Compares this snapshot with a later one
+    ///
+    /// Returns each changed range of bytes as `(address, bytes in this snapshot, bytes in other)`.
+    /// Only regions in both snapshots are compared
+    pub fn diff_snapshot(&self, other: &Snapshot) -> Vec<(u64, Vec<u8>, Vec<u8>)> {
+        let mut changes = vec![];
+        for (region, old_memory) in self.regions.iter() {
+            let Some(new_memory) = other.regions.get(region) else {
+                continue;
+            };
+            let len = old_memory.len().min(new_memory.len());
+            let mut offset = 0;
+            while offset < len {
+                if old_memory[offset] == new_memory[offset] {
+                    offset += 1;
+                    continue;
+                }
+                let start = offset;
+                while offset < len && old_memory[offset] != new_memory[offset] {
+                    offset += 1;
+                }
+                changes.push((
+                    region.base_address + start as u64,
+                    old_memory[start..offset].to_vec(),
+                    new_memory[start..offset].to_vec(),
+                ));
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockProcess;
+
+    const BASE: u64 = 0x10000;
+
+    fn regions(process: &MockProcess) -> Vec<MemoryRegion> {
+        process.get_readable_regions()
+    }
+
+    #[test]
+    fn diff_finds_changed_byte_ranges() {
+        let process = MockProcess::new()
+            .with_region(BASE, vec![0; 16])
+            .with_region(BASE + 0x1000, vec![0; 16]);
+        let snapshot = Snapshot::capture(&process, &regions(&process)).unwrap();
+        assert_eq!(snapshot.size(), 32);
+        assert!(snapshot.diff(&process).unwrap().is_empty());
+
+        process.set_bytes(BASE + 2, &[1, 2, 3]);
+        process.set_bytes(BASE + 8, &[4]);
+        process.set_bytes(BASE + 0x100f, &[5]);
+        assert_eq!(
+            snapshot.diff(&process).unwrap(),
+            vec![
+                (BASE + 2, vec![0, 0, 0], vec![1, 2, 3]),
+                (BASE + 8, vec![0], vec![4]),
+                (BASE + 0x100f, vec![0], vec![5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn any_two_snapshots_can_be_compared() {
+        let process = MockProcess::new().with_region(BASE, vec![0; 8]);
+        let first = Snapshot::capture(&process, &regions(&process)).unwrap();
+        process.set_bytes(BASE, &[1]);
+        let second = Snapshot::capture(&process, &regions(&process)).unwrap();
+        process.set_bytes(BASE + 4, &[2]);
+        let third = Snapshot::capture(&process, &regions(&process)).unwrap();
+
+        assert_eq!(
+            first.diff_snapshot(&third),
+            vec![(BASE, vec![0], vec![1]), (BASE + 4, vec![0], vec![2])]
+        );
+        assert_eq!(
+            second.diff_snapshot(&third),
+            vec![(BASE + 4, vec![0], vec![2])]
+        );
+        assert_eq!(
+            third.diff_snapshot(&first),
+            vec![(BASE, vec![1], vec![0]), (BASE + 4, vec![2], vec![0])]
+        );
+    }
+
+    #[test]
+    fn diff_fails_once_memory_is_unreadable() {
+        let process = MockProcess::new().with_region(BASE, vec![0; 8]);
+        let snapshot = Snapshot::capture(&process, &regions(&process)).unwrap();
+        process.set_unreadable(BASE, 8);
+        assert!(snapshot.diff(&process).is_err());
+    }
+}
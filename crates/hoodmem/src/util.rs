@@ -1,4 +1,306 @@
-/// Reinterpret the memory at `t_ptr` as something else
-pub fn read_from_buffer<T: Copy>(buffer: &Vec<u8>, offset: u64) -> T {
-    unsafe { *std::mem::transmute::<u64, *const T>(buffer.as_ptr() as u64 + offset) }
+use crate::MemoryRegion;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Byte order values are stored in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// The byte order of the machine we are running on
+    pub fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+}
+
+/// Reinterpret the memory at `offset` in `buffer` as something else
+///
+/// The memory does not need to be aligned for `T`. Panics if `T` doesn't fit in the buffer at `offset`,
+/// so use [`try_read_from_buffer`] for buffers which might be short
+pub fn read_from_buffer<T: Copy>(buffer: &[u8], offset: u64) -> T {
+    match try_read_from_buffer(buffer, offset) {
+        Some(value) => value,
+        None => panic!(
+            "Reading {} bytes at offset {} overruns a {} byte buffer",
+            std::mem::size_of::<T>(),
+            offset,
+            buffer.len()
+        ),
+    }
+}
+
+/// Like [`read_from_buffer`], but returns `None` if `T` doesn't fit in the buffer at `offset`
+pub fn try_read_from_buffer<T: Copy>(buffer: &[u8], offset: u64) -> Option<T> {
+    let end = offset.checked_add(std::mem::size_of::<T>() as u64)?;
+    if end > buffer.len() as u64 {
+        return None;
+    }
+    Some(unsafe { std::ptr::read_unaligned(buffer.as_ptr().add(offset as usize) as *const T) })
+}
+
+/// Like [`read_from_buffer`], but byte-swaps the value if it is stored with a different endianness to ours
+pub fn read_from_buffer_endian<T: Copy>(
+    buffer: &[u8],
+    offset: u64,
+    endianness: Endianness,
+) -> T {
+    if endianness == Endianness::native() {
+        return read_from_buffer::<T>(buffer, offset);
+    }
+    let start = offset as usize;
+    let mut bytes = buffer[start..start + std::mem::size_of::<T>()].to_vec();
+    bytes.reverse();
+    read_from_buffer::<T>(&bytes, 0)
+}
+
+/// Like [`read_from_buffer_endian`], but returns `None` if `T` doesn't fit in the buffer at `offset`
+pub fn try_read_from_buffer_endian<T: Copy>(
+    buffer: &[u8],
+    offset: u64,
+    endianness: Endianness,
+) -> Option<T> {
+    if endianness == Endianness::native() {
+        return try_read_from_buffer::<T>(buffer, offset);
+    }
+    let start = offset as usize;
+    let mut bytes = buffer
+        .get(start..start.checked_add(std::mem::size_of::<T>())?)?
+        .to_vec();
+    bytes.reverse();
+    try_read_from_buffer::<T>(&bytes, 0)
+}
+
+/// Parses an array of bytes pattern such as `48 8B ?? C3`
+///
+/// Each space separated token is either a hex byte or a `??`/`?` wildcard, which is returned as `None`
+pub fn parse_aob(pattern: &str) -> Result<Vec<Option<u8>>> {
+    let parsed: Vec<Option<u8>> = pattern
+        .split_whitespace()
+        .map(|token| match token {
+            "??" | "?" => Ok(None),
+            _ => u8::from_str_radix(token, 16)
+                .map(Some)
+                .map_err(|_| anyhow::anyhow!("Invalid byte '{}' in AOB pattern", token)),
+        })
+        .collect::<Result<_>>()?;
+    if parsed.is_empty() {
+        anyhow::bail!("AOB pattern cannot be empty");
+    }
+    Ok(parsed)
+}
+
+/// Parses space separated hex bytes, such as `90 90 90`. Unlike [`parse_aob`], wildcards aren't allowed
+pub fn parse_hex_bytes(bytes: &str) -> Result<Vec<u8>> {
+    let parsed: Vec<u8> = bytes
+        .split_whitespace()
+        .map(|token| {
+            u8::from_str_radix(token, 16).map_err(|_| anyhow::anyhow!("Invalid byte '{}'", token))
+        })
+        .collect::<Result<_>>()?;
+    if parsed.is_empty() {
+        anyhow::bail!("Expected at least one byte");
+    }
+    Ok(parsed)
+}
+
+/// Merges regions which directly follow on from each other, so values straddling the boundary can be found
+///
+/// Regions are only merged if they have the same protection, and while the merged region stays within `max_merged_size` bytes
+pub fn merge_adjacent_regions(
+    mut regions: Vec<MemoryRegion>,
+    max_merged_size: u64,
+) -> Vec<MemoryRegion> {
+    regions.sort_by_key(|region| region.base_address);
+    let mut merged: Vec<MemoryRegion> = Vec::with_capacity(regions.len());
+    for region in regions {
+        if let Some(prev) = merged.last_mut() {
+            if prev.base_address + prev.size == region.base_address
+                && prev.protection == region.protection
+                && prev.size + region.size <= max_merged_size
+            {
+                prev.size += region.size;
+                continue;
+            }
+        }
+        merged.push(region);
+    }
+    merged
+}
+
+/// Bytes shown on each row of a [`hex_dump`]
+const HEX_DUMP_ROW_LEN: usize = 16;
+
+/// Formats `bytes`, read from `base`, as rows of address, hex bytes and ASCII
+///
+/// Bytes which aren't printable ASCII are shown as `.`. The hex column of a short final row is padded so the ASCII column lines up
+pub fn hex_dump(bytes: &[u8], base: u64) -> String {
+    bytes
+        .chunks(HEX_DUMP_ROW_LEN)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|byte| {
+                    if byte.is_ascii_graphic() || *byte == b' ' {
+                        *byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "0x{:016x}  {:<width$}  {}",
+                base + (row * HEX_DUMP_ROW_LEN) as u64,
+                hex.join(" "),
+                ascii,
+                width = HEX_DUMP_ROW_LEN * 3 - 1
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Protection;
+
+    #[test]
+    fn parse_aob_accepts_both_wildcards() {
+        assert_eq!(
+            parse_aob("48 8B ?? C3 ?").unwrap(),
+            vec![Some(0x48), Some(0x8b), None, Some(0xc3), None]
+        );
+    }
+
+    #[test]
+    fn parse_aob_rejects_bad_patterns() {
+        assert!(parse_aob("48 8G").is_err());
+        assert!(parse_aob("488B").is_err());
+        assert!(parse_aob("   ").is_err());
+    }
+
+    #[test]
+    fn read_from_buffer_endian_swaps_foreign_order() {
+        let buffer = vec![0xff, 0x12, 0x34, 0x56, 0x78];
+        assert_eq!(
+            read_from_buffer_endian::<u32>(&buffer, 1, Endianness::Big),
+            0x12345678
+        );
+        assert_eq!(
+            read_from_buffer_endian::<u32>(&buffer, 1, Endianness::Little),
+            0x78563412
+        );
+    }
+
+    #[test]
+    fn merge_adjacent_regions_joins_contiguous_maps() {
+        let rw = Protection::READ | Protection::WRITE;
+        let region = |base_address, size, protection| MemoryRegion {
+            base_address,
+            size,
+            protection,
+        };
+        let regions = vec![
+            region(0x3000, 0x1000, rw),
+            region(0x1000, 0x1000, rw),
+            region(0x2000, 0x1000, rw),
+            // A gap before this one
+            region(0x5000, 0x1000, rw),
+            // Contiguous, but with different protection
+            region(0x6000, 0x1000, Protection::READ),
+        ];
+        assert_eq!(
+            merge_adjacent_regions(regions.clone(), u64::MAX),
+            vec![
+                region(0x1000, 0x3000, rw),
+                region(0x5000, 0x1000, rw),
+                region(0x6000, 0x1000, Protection::READ),
+            ]
+        );
+
+        // Merging stops before a region would grow past the limit
+        assert_eq!(
+            merge_adjacent_regions(regions, 0x2000),
+            vec![
+                region(0x1000, 0x2000, rw),
+                region(0x3000, 0x1000, rw),
+                region(0x5000, 0x1000, rw),
+                region(0x6000, 0x1000, Protection::READ),
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_dump_pads_partial_rows_and_hides_unprintable_bytes() {
+        let mut bytes: Vec<u8> = b"Hello, world!\x00\x01\xff".to_vec();
+        bytes.extend_from_slice(b"ab\n");
+        let dump = hex_dump(&bytes, 0x1000);
+        let rows: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            rows,
+            vec![
+                "0x0000000000001000  48 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 00 01 ff  Hello, world!...",
+                "0x0000000000001010  61 62 0a                                         ab.",
+            ]
+        );
+        assert_eq!(hex_dump(&[], 0x1000), "");
+    }
+
+    #[test]
+    fn try_read_from_buffer_checks_bounds() {
+        let buffer = 0x12345678u32.to_le_bytes();
+        // Exact fit
+        assert_eq!(try_read_from_buffer::<u32>(&buffer, 0), Some(0x12345678));
+        assert_eq!(try_read_from_buffer::<u8>(&buffer, 3), Some(0x12));
+        // One byte short
+        assert_eq!(try_read_from_buffer::<u32>(&buffer[..3], 0), None);
+        assert_eq!(try_read_from_buffer::<u16>(&buffer, 3), None);
+        // Zero length
+        assert_eq!(try_read_from_buffer::<u8>(&[], 0), None);
+        assert_eq!(try_read_from_buffer::<u8>(&buffer, u64::MAX), None);
+    }
+
+    #[test]
+    fn try_read_from_buffer_endian_checks_bounds() {
+        let buffer = [0x12, 0x34, 0x56, 0x78];
+        assert_eq!(
+            try_read_from_buffer_endian::<u32>(&buffer, 0, Endianness::Big),
+            Some(0x12345678)
+        );
+        assert_eq!(
+            try_read_from_buffer_endian::<u32>(&buffer, 1, Endianness::Big),
+            None
+        );
+        assert_eq!(
+            try_read_from_buffer_endian::<u16>(&[], 0, Endianness::Little),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overruns")]
+    fn read_from_buffer_panics_past_end() {
+        read_from_buffer::<u32>(&vec![0; 3], 0);
+    }
+
+    #[test]
+    fn parse_hex_bytes_rejects_wildcards_and_empty_input() {
+        assert_eq!(
+            parse_hex_bytes("90 0f  1F").unwrap(),
+            vec![0x90, 0x0f, 0x1f]
+        );
+        assert!(parse_hex_bytes("90 ?? 90").is_err());
+        assert!(parse_hex_bytes("100").is_err());
+        assert!(parse_hex_bytes("  ").is_err());
+    }
 }
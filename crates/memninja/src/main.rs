@@ -14,25 +14,59 @@ use egui_tiles::{Behavior, Linear, Tile, TileId, Tiles, Tree};
 use hoodmem::scanner::ScanFilter;
 use hoodmem::Process;
 
-use memninja_core::utils::GenericScanFilter;
+use memninja_core::config::Config;
+use memninja_core::utils::{format_mem_value, format_result_value, GenericScanFilter};
 use memninja_core::{types::*, CoreCommand, CoreController};
+use strum::IntoEnumIterator;
 
 struct MemNinja {
     tree: egui_tiles::Tree<Pane>,
     tree_behaviour: TreeBehaviour,
 }
 
+/// How long a result value which failed to parse is shown in red
+const EDIT_ERROR_FLASH: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How many bytes the memory viewer shows
+const MEMORY_VIEWER_LEN: usize = 256;
+
+/// Starts a new scan, the same as the "New Scan" button
+const NEW_SCAN_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::N);
+
+/// How often the current value of each cheat is added to its history
+const CHEAT_HISTORY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
 struct TreeBehaviour {
     core: Option<CoreController>,
     process_id: String,
     window_name: String,
+    exe_path: String,
     attach_type: AttachType,
     attached_status: egui::RichText,
+    /// The last error reported by MemNinja Core, such as why attaching failed
+    last_error: String,
     scan_options: ScanOptions,
     scan_results: MemValues,
     min_results_index: usize,
     max_results_index: usize,
     cheats: Vec<Cheat>,
+    /// Address entered to add a cheat for directly, in hex
+    manual_address: String,
+    /// Where the cheats list is saved to and loaded from
+    cheat_table_path: String,
+    /// When cheat values were last added to their histories
+    last_history_sample: std::time::Instant,
+    /// How the current values of cheats are shown
+    cheats_display_format: DisplayFormat,
+    /// Address shown by the memory viewer, in hex
+    viewer_address: String,
+    /// Index of the cheat waiting for a key press to set its hotkey
+    capturing_hotkey: Option<usize>,
+    /// Defaults loaded at startup. The scan options are written back to it on exit
+    config: Config,
+    /// Whether the user is being asked to confirm throwing away the results for a new scan
+    confirming_new_scan: bool,
 }
 
 impl Behavior<Pane> for TreeBehaviour {
@@ -49,6 +83,7 @@ impl Behavior<Pane> for TreeBehaviour {
                 PaneType::Results => self.render_results_panel(ui),
                 PaneType::Scan => self.render_scanner_panel(ui),
                 PaneType::Cheats => self.render_cheats_panel(ui),
+                PaneType::Memory => self.render_memory_panel(ui),
             });
 
         egui_tiles::UiResponse::None
@@ -74,6 +109,7 @@ enum PaneType {
     Results,
     Scan,
     Cheats,
+    Memory,
 }
 
 struct Pane {
@@ -91,18 +127,211 @@ impl Pane {
             PaneType::Results => "Scan Results",
             PaneType::Scan => "Scanner",
             PaneType::Cheats => "Cheats",
+            PaneType::Memory => "Memory Viewer",
         }
     }
 }
 
 impl TreeBehaviour {
+    fn render_memory_panel(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Address");
+                ui.text_edit_singleline(&mut self.viewer_address);
+            });
+            let address = u64::from_str_radix(self.viewer_address.trim().trim_start_matches("0x"), 16);
+            let dump = match (address, self.core.as_ref()) {
+                (Ok(address), Some(core)) => match core.read_bytes(address, MEMORY_VIEWER_LEN) {
+                    Some(bytes) => hoodmem::util::hex_dump(&bytes, address),
+                    None => format!("Unable to read memory at 0x{:016x}", address),
+                },
+                (Err(_), _) => "Enter an address in hex".into(),
+                (_, None) => "MemNinja Core is not running".into(),
+            };
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.label(egui::RichText::new(dump).monospace());
+            });
+            // Keep the memory up to date as the process changes it
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(500));
+        });
+    }
+
+    /// Starts a new scan, first asking for confirmation if there are results which would be lost
+    fn request_new_scan(&mut self) {
+        if self.scan_results.new_scan_needs_confirmation() {
+            self.confirming_new_scan = true;
+        } else {
+            self.start_new_scan();
+        }
+    }
+
+    fn start_new_scan(&mut self) {
+        if let Some(core) = self.core.as_ref() {
+            let _ = core.send_command(CoreCommand::NewScan);
+        }
+        self.scan_results.clear();
+    }
+
+    /// Starts a new scan if its shortcut was pressed this frame
+    fn handle_new_scan_shortcut(&mut self, ctx: &egui::Context) {
+        let attached = self.core.as_ref().is_some_and(|core| core.check_attached());
+        if attached && ctx.input_mut(|input| input.consume_shortcut(&NEW_SCAN_SHORTCUT)) {
+            self.request_new_scan();
+        }
+    }
+
+    /// Asks whether to throw away the current results for a new scan, while waiting for confirmation
+    fn render_new_scan_confirmation(&mut self, ctx: &egui::Context) {
+        if !self.confirming_new_scan {
+            return;
+        }
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("New Scan")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Start a new scan? The current {} results will be lost",
+                    self.scan_results.total_results
+                ));
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Yes").clicked();
+                    cancelled = ui.button("No").clicked();
+                });
+            });
+        if confirmed {
+            self.start_new_scan();
+        }
+        if confirmed || cancelled {
+            self.confirming_new_scan = false;
+        }
+    }
+
+    /// Toggles the cheats whose hotkeys were pressed this frame
+    ///
+    /// Cheats sharing a hotkey are all toggled together
+    fn handle_cheat_hotkeys(&mut self, ctx: &egui::Context) {
+        // Don't toggle cheats while typing, or while a hotkey is being set
+        if self.capturing_hotkey.is_some() || ctx.wants_keyboard_input() {
+            return;
+        }
+        let mut hotkeys: Vec<egui::KeyboardShortcut> = vec![];
+        for hotkey in self.cheats.iter().filter_map(|cheat| cheat.hotkey) {
+            if !hotkeys.contains(&hotkey) {
+                hotkeys.push(hotkey);
+            }
+        }
+        for hotkey in hotkeys {
+            if !ctx.input_mut(|input| input.consume_shortcut(&hotkey)) {
+                continue;
+            }
+            for index in Cheat::bound_to(&self.cheats, &hotkey) {
+                let cheat = &mut self.cheats[index];
+                cheat.enabled = !cheat.enabled;
+                if let Some(core) = self.core.as_ref() {
+                    let _ = core.send_command(cheat.freeze_command(index));
+                }
+            }
+        }
+    }
+
+    /// Saves the cheats list to `cheat_table_path`
+    fn save_cheats(&mut self) {
+        let path = std::path::Path::new(self.cheat_table_path.trim());
+        match Cheat::save_table(&self.cheats, path) {
+            Ok(()) => self.last_error.clear(),
+            Err(err) => self.last_error = format!("Failed to save cheats: {:#}", err),
+        }
+    }
+
+    /// Replaces the cheats list with the one saved at `cheat_table_path`. Loaded cheats start disabled
+    fn load_cheats(&mut self) {
+        let path = std::path::Path::new(self.cheat_table_path.trim());
+        match Cheat::load_table(path) {
+            Ok(cheats) => {
+                if let Some(core) = self.core.as_ref() {
+                    // Cheats are frozen by index, so the old cheats must stop being written
+                    for index in 0..self.cheats.len() {
+                        let _ = core.send_command(CoreCommand::UnfreezeCheat(index));
+                    }
+                }
+                self.cheats = cheats;
+                self.capturing_hotkey = None;
+                self.last_error.clear();
+            }
+            Err(err) => self.last_error = format!("Failed to load cheats: {:#}", err),
+        }
+    }
+
+    /// Enables the cheat at `index`, frozen at whatever value it currently reads
+    fn freeze_cheat_at_current(&mut self, index: usize) {
+        let Some(core) = self.core.as_ref() else {
+            return;
+        };
+        let cheat = &mut self.cheats[index];
+        match core.freeze_at_current(index, &cheat.cheat_type) {
+            Ok(value) => {
+                cheat.frozen_input = match (cheat.cheat_type.mem_type(), &value) {
+                    (MemType::Bool, MemValue::U8(x)) => (*x != 0).to_string(),
+                    _ => value.to_string(),
+                };
+                cheat.frozen_value = Some(value);
+                cheat.enabled = true;
+            }
+            Err(err) => self.last_error = format!("{:#}", err),
+        }
+    }
+
     fn render_cheats_panel(&mut self, ui: &mut egui::Ui) {
         ui.vertical_centered_justified(|ui| {
             ui.heading("Cheats");
+            display_format_combo(ui, "Cheats Format", &mut self.cheats_display_format);
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.manual_address);
+                let address = u64::from_str_radix(
+                    self.manual_address.trim().trim_start_matches("0x"),
+                    16,
+                );
+                if ui.add_enabled(address.is_ok(), egui::Button::new("Add Address")).clicked() {
+                    if let Ok(addr) = address {
+                        self.cheats.push(Cheat {
+                            enabled: false,
+                            name: "New Cheat".into(),
+                            cheat_type: CheatType::for_address(
+                                addr,
+                                self.scan_options.value_type,
+                                &self.scan_results.modules,
+                            ),
+                            frozen_value: None,
+                            frozen_input: String::new(),
+                            history: Default::default(),
+                            hotkey: None,
+                        });
+                        self.manual_address.clear();
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Cheat Table");
+                ui.text_edit_singleline(&mut self.cheat_table_path);
+                let has_path = !self.cheat_table_path.trim().is_empty();
+                if ui.add_enabled(has_path, egui::Button::new("Save")).clicked() {
+                    self.save_cheats();
+                }
+                if ui.add_enabled(has_path, egui::Button::new("Load")).clicked() {
+                    self.load_cheats();
+                }
+            });
+            let sample_history = self.last_history_sample.elapsed() >= CHEAT_HISTORY_INTERVAL;
+            if sample_history {
+                self.last_history_sample = std::time::Instant::now();
+            }
             ui.push_id("CheatsUI", |ui| {
                 egui_extras::TableBuilder::new(ui)
                     .striped(true)
-                    .columns(Column::remainder().at_least(200.0), 4)
+                    .columns(Column::remainder().at_least(200.0), 8)
                     .sense(egui::Sense {
                         click: true,
                         drag: false,
@@ -123,13 +352,29 @@ impl TreeBehaviour {
                         header_row.col(|ui| {
                             ui.heading("Info");
                         });
+                        header_row.col(|ui| {
+                            ui.heading("Current Value");
+                        });
+                        header_row.col(|ui| {
+                            ui.heading("History");
+                        });
+                        header_row.col(|ui| {
+                            ui.heading("Frozen Value");
+                        });
+                        header_row.col(|ui| {
+                            ui.heading("Hotkey");
+                        });
                     })
                     .body(|tbody| {
                         tbody.rows(20.0, self.cheats.len(), |mut row| {
                             let row_index = row.index();
+                            let conflicted = self.cheats[row_index]
+                                .hotkey
+                                .is_some_and(|hotkey| Cheat::bound_to(&self.cheats, &hotkey).len() > 1);
                             let cheat = self.cheats[row_index].borrow_mut();
+                            let mut freeze_changed = false;
                             row.col(|ui| {
-                                ui.checkbox(&mut cheat.enabled, "");
+                                freeze_changed |= ui.checkbox(&mut cheat.enabled, "").changed();
                             });
                             row.col(|ui| {
                                 ui.label(&cheat.name);
@@ -140,11 +385,100 @@ impl TreeBehaviour {
                             row.col(|ui| {
                                 ui.label(cheat.get_summary());
                             });
+                            row.col(|ui| {
+                                let mem_type = cheat.cheat_type.mem_type();
+                                let current_value = self.core.as_ref().and_then(|core| {
+                                    let addr = core.resolve_cheat_address(&cheat.cheat_type).ok()?;
+                                    core.read_value(addr, mem_type)
+                                });
+                                if sample_history {
+                                    if let Some(value) = current_value.as_ref().and_then(MemValue::to_f64) {
+                                        cheat.record_value(value);
+                                    }
+                                }
+                                match current_value {
+                                    Some(MemValue::U8(value)) if mem_type == MemType::Bool => {
+                                        ui.label(format!("{}", value != 0))
+                                    }
+                                    Some(value) => {
+                                        ui.label(format_mem_value(&value, self.cheats_display_format))
+                                    }
+                                    None => ui.label("??"),
+                                };
+                            });
+                            row.col(|ui| {
+                                draw_sparkline(ui, &cheat.history);
+                            });
+                            row.col(|ui| {
+                                let mem_type = cheat.cheat_type.mem_type();
+                                if mem_type == MemType::Bool {
+                                    let mut checked = cheat.frozen_input == "true";
+                                    if ui.checkbox(&mut checked, "").changed() {
+                                        cheat.frozen_input = checked.to_string();
+                                        cheat.frozen_value = mem_type.parse_value(&cheat.frozen_input).ok();
+                                        freeze_changed = true;
+                                    }
+                                } else {
+                                    let input = ui.text_edit_singleline(&mut cheat.frozen_input);
+                                    if input.changed() {
+                                        cheat.frozen_value = mem_type.parse_value(&cheat.frozen_input).ok();
+                                        freeze_changed = true;
+                                    }
+                                }
+                            });
+                            row.col(|ui| {
+                                if self.capturing_hotkey == Some(row_index) {
+                                    ui.label("Press a key (Esc to clear)");
+                                    let pressed = ui.input(|input| {
+                                        input.events.iter().find_map(|event| match event {
+                                            egui::Event::Key {
+                                                key,
+                                                pressed: true,
+                                                modifiers,
+                                                ..
+                                            } => Some((*key, *modifiers)),
+                                            _ => None,
+                                        })
+                                    });
+                                    match pressed {
+                                        Some((egui::Key::Escape, _)) => {
+                                            cheat.hotkey = None;
+                                            self.capturing_hotkey = None;
+                                        }
+                                        Some((key, modifiers)) => {
+                                            cheat.hotkey = Some(egui::KeyboardShortcut::new(modifiers, key));
+                                            self.capturing_hotkey = None;
+                                        }
+                                        None => {}
+                                    }
+                                } else {
+                                    let text = match &cheat.hotkey {
+                                        Some(hotkey) => ui.ctx().format_shortcut(hotkey),
+                                        None => "Set hotkey".into(),
+                                    };
+                                    if ui.button(text).clicked() {
+                                        self.capturing_hotkey = Some(row_index);
+                                    }
+                                    if conflicted {
+                                        ui.colored_label(Color32::YELLOW, "⚠")
+                                            .on_hover_text("Another cheat has the same hotkey");
+                                    }
+                                }
+                            });
+                            if freeze_changed {
+                                if let Some(core) = self.core.as_ref() {
+                                    let _ = core.send_command(cheat.freeze_command(row_index));
+                                }
+                            }
 
                             if row.response().double_clicked() {}
                         });
                     });
             });
+            if !self.cheats.is_empty() {
+                // Keep the current values of cheats up to date
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(500));
+            }
         });
     }
 
@@ -165,11 +499,21 @@ impl TreeBehaviour {
                 cols[1].text_edit_singleline(&mut self.window_name);
             });
 
+            ui.columns(2, |cols| {
+                cols[0].radio_value(
+                    &mut self.attach_type,
+                    AttachType::ByExecutablePath,
+                    "By Executable Path",
+                );
+                cols[1].text_edit_singleline(&mut self.exe_path);
+            });
+
             ui.horizontal_wrapped(|ui| {
                 // Attached status, as well as an attached or detached button
                 if !self.core.as_ref().is_some_and(|core| core.check_attached()) {
                     // Not currently attached
                     if ui.button("Attach").clicked() {
+                        self.last_error.clear();
                         match self.attach_type {
                             AttachType::ByPID => {
                                 if let Ok(pid) = self.process_id.parse::<u32>() {
@@ -191,6 +535,13 @@ impl TreeBehaviour {
                                     )));
                                 }
                             }
+                            AttachType::ByExecutablePath => {
+                                if let Some(core) = self.core.as_ref() {
+                                    core.send_command(CoreCommand::Attach(
+                                        AttachTarget::ExecutablePath(self.exe_path.clone()),
+                                    ));
+                                }
+                            }
                         };
                     }
                 } else {
@@ -199,14 +550,17 @@ impl TreeBehaviour {
                         if let Some(core) = self.core.as_mut() {
                             core.send_command(CoreCommand::Detach);
                         }
+                        self.scan_results.clear();
                     }
                 }
 
                 if let Some(core) = self.core.as_ref() {
                     self.attached_status = match core.get_attach_status() {
-                        AttachStatus::Detached => {
-                            egui::RichText::new("Detached").color(egui::Color32::RED)
-                        }
+                        AttachStatus::Detached => match core.get_detach_reason() {
+                            Some(reason) => egui::RichText::new(format!("Detached ({})", reason))
+                                .color(egui::Color32::RED),
+                            None => egui::RichText::new("Detached").color(egui::Color32::RED),
+                        },
                         AttachStatus::Attached(_) => {
                             egui::RichText::new("Attached").color(egui::Color32::LIGHT_GREEN)
                         }
@@ -219,6 +573,12 @@ impl TreeBehaviour {
                 if self.attached_status.text().len() > 0 {
                     ui.label(self.attached_status.clone());
                 }
+                if let Some(err) = self.core.as_ref().and_then(|core| core.take_last_error()) {
+                    self.last_error = err;
+                }
+                if !self.last_error.is_empty() {
+                    ui.label(egui::RichText::new(&self.last_error).color(egui::Color32::RED));
+                }
             });
         });
     }
@@ -230,30 +590,77 @@ impl TreeBehaviour {
                 ui.heading("Memory Scanning");
                 ui.horizontal_top(|ui| {
                     ui.checkbox(&mut self.scan_options.is_hex, "Hex");
-                    // TODO: Allow for setting is_hex to true later. For now we don't support hex                    
-                    self.scan_options.is_hex = false;
-                    ui.text_edit_singleline(&mut self.scan_options.scan_input);
-                    if ui.button("Scan").clicked() {
+                    ui.checkbox(&mut self.scan_options.aligned, "Aligned");
+                    let input_valid = self.scan_options.scan_input_valid();
+                    let mut scan_input = egui::TextEdit::singleline(&mut self.scan_options.scan_input);
+                    if !input_valid {
+                        scan_input = scan_input.text_color(Color32::RED);
+                    }
+                    ui.add(scan_input);
+                    if self.scan_options.scan_type.needs_secondary_value() {
+                        ui.text_edit_singleline(&mut self.scan_options.secondary_input);
+                    }
+                    if ui.add_enabled(input_valid, egui::Button::new("Scan")).clicked() {
                         if let Some(core) = self.core.as_ref() {
-                            let mem_type = self.scan_options.value_type;
-                            let mem_value =
-                                mem_type.parse_value(&self.scan_options.scan_input).ok();
-                            let scan_filter = GenericScanFilter::new(
-                                self.scan_options.scan_type,
-                                mem_type,
-                                mem_value,
-                            );
-                            if let Ok(scan_filter) = scan_filter {
+                            if self.scan_options.scan_type == ScanType::ExactAddress {
+                                if let Some(address) = self.scan_options.seed_address() {
+                                    let _ = core.send_command(CoreCommand::SeedAddress(address, self.scan_options.value_type));
+                                }
+                            } else if let Ok(scan_filter) = self.scan_options.scan_filter() {
+                                let _ = core.send_command(CoreCommand::SetAlignment(self.scan_options.alignment()));
                                 let _ = core.send_command(CoreCommand::Scan(scan_filter));
                             };
                         }
                     }
-                    if ui.button("New Scan").clicked() {
+                    let scanning = self
+                        .core
+                        .as_ref()
+                        .is_some_and(|core| matches!(core.get_scan_status(), ScanStatus::Scanning(_)));
+                    if ui.add_enabled(scanning, egui::Button::new("Cancel")).clicked() {
+                        if let Some(core) = self.core.as_ref() {
+                            let _ = core.send_command(CoreCommand::CancelScan);
+                        }
+                    }
+                    let new_scan = ui
+                        .button("New Scan")
+                        .on_hover_text(ui.ctx().format_shortcut(&NEW_SCAN_SHORTCUT));
+                    if new_scan.clicked() {
+                        self.request_new_scan();
+                    }
+                    if ui.add_enabled(!scanning, egui::Button::new("Undo")).on_hover_text("Undo the last scan").clicked() {
+                        if let Some(core) = self.core.as_ref() {
+                            let _ = core.send_command(CoreCommand::UndoScan);
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    // Repeats the scan until stopped, such as `Unchanged` while waiting for an in-game event
+                    let auto_scanning = self.core.as_ref().is_some_and(|core| core.is_auto_scanning());
+                    let mut auto_scan = auto_scanning;
+                    let toggle = ui
+                        .add_enabled(self.scan_options.scan_input_valid() || auto_scanning, egui::Checkbox::new(&mut auto_scan, "Auto Scan"))
+                        .on_hover_text("Keep repeating the scan every interval");
+                    ui.label("Every (ms)");
+                    ui.add_enabled(
+                        !auto_scanning,
+                        egui::DragValue::new(&mut self.scan_options.auto_scan_ms).clamp_range(100..=60_000),
+                    );
+                    if toggle.changed() {
                         if let Some(core) = self.core.as_ref() {
-                            let _ = core.send_command(CoreCommand::NewScan);
+                            if !auto_scan {
+                                let _ = core.send_command(CoreCommand::StopAutoScan);
+                            } else if let Ok(scan_filter) = self.scan_options.scan_filter() {
+                                let _ = core.send_command(CoreCommand::SetAlignment(self.scan_options.alignment()));
+                                let _ = core.send_command(CoreCommand::AutoScan(
+                                    scan_filter,
+                                    std::time::Duration::from_millis(self.scan_options.auto_scan_ms),
+                                ));
+                            }
                         }
-                        self.scan_results.visible_results.clear();
-                        self.scan_results.num_results = "No results yet".into();
+                    }
+                    if auto_scanning {
+                        // Keep the toggle in sync if the core stops auto scanning by itself
+                        ui.ctx().request_repaint_after(std::time::Duration::from_millis(500));
                     }
                 });
                 ui.heading("Scan Options");
@@ -263,26 +670,13 @@ impl TreeBehaviour {
                         egui::ComboBox::from_id_source("Scan Type")
                             .selected_text(format!("{}", self.scan_options.scan_type))
                             .show_ui(&mut cols[1], |ui| {
-                                ui.selectable_value(
-                                    &mut self.scan_options.scan_type,
-                                    ScanType::Exact,
-                                    format!("{}", ScanType::Exact),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.scan_type,
-                                    ScanType::Unknown,
-                                    format!("{}", ScanType::Unknown),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.scan_type,
-                                    ScanType::Increased,
-                                    format!("{}", ScanType::Increased),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.scan_type,
-                                    ScanType::Decreased,
-                                    format!("{}", ScanType::Decreased),
-                                );
+                                for scan_type in ScanType::iter() {
+                                    ui.selectable_value(
+                                        &mut self.scan_options.scan_type,
+                                        scan_type,
+                                        format!("{}", scan_type),
+                                    );
+                                }
                             });
 
                         // Value Type
@@ -290,56 +684,14 @@ impl TreeBehaviour {
                         egui::ComboBox::from_id_source("Value Type")
                             .selected_text(format!("{}", self.scan_options.value_type))
                             .show_ui(&mut cols[1], |ui| {
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::U8,
-                                    format!("{}", MemType::U8),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::U16,
-                                    format!("{}", MemType::U16),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::U32,
-                                    format!("{}", MemType::U32),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::U64,
-                                    format!("{}", MemType::U64),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::I8,
-                                    format!("{}", MemType::I8),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::I16,
-                                    format!("{}", MemType::I16),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::I32,
-                                    format!("{}", MemType::I32),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::I64,
-                                    format!("{}", MemType::I64),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::F32,
-                                    format!("{}", MemType::F32),
-                                );
-                                ui.selectable_value(
-                                    &mut self.scan_options.value_type,
-                                    MemType::F64,
-                                    format!("{}", MemType::F64),
-                                );
+                                // Unknown is only used internally, so can't be scanned for
+                                for value_type in MemType::iter().filter(|value_type| *value_type != MemType::Unknown) {
+                                    ui.selectable_value(
+                                        &mut self.scan_options.value_type,
+                                        value_type,
+                                        format!("{}", value_type),
+                                    );
+                                }
                             });
                     });
                 });
@@ -348,35 +700,95 @@ impl TreeBehaviour {
     }
 
     fn render_results_panel(&mut self, ui: &mut egui::Ui) {
+        // Only fetch the results around the rows visible last frame
+        let page_start = self
+            .min_results_index
+            .saturating_sub(self.config.results_page_padding);
+        let page_end = self.max_results_index + self.config.results_page_padding;
+        let interval = self.config.results_refresh_interval();
+        let value_type = self.scan_options.value_type;
+        let page = ResultsPage {
+            num_results: self.scan_results.total_results as u64,
+            start: page_start,
+            end: page_end,
+            value_type,
+        };
+        // Values only change as often as the core refreshes them, so there's no need to fetch them every frame
+        let fetch_page = self.scan_results.fetch_due(&page, interval);
+        // Everything shown comes from one snapshot, so the status and results can't disagree
+        let snapshot = self.core.as_ref().map(|core| {
+            core.snapshot(fetch_page.then_some((value_type, page_start, page_end - page_start + 1)))
+        });
         ui.vertical_centered_justified(|ui| {
             ui.heading("Scan Results");
-            if let Some(core) = self.core.as_ref() {
-                let scan_status = core.get_scan_status();
+            display_format_combo(ui, "Results Format", &mut self.scan_results.display_format);
+            ui.horizontal(|ui| {
+                ui.label("Refresh Interval (ms)");
+                let interval = ui.add(
+                    egui::DragValue::new(&mut self.config.results_refresh_ms).clamp_range(50..=10_000),
+                );
+                if interval.changed() {
+                    if let Some(core) = self.core.as_ref() {
+                        let _ = core.send_command(CoreCommand::SetResultsRefreshInterval(
+                            self.config.results_refresh_interval(),
+                        ));
+                    }
+                }
+            });
+            if let Some(snapshot) = snapshot.as_ref() {
+                let scan_status = &snapshot.scan_status;
                 self.scan_results.scan_status = egui::RichText::new(format!("{}", scan_status))
                     .color(match scan_status {
-                        ScanStatus::Scanning => Color32::LIGHT_BLUE,
+                        ScanStatus::Scanning(_) => Color32::LIGHT_BLUE,
                         ScanStatus::Done(_) => Color32::LIGHT_GREEN,
                         ScanStatus::Failed(_) => Color32::RED,
+                        ScanStatus::Cancelled => Color32::YELLOW,
                         _ => Color32::WHITE,
                     });
             }
             if self.scan_results.scan_status.text().len() > 0 {
                 ui.label(self.scan_results.scan_status.clone());
             }
+            if let Some(stats) = snapshot.as_ref().and_then(|snapshot| snapshot.last_scan_stats) {
+                ui.label(stats.to_string());
+            }
+            if let Some(ScanStatus::Scanning(fraction)) = snapshot.as_ref().map(|snapshot| &snapshot.scan_status) {
+                ui.add(egui::ProgressBar::new(*fraction).show_percentage());
+                // Keep repainting so the progress bar moves without user input
+                ui.ctx().request_repaint();
+            }
             // ui.label(&self.scan_results.num_results);
         });
 
-        if let Some(core) = self.core.as_ref() {
-            let scan_status = core.get_scan_status();
+        if let (Some(core), Some(snapshot)) = (self.core.as_ref(), snapshot) {
+            let scan_status = snapshot.scan_status;
             if let ScanStatus::Done(num_results) = scan_status {
                 self.scan_results.num_results = format!("{} Results", num_results);
-                self.scan_results.visible_results = core.get_first_results(self.scan_options.value_type, 500);
+                self.scan_results.total_results = num_results as usize;
+                if let Some(results_page) = snapshot.results_page {
+                    self.scan_results.results_page_start = page_start;
+                    self.scan_results.visible_results = results_page;
+                    self.scan_results.modules = core.get_modules();
+                    // Keyed by the number of results in the snapshot, so a page fetched for an older scan is fetched again
+                    let fetched_page = ResultsPage { num_results, ..page };
+                    self.scan_results.last_fetch = Some((std::time::Instant::now(), fetched_page));
+                }
+                if num_results != page.num_results {
+                    // The page fetched was for a different number of results, so fetch it again next frame
+                    ui.ctx().request_repaint();
+                }
+                // Result values are refreshed by the core periodically, so keep the table up to date
+                ui.ctx().request_repaint_after(interval);
+            } else if let ScanStatus::Ready = scan_status {
+                // Nothing has been scanned since attaching, so any results shown are from an old process
+                self.scan_results.clear();
             }
         }
+        let mut visible_rows = (usize::MAX, 0);
         ui.push_id("ResultsUI", |ui| {
             egui_extras::TableBuilder::new(ui)
                 .striped(true)
-                .columns(Column::remainder().at_least(200.0), 2)
+                .columns(Column::remainder().at_least(200.0), 3)
                 .sense(egui::Sense {
                     click: true,
                     drag: false,
@@ -391,27 +803,127 @@ impl TreeBehaviour {
                     header_row.col(|ui| {
                         ui.heading("Value");
                     });
+                    header_row.col(|ui| {
+                        ui.heading("Module");
+                    });
                 })
                 .body(|tbody| {
-                    tbody.rows(20.0, self.scan_results.visible_results.len(), |mut row| {
+                    tbody.rows(20.0, self.scan_results.total_results, |mut row| {
                         let row_index = row.index();
-                        if let Some((addr, val)) = self.scan_results.visible_results.get(row_index)
+                        visible_rows = (visible_rows.0.min(row_index), visible_rows.1.max(row_index));
+                        if let Some((addr, val, delta)) = row_index
+                            .checked_sub(self.scan_results.results_page_start)
+                            .and_then(|page_index| self.scan_results.visible_results.get(page_index))
                         {
                             row.col(|ui| {
                                 ui.label(format!("0x{:016x}", addr));
                             });
                             row.col(|ui| {
-                                ui.label(format!("{}", val));
+                                let mem_type = self.scan_options.value_type;
+                                let write = |value: MemValue| {
+                                    if let Some(core) = self.core.as_ref() {
+                                        let _ = core.send_command(CoreCommand::WriteResult(*addr, value));
+                                    }
+                                };
+                                match &mut self.scan_results.editing {
+                                    _ if mem_type == MemType::Bool => {
+                                        let mut checked = matches!(val, MemValue::U8(value) if *value != 0);
+                                        if ui.checkbox(&mut checked, "").changed() {
+                                            write(MemValue::U8(checked as u8));
+                                        }
+                                    }
+                                    Some((editing_addr, input)) if *editing_addr == *addr => {
+                                        let flash_error = self
+                                            .scan_results
+                                            .edit_error
+                                            .is_some_and(|failed_at| failed_at.elapsed() < EDIT_ERROR_FLASH);
+                                        let mut text_edit = egui::TextEdit::singleline(input);
+                                        if flash_error {
+                                            text_edit = text_edit.text_color(Color32::RED);
+                                            ui.ctx().request_repaint_after(EDIT_ERROR_FLASH);
+                                        }
+                                        let response = ui.add(text_edit);
+                                        if !response.lost_focus() {
+                                            response.request_focus();
+                                        } else if !ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                                            // Clicked away or pressed escape
+                                            self.scan_results.editing = None;
+                                        } else {
+                                            let is_hex = self.scan_results.display_format == DisplayFormat::Hex;
+                                            match mem_type.parse_value_radix(input.trim(), is_hex) {
+                                                Ok(value) => {
+                                                    write(value);
+                                                    self.scan_results.editing = None;
+                                                }
+                                                Err(_) => {
+                                                    self.scan_results.edit_error = Some(std::time::Instant::now())
+                                                }
+                                            }
+                                        }
+                                    }
+                                    // String results aren't values which can be edited
+                                    _ if mem_type == MemType::String => {
+                                        ui.label(format_result_value(val, mem_type, self.scan_results.display_format));
+                                    }
+                                    _ => {
+                                        let text = format_result_value(val, mem_type, self.scan_results.display_format);
+                                        // Highlight values which changed in the last refresh
+                                        let text = match delta {
+                                            std::cmp::Ordering::Greater => egui::RichText::new(text).color(Color32::LIGHT_GREEN),
+                                            std::cmp::Ordering::Less => egui::RichText::new(text).color(Color32::LIGHT_RED),
+                                            std::cmp::Ordering::Equal => egui::RichText::new(text),
+                                        };
+                                        let label = ui
+                                            .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                            .on_hover_text("Click to edit");
+                                        if label.clicked() {
+                                            // Other formats show more than just the value, so start from scratch
+                                            let input = match self.scan_results.display_format {
+                                                DisplayFormat::Decimal => val.to_string(),
+                                                _ => String::new(),
+                                            };
+                                            self.scan_results.editing = Some((*addr, input));
+                                            self.scan_results.edit_error = None;
+                                        }
+                                    }
+                                }
                             });
-                            if row.response().double_clicked() {
+                            row.col(|ui| {
+                                if let Some(module) = hoodmem::find_module(&self.scan_results.modules, *addr) {
+                                    ui.label(format!("{}+0x{:x}", module.name, addr - module.base_address));
+                                }
+                            });
+                            let response = row.response();
+                            let mut add_cheat = response.double_clicked();
+                            let mut freeze = false;
+                            response.context_menu(|ui| {
+                                if ui.button("Add Cheat").clicked() {
+                                    add_cheat = true;
+                                    ui.close_menu();
+                                }
+                                if ui.button("Freeze at Current Value").clicked() {
+                                    freeze = true;
+                                    ui.close_menu();
+                                }
+                            });
+                            if add_cheat || freeze {
                                 self.cheats.push(Cheat {
                                     enabled: false,
                                     name: "New Cheat".into(),
-                                    cheat_type: CheatType::Simple {
-                                        addr: *addr,
-                                        mem_type: self.scan_options.value_type,
-                                    },
-                                })
+                                    // Module relative cheats keep working after the process is restarted
+                                    cheat_type: CheatType::for_address(
+                                        *addr,
+                                        self.scan_options.value_type,
+                                        &self.scan_results.modules,
+                                    ),
+                                    frozen_value: None,
+                                    frozen_input: String::new(),
+                                    history: Default::default(),
+                                    hotkey: None,
+                                });
+                                if freeze {
+                                    self.freeze_cheat_at_current(self.cheats.len() - 1);
+                                }
                             }
                         } else {
                             row.col(|ui| {
@@ -420,10 +932,19 @@ impl TreeBehaviour {
                             row.col(|ui| {
                                 ui.label("null");
                             });
+                            row.col(|_| {});
                         }
                     });
                 });
         });
+        if visible_rows.0 <= visible_rows.1
+            && (visible_rows.0, visible_rows.1) != (self.min_results_index, self.max_results_index)
+        {
+            // Fetch the newly visible results next frame
+            self.min_results_index = visible_rows.0;
+            self.max_results_index = visible_rows.1;
+            ui.ctx().request_repaint();
+        }
 
         ui.add_space(20.0);
     }
@@ -435,6 +956,8 @@ fn create_tree() -> Tree<Pane> {
     let scan_pane = tiles.insert_pane(Pane::from_type(PaneType::Scan));
     let results_pane = tiles.insert_pane(Pane::from_type(PaneType::Results));
     let cheats_pane = tiles.insert_pane(Pane::from_type(PaneType::Cheats));
+    let memory_pane = tiles.insert_pane(Pane::from_type(PaneType::Memory));
+    let bottom = tiles.insert_tab_tile(vec![cheats_pane, memory_pane]);
 
     let layout_left = Linear {
         children: vec![attach_pane, results_pane],
@@ -456,7 +979,7 @@ fn create_tree() -> Tree<Pane> {
     )));
 
     let layout = Linear {
-        children: vec![top, cheats_pane],
+        children: vec![top, bottom],
         dir: egui_tiles::LinearDir::Vertical,
         ..Default::default()
     };
@@ -465,55 +988,272 @@ fn create_tree() -> Tree<Pane> {
     Tree::new("root", root, tiles)
 }
 
-#[derive(Default)]
 struct ScanOptions {
     value_type: MemType,
     scan_type: ScanType,
     is_hex: bool,
+    /// Only find values aligned to their size, which is faster and skips hits which are almost never real
+    aligned: bool,
     scan_input: String,
+    secondary_input: String,
+    /// How often auto scanning repeats the scan, in milliseconds
+    auto_scan_ms: u64,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            value_type: Default::default(),
+            scan_type: Default::default(),
+            is_hex: false,
+            aligned: true,
+            scan_input: Default::default(),
+            secondary_input: Default::default(),
+            auto_scan_ms: 1000,
+        }
+    }
 }
 
 #[derive(Default)]
 struct MemValues {
     scan_status: egui::RichText,
     num_results: String,
-    visible_results: Vec<(u64, String)>,
+    /// The page of results around the visible rows of the results table. Values are only formatted when drawn
+    /// Each with how its value changed in the last refresh
+    visible_results: Vec<(u64, MemValue, std::cmp::Ordering)>,
+    /// Index of the first result in `visible_results`
+    results_page_start: usize,
+    total_results: usize,
+    /// Modules of the attached process, used to show which module each result is in
+    modules: Vec<hoodmem::Module>,
+    /// How result values are shown
+    display_format: DisplayFormat,
+    /// The address of the result being edited, and the new value entered so far
+    editing: Option<(u64, String)>,
+    /// When the edited value last failed to parse
+    edit_error: Option<std::time::Instant>,
+    /// When `visible_results` was last fetched, and which page was fetched
+    last_fetch: Option<(std::time::Instant, ResultsPage)>,
+}
+
+/// A page of results fetched from MemNinja Core
+#[derive(PartialEq)]
+struct ResultsPage {
+    num_results: u64,
+    start: usize,
+    end: usize,
+    value_type: MemType,
+}
+
+impl MemValues {
+    /// Clears the results shown, such as when starting a new scan
+    fn clear(&mut self) {
+        self.visible_results.clear();
+        self.total_results = 0;
+        self.num_results = "No results yet".into();
+        self.last_fetch = None;
+    }
+
+    /// Whether starting a new scan should be confirmed first, because it would throw away results
+    fn new_scan_needs_confirmation(&self) -> bool {
+        self.total_results > 0
+    }
+
+    /// Whether `page` should be fetched, because a different page is shown or the last fetch is `interval` old
+    fn fetch_due(&self, page: &ResultsPage, interval: std::time::Duration) -> bool {
+        match &self.last_fetch {
+            Some((fetched_at, fetched_page)) => {
+                fetched_page != page || fetched_at.elapsed() >= interval
+            }
+            None => true,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Alignment of the values to scan for, in bytes
+    fn alignment(&self) -> u64 {
+        if self.aligned {
+            self.value_type.alignment() as u64
+        } else {
+            1
+        }
+    }
+
+    /// The address entered for an `ExactAddress` scan, in hex
+    fn seed_address(&self) -> Option<u64> {
+        u64::from_str_radix(self.scan_input.trim().trim_start_matches("0x"), 16).ok()
+    }
+
+    /// Builds the filter for a scan with these options
+    fn scan_filter(&self) -> anyhow::Result<GenericScanFilter> {
+        let mem_type = self.value_type;
+        let is_hex = self.is_hex;
+        let mem_value = if self.scan_type == ScanType::Percent {
+            // The main value is the percentage, and the secondary value is the maximum
+            MemType::F64.parse_value(&self.scan_input).ok()
+        } else {
+            mem_type.parse_value_radix(&self.scan_input, is_hex).ok()
+        };
+        let secondary_value = if self.scan_type == ScanType::Rounded {
+            // The secondary value is the number of decimal places
+            MemType::U32.parse_value(&self.secondary_input).ok()
+        } else {
+            mem_type.parse_value_radix(&self.secondary_input, is_hex).ok()
+        };
+        if mem_type == MemType::Vec3F32
+            && matches!(self.scan_type, ScanType::Exact | ScanType::Approximate)
+        {
+            // Vectors can have unknown components, so are parsed separately
+            let tolerance = match self.scan_type {
+                ScanType::Exact => Some(0.0),
+                _ => self.secondary_input.trim().parse::<f32>().ok(),
+            };
+            GenericScanFilter::vec3(&self.scan_input, tolerance)
+        } else if self.scan_type == ScanType::ExactAny {
+            self.scan_input
+                .split(',')
+                .map(|value| mem_type.parse_value_radix(value.trim(), is_hex))
+                .collect::<anyhow::Result<Vec<MemValue>>>()
+                .and_then(GenericScanFilter::exact_any)
+        } else {
+            GenericScanFilter::new(self.scan_type, mem_type, mem_value, secondary_value)
+        }
+    }
+
+    /// Whether the scan value parses as the selected value type, or isn't needed by the scan type
+    fn scan_input_valid(&self) -> bool {
+        let input = self.scan_input.as_str();
+        let mem_type = self.value_type;
+        match self.scan_type {
+            scan_type if !scan_type.needs_value() => true,
+            // The percentage is always a float, whatever the value type
+            ScanType::Percent => MemType::F64.parse_value(input).is_ok(),
+            ScanType::ExactAddress => {
+                self.seed_address().is_some() && !matches!(mem_type, MemType::String | MemType::Unknown)
+            }
+            ScanType::ExactAny => input
+                .split(',')
+                .all(|value| mem_type.parse_value_radix(value.trim(), self.is_hex).is_ok()),
+            ScanType::Exact | ScanType::Approximate if mem_type == MemType::Vec3F32 => {
+                parse_vec3(input).is_ok()
+            }
+            _ => mem_type.parse_value_radix(input, self.is_hex).is_ok(),
+        }
+    }
+
+    fn from_config(config: &Config) -> Self {
+        Self {
+            value_type: config.value_type,
+            scan_type: config.scan_type,
+            is_hex: config.is_hex,
+            aligned: config.aligned,
+            ..Default::default()
+        }
+    }
 }
 
 impl Default for MemNinja {
     fn default() -> Self {
+        let config = Config::load();
         let mut core = CoreController::default();
         core.start().expect("Failure starting MemNinja Core");
+        let _ = core.send_command(CoreCommand::SetResultsRefreshInterval(
+            config.results_refresh_interval(),
+        ));
         Self {
             tree: create_tree(),
             tree_behaviour: TreeBehaviour {
                 core: Some(core),
                 process_id: Default::default(),
                 window_name: Default::default(),
+                exe_path: Default::default(),
                 attach_type: Default::default(),
                 attached_status: Default::default(),
-                scan_options: Default::default(),
+                last_error: Default::default(),
+                scan_options: ScanOptions::from_config(&config),
                 scan_results: Default::default(),
                 min_results_index: 0,
                 max_results_index: 0,
                 cheats: vec![],
+                manual_address: Default::default(),
+                cheat_table_path: "cheats.json".into(),
+                last_history_sample: std::time::Instant::now(),
+                cheats_display_format: Default::default(),
+                viewer_address: Default::default(),
+                capturing_hotkey: None,
+                config,
+                confirming_new_scan: false,
             },
         }
     }
 }
 
 
+/// Shows a combo box for picking how values are displayed
+fn display_format_combo(ui: &mut Ui, id: &str, display_format: &mut DisplayFormat) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(format!("{}", display_format))
+        .show_ui(ui, |ui| {
+            for format in DisplayFormat::iter() {
+                ui.selectable_value(display_format, format, format!("{}", format));
+            }
+        });
+}
+
+/// Draws `values` as a line filling the available width, scaled so the smallest and largest values span the height
+///
+/// Fewer than 2 values, or values which never change, are drawn as a flat line
+fn draw_sparkline(ui: &mut Ui, values: &std::collections::VecDeque<f64>) {
+    let (rect, _) = ui.allocate_exact_size(
+        Vec2::new(ui.available_width(), ui.available_height().min(16.0)),
+        egui::Sense::hover(),
+    );
+    let stroke = egui::Stroke::new(1.0, ui.visuals().text_color());
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if values.len() < 2 || max <= min {
+        ui.painter().hline(rect.x_range(), rect.center().y, stroke);
+        return;
+    }
+    let step = rect.width() / (values.len() - 1) as f32;
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let height = ((value - min) / (max - min)) as f32;
+            egui::pos2(rect.left() + i as f32 * step, rect.bottom() - height * rect.height())
+        })
+        .collect();
+    ui.painter().add(egui::Shape::line(points, stroke));
+}
+
 impl App for MemNinja {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.tree_behaviour.handle_cheat_hotkeys(ctx);
+        self.tree_behaviour.handle_new_scan_shortcut(ctx);
         // Main app panel
         egui::CentralPanel::default().show(ctx, |ui| {
             // Tree UI
             self.tree.ui(&mut self.tree_behaviour, ui);
         });
+        self.tree_behaviour.render_new_scan_confirmation(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        let behaviour = &mut self.tree_behaviour;
+        behaviour.config.value_type = behaviour.scan_options.value_type;
+        behaviour.config.scan_type = behaviour.scan_options.scan_type;
+        behaviour.config.is_hex = behaviour.scan_options.is_hex;
+        behaviour.config.aligned = behaviour.scan_options.aligned;
+        if let Err(err) = behaviour.config.save() {
+            tracing::warn!("Failed to save config: {:#}", err);
+        }
     }
 }
 
 fn main() -> eframe::Result<()> {
+    tracing_subscriber::fmt::init();
     let native_options = eframe::NativeOptions {
         hardware_acceleration: eframe::HardwareAcceleration::Preferred,
         viewport: egui::ViewportBuilder {
@@ -531,3 +1271,75 @@ fn main() -> eframe::Result<()> {
         Box::new(|_cc| Box::new(MemNinja::default())),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn page(start: usize) -> ResultsPage {
+        ResultsPage { num_results: 1000, start, end: start + 100, value_type: MemType::U32 }
+    }
+
+    fn fetched(ago: Duration, page: ResultsPage) -> MemValues {
+        MemValues { last_fetch: Some((Instant::now() - ago, page)), ..Default::default() }
+    }
+
+    #[test]
+    fn fetch_due_waits_for_interval() {
+        let interval = Duration::from_millis(250);
+        assert!(MemValues::default().fetch_due(&page(0), interval));
+        assert!(!fetched(Duration::ZERO, page(0)).fetch_due(&page(0), interval));
+        assert!(fetched(Duration::from_millis(300), page(0)).fetch_due(&page(0), interval));
+    }
+
+    #[test]
+    fn fetch_due_when_page_changes() {
+        let interval = Duration::from_secs(60);
+        assert!(fetched(Duration::ZERO, page(0)).fetch_due(&page(50), interval));
+        let mut values = fetched(Duration::ZERO, page(0));
+        values.clear();
+        assert!(values.fetch_due(&page(0), interval));
+    }
+
+    #[test]
+    fn new_scan_confirmed_only_with_results() {
+        let mut values = MemValues::default();
+        assert!(!values.new_scan_needs_confirmation());
+        values.total_results = 1;
+        assert!(values.new_scan_needs_confirmation());
+        values.clear();
+        assert!(!values.new_scan_needs_confirmation());
+    }
+
+    fn options(value_type: MemType, scan_type: ScanType, scan_input: &str) -> ScanOptions {
+        ScanOptions { value_type, scan_type, scan_input: scan_input.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn scan_input_valid_parses_as_value_type() {
+        assert!(options(MemType::I32, ScanType::Exact, "-5").scan_input_valid());
+        assert!(!options(MemType::U32, ScanType::Exact, "-5").scan_input_valid());
+        assert!(!options(MemType::U8, ScanType::Exact, "256").scan_input_valid());
+        assert!(options(MemType::F32, ScanType::Exact, "1.5").scan_input_valid());
+        assert!(!options(MemType::I64, ScanType::Exact, "1.5").scan_input_valid());
+        assert!(!options(MemType::U32, ScanType::Exact, "").scan_input_valid());
+        assert!(!options(MemType::U32, ScanType::Exact, "abc").scan_input_valid());
+        let mut hex = options(MemType::U32, ScanType::Exact, "ff");
+        assert!(!hex.scan_input_valid());
+        hex.is_hex = true;
+        assert!(hex.scan_input_valid());
+    }
+
+    #[test]
+    fn scan_input_valid_by_scan_type() {
+        // Scans which compare with the last scan don't need a value at all
+        assert!(options(MemType::U32, ScanType::Unchanged, "abc").scan_input_valid());
+        assert!(options(MemType::U32, ScanType::ExactAny, "1, 2,3").scan_input_valid());
+        assert!(!options(MemType::U32, ScanType::ExactAny, "1, -2").scan_input_valid());
+        // The percentage is a float even for integer types
+        assert!(options(MemType::U32, ScanType::Percent, "12.5").scan_input_valid());
+        assert!(options(MemType::Vec3F32, ScanType::Exact, "1, 2, 3").scan_input_valid());
+        assert!(!options(MemType::Vec3F32, ScanType::Exact, "1, 2").scan_input_valid());
+    }
+}
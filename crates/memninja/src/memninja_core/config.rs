@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::types::{MemType, ScanType};
+
+/// How many results either side of the visible rows are fetched by default
+pub const DEFAULT_RESULTS_PAGE_PADDING: usize = 50;
+
+/// How often the values of scan results are re-read by default, in milliseconds
+pub const DEFAULT_RESULTS_REFRESH_MS: u64 = 500;
+
+/// Defaults for MemNinja, loaded from `config.toml` at startup and written back on exit
+///
+/// Fields missing from the file keep their defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub value_type: MemType,
+    pub scan_type: ScanType,
+    pub is_hex: bool,
+    pub aligned: bool,
+    /// How many results either side of the visible rows are fetched, so scrolling a little doesn't show missing rows
+    pub results_page_padding: usize,
+    /// How often the values of scan results are re-read from memory, in milliseconds
+    pub results_refresh_ms: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            value_type: Default::default(),
+            scan_type: Default::default(),
+            is_hex: false,
+            aligned: true,
+            results_page_padding: DEFAULT_RESULTS_PAGE_PADDING,
+            results_refresh_ms: DEFAULT_RESULTS_REFRESH_MS,
+        }
+    }
+}
+
+impl Config {
+    /// Gets where the config file is kept, such as `~/.config/memninja/config.toml`
+    ///
+    /// `None` if there is no home or config directory
+    pub fn path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+        Some(config_dir.join("memninja").join("config.toml"))
+    }
+
+    /// Loads the config file, falling back to the defaults if it is missing or malformed
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Self::from_toml(&contents).unwrap_or_else(|err| {
+                tracing::warn!("Ignoring malformed config {}: {:#}", path.display(), err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Writes the config file, creating its directory if needed
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().ok_or_else(|| anyhow::anyhow!("No config directory found"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    pub fn results_refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.results_refresh_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_round_trips_through_toml() {
+        let config = Config {
+            value_type: MemType::I32,
+            scan_type: ScanType::Exact,
+            is_hex: true,
+            aligned: false,
+            results_page_padding: 20,
+            results_refresh_ms: 250,
+        };
+        let contents = config.to_toml().unwrap();
+        assert_eq!(Config::from_toml(&contents).unwrap(), config);
+    }
+
+    #[test]
+    fn config_fills_missing_fields_with_defaults() {
+        let config = Config::from_toml("is_hex = true").unwrap();
+        assert_eq!(
+            config,
+            Config {
+                is_hex: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(Config::from_toml("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn config_rejects_malformed_toml() {
+        assert!(Config::from_toml("value_type = [").is_err());
+        assert!(Config::from_toml("results_refresh_ms = \"soon\"").is_err());
+    }
+}
@@ -1,22 +1,52 @@
+pub mod config;
 pub mod types;
 pub mod utils;
 
+use std::collections::HashMap;
 use std::iter;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use hoodmem::scanner::ScanFilter;
-use hoodmem::Process;
+use hoodmem::scanner::{ScanFilter, ScanOutcome, ScanProgress, ScanStats};
+use hoodmem::{Module, Process};
 use types::*;
 
-use self::utils::GenericScanFilter;
+use self::utils::{format_result_value, GenericScanFilter, ResultDeltas};
+
+/// How often the core thread wakes up to do periodic work, such as freezing cheats
+const CORE_TICK_INTERVAL: Duration = Duration::from_millis(10);
 
 pub struct Core {
     process: Option<Arc<dyn Process>>,
     scanner: Option<hoodmem::scanner::Scanner>,
     attach_status: AttachStatus,
-    scan_status: ScanStatus
+    /// Why the core detached without being asked to, such as the process exiting
+    detach_reason: Option<String>,
+    /// Error from the last command which failed, until it is taken by the UI
+    last_error: Option<String>,
+    scan_status: ScanStatus,
+    /// Progress of the current scan, if one is in progress
+    scan_progress: Option<ScanProgress>,
+    /// Set to cancel the current scan. Shared with the `CoreController` so it can be set while a scan is running
+    cancel_scan: Arc<AtomicBool>,
+    /// Bytes to keep writing to a cheat's address, keyed by cheat id
+    frozen_cheats: HashMap<usize, (CheatType, Vec<u8>)>,
+    /// How often frozen cheats are re-written
+    freeze_interval: Duration,
+    last_freeze: Instant,
+    /// How often the values of scan results are re-read from memory
+    results_refresh_interval: Duration,
+    last_results_refresh: Instant,
+    /// Modules mapped into the attached process. Refreshed along with the scan results, as libraries can be loaded at any time
+    modules: Vec<Module>,
+    /// Filter to keep scanning with, and how often, until auto scanning is stopped
+    auto_scan: Option<(GenericScanFilter, Duration)>,
+    last_auto_scan: Instant,
+    /// How the values of the results looked at changed in the last refresh
+    result_deltas: ResultDeltas,
 }
 
 impl Default for Core {
@@ -25,7 +55,20 @@ impl Default for Core {
             process: Default::default(),
             scanner: Default::default(),
             attach_status: Default::default(),
-            scan_status: Default::default()
+            detach_reason: None,
+            last_error: None,
+            scan_status: Default::default(),
+            scan_progress: None,
+            cancel_scan: Default::default(),
+            frozen_cheats: Default::default(),
+            freeze_interval: Duration::from_millis(100),
+            last_freeze: Instant::now(),
+            results_refresh_interval: Duration::from_millis(500),
+            last_results_refresh: Instant::now(),
+            modules: vec![],
+            auto_scan: None,
+            last_auto_scan: Instant::now(),
+            result_deltas: Default::default(),
         }
     }
 }
@@ -46,15 +89,19 @@ impl Core {
                         self.attach_status = AttachStatus::Attached(target.clone());
                         Ok(())
                     }
-                    _ => Err(anyhow::anyhow!(
-                        "Attach not yet implemented for target: {:?}",
-                        target
-                    )),
+                    AttachTarget::ExecutablePath(path) => {
+                        self.process = Some(hoodmem::attach_external_by_exe_path(path)?);
+                        self.attach_status = AttachStatus::Attached(target.clone());
+                        Ok(())
+                    }
                 };
                 if let Some(process) = &self.process {
                     self.scanner = Some(hoodmem::scanner::Scanner::new(process.clone()));
+                    self.modules = process.get_modules();
+                    self.detach_reason = None;
+                    tracing::debug!(?target, "Attached");
                 };
-                Ok(())
+                attach_status
             }
             AttachStatus::Attached(target) => {
                 Err(anyhow::anyhow!("Already attached to {:?}", target))
@@ -66,11 +113,288 @@ impl Core {
     }
 
     /// Detach from the current process
+    ///
+    /// Results from the old process are dropped with the scanner, so the scan status is reset too
     pub fn detach(&mut self) {
         self.process = None;
         self.scanner = None;
+        self.modules.clear();
+        self.detach_reason = None;
         self.attach_status = AttachStatus::Detached;
+        self.scan_status = ScanStatus::Ready;
+        self.scan_progress = None;
+        self.auto_scan = None;
+        self.result_deltas.clear();
+        tracing::debug!("Detached");
+    }
+
+    /// Performs a scan with the given filter
+    ///
+    /// The scanner is taken out of the core for the duration of the scan, so that the core lock is not held while scanning.
+    /// This lets the scan status and progress be queried while a scan is in progress
+    fn scan(core: &Mutex<Core>, filter: &GenericScanFilter) -> Result<()> {
+        let lock_core = || {
+            core.lock()
+                .map_err(|_| anyhow::anyhow!("Failed to accquire MemNinja Core lock"))
+        };
+        let (mut scanner, cancel_scan) = {
+            let mut core = lock_core()?;
+            match core.scanner.take() {
+                Some(scanner) => {
+                    core.scan_status = ScanStatus::Scanning(0.0);
+                    core.scan_progress = Some(Default::default());
+                    // Only cancel requests made from here on apply to this scan
+                    core.cancel_scan.store(false, Ordering::Relaxed);
+                    (scanner, core.cancel_scan.clone())
+                }
+                None => return Ok(()),
+            }
+        };
+
+        let result = filter.scan_cancellable(&mut scanner, &cancel_scan, |progress| {
+            if let Ok(mut core) = core.lock() {
+                core.scan_status = ScanStatus::Scanning(progress.fraction());
+                core.scan_progress = Some(progress);
+            }
+        });
+        let num_results = scanner.count_results().unwrap_or(0);
+
+        let mut core = lock_core()?;
+        core.scanner = Some(scanner);
+        core.scan_progress = None;
+        // Scanning reads the values of the results again too
+        core.result_deltas.next_refresh();
+        core.scan_status = match result {
+            Ok(ScanOutcome::Cancelled) => ScanStatus::Cancelled,
+            Ok(ScanOutcome::Completed) => ScanStatus::Done(num_results as u64),
+            Err(err) => ScanStatus::Failed(err.to_string()),
+        };
+        Ok(())
+    }
+
+    /// Gets the auto scan filter if it is time to scan with it again
+    ///
+    /// Never due while a scan is in progress, so auto scans can't overlap each other or a scan the user started
+    fn take_due_auto_scan(&mut self) -> Option<GenericScanFilter> {
+        let (filter, interval) = self.auto_scan.as_ref()?;
+        if self.scanner.is_none() || self.last_auto_scan.elapsed() < *interval {
+            return None;
+        }
+        self.last_auto_scan = Instant::now();
+        Some(filter.clone())
+    }
+
+    /// Runs the auto scan if one is due. Like `scan`, the core lock isn't held while scanning
+    ///
+    /// Auto scanning stops if a scan fails, so the same error isn't reported every interval
+    fn auto_scan(core: &Mutex<Core>) -> Result<()> {
+        let filter = match core.lock() {
+            Ok(mut core) => core.take_due_auto_scan(),
+            Err(_) => anyhow::bail!("Failed to accquire MemNinja Core lock"),
+        };
+        let Some(filter) = filter else {
+            return Ok(());
+        };
+        Core::scan(core, &filter)?;
+        let mut core = core
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to accquire MemNinja Core lock"))?;
+        if let ScanStatus::Failed(err) = &core.scan_status {
+            let err = anyhow::anyhow!("Auto scan stopped: {}", err);
+            core.auto_scan = None;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Performs periodic work. Called by the core thread every `CORE_TICK_INTERVAL`
+    pub fn tick(&mut self) {
+        if self.last_freeze.elapsed() >= self.freeze_interval {
+            self.last_freeze = Instant::now();
+            self.write_frozen_cheats();
+        }
+        if self.last_results_refresh.elapsed() >= self.results_refresh_interval {
+            self.last_results_refresh = Instant::now();
+            self.detach_if_exited();
+            self.refresh_results();
+            self.refresh_modules();
+        }
+    }
+
+    /// Detaches if the attached process has exited, since nothing can be read from it anymore
+    fn detach_if_exited(&mut self) {
+        if self.process.as_ref().is_some_and(|process| !process.is_alive()) {
+            tracing::info!("Attached process exited");
+            self.detach();
+            self.detach_reason = Some("Process exited".into());
+        }
+    }
+
+    /// Re-reads the values of the current scan results, so they don't go stale between scans
+    fn refresh_results(&mut self) {
+        // The scanner is taken out of the core while scanning, so this never races a scan
+        if let Some(scanner) = &mut self.scanner {
+            match scanner.refresh_values() {
+                Ok(()) => self.result_deltas.next_refresh(),
+                Err(err) => tracing::warn!("Failed to refresh scan results: {:?}", err),
+            }
+        }
+    }
+
+    fn refresh_modules(&mut self) {
+        if let Some(process) = &self.process {
+            self.modules = process.get_modules();
+        }
+    }
+
+    /// Gets the address a cheat currently points at
+    ///
+    /// Module relative cheats are resolved using the modules of the attached process, so fail while the module isn't loaded.
+    /// Conditional cheats point at the value they watch
+    pub fn resolve_cheat_address(&self, cheat: &CheatType) -> Result<u64> {
+        match cheat {
+            CheatType::Simple { addr, .. } => Ok(*addr),
+            CheatType::Conditional { watch_addr, .. } => Ok(*watch_addr),
+            CheatType::ModuleRelative { module, offset, .. } => self
+                .modules
+                .iter()
+                .find(|loaded| loaded.name == *module)
+                .map(|loaded| loaded.base_address + offset)
+                .ok_or_else(|| anyhow::anyhow!("Module '{}' is not loaded", module)),
+        }
+    }
+
+    /// Starts a new scan whose only result is the `mem_type` value at `addr`, so it can be narrowed down by later scans
+    pub fn seed_address(&mut self, addr: u64, mem_type: MemType) -> Result<()> {
+        let scanner = self
+            .scanner
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Not attached to a process"))?;
+        let result = match mem_type {
+            MemType::U8 | MemType::Bool => scanner.seed_address::<u8>(addr),
+            MemType::U16 => scanner.seed_address::<u16>(addr),
+            MemType::U32 => scanner.seed_address::<u32>(addr),
+            MemType::U64 => scanner.seed_address::<u64>(addr),
+            MemType::I8 => scanner.seed_address::<i8>(addr),
+            MemType::I16 => scanner.seed_address::<i16>(addr),
+            MemType::I32 => scanner.seed_address::<i32>(addr),
+            MemType::I64 => scanner.seed_address::<i64>(addr),
+            MemType::F32 => scanner.seed_address::<f32>(addr),
+            MemType::F64 => scanner.seed_address::<f64>(addr),
+            MemType::Vec3F32 => scanner.seed_address::<[f32; 3]>(addr),
+            MemType::String | MemType::Unknown => Err(anyhow::anyhow!("Cannot seed an address with a {} value", mem_type)),
+        };
+        self.scan_status = match &result {
+            Ok(_) => ScanStatus::Done(scanner.count_results().unwrap_or(0) as u64),
+            Err(err) => ScanStatus::Failed(err.to_string()),
+        };
+        self.result_deltas.clear();
+        result
+    }
+
+    /// Writes `value` to the scan result at `addr`, then re-reads the results so the new value shows straight away
+    pub fn write_result(&mut self, addr: u64, value: &MemValue) -> Result<()> {
+        let scanner = self
+            .scanner
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Not attached to a process"))?;
+        if let MemValue::String(_) | MemValue::Null = value {
+            anyhow::bail!("Cannot write {} to a scan result", value)
+        }
+        scanner.process().write_memory_bytes(addr, &value.to_le_bytes()?)?;
+        scanner.refresh_values()
+    }
+
+    /// Gets up to `limit` results starting from the result at index `offset`, ordered by address
+    ///
+    /// Bools are read as `MemValue::U8`. String results have no value of their own, so are `MemValue::Null`
+    pub fn results_page_values(&self, scan_type: MemType, offset: usize, limit: usize) -> Vec<(u64, MemValue)> {
+        if let Some(scanner) = self.scanner.as_ref() {
+            match scan_type {
+                MemType::U8 | MemType::Bool => scanner.results_page::<u8>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::U8(v))).collect(),
+                MemType::U16 => scanner.results_page::<u16>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::U16(v))).collect(),
+                MemType::U32 => scanner.results_page::<u32>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::U32(v))).collect(),
+                MemType::U64 => scanner.results_page::<u64>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::U64(v))).collect(),
+                MemType::I8 => scanner.results_page::<i8>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::I8(v))).collect(),
+                MemType::I16 => scanner.results_page::<i16>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::I16(v))).collect(),
+                MemType::I32 => scanner.results_page::<i32>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::I32(v))).collect(),
+                MemType::I64 => scanner.results_page::<i64>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::I64(v))).collect(),
+                MemType::F32 => scanner.results_page::<f32>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::F32(v))).collect(),
+                MemType::F64 => scanner.results_page::<f64>(offset, limit).into_iter().map(|(addr, v)| (addr, MemValue::F64(v))).collect(),
+                MemType::Vec3F32 => scanner.get_results_vec3().into_iter().skip(offset).take(limit).map(|(addr, v)| (addr, MemValue::Vec3F32(v))).collect(),
+                MemType::String => scanner.results_page::<u8>(offset, limit).into_iter().map(|(addr, _)| (addr, MemValue::Null)).collect(),
+                MemType::Unknown => vec![],
+            }
+        }
+        else {
+            vec![]
+        }
+    }
+
+    /// Same as `results_page_values`, but also with how each value changed in the last refresh. See [`ResultDeltas::apply`]
+    pub fn results_page_with_delta(&mut self, scan_type: MemType, offset: usize, limit: usize) -> Vec<(u64, MemValue, std::cmp::Ordering)> {
+        let results = self.results_page_values(scan_type, offset, limit);
+        self.result_deltas.apply(results)
     }
+
+    /// Freezes a cheat at whatever value it currently reads, returning that value
+    ///
+    /// Identified by a cheat id, like `CoreCommand::FreezeCheat`
+    pub fn freeze_at_current(&mut self, id: usize, cheat: &CheatType) -> Result<MemValue> {
+        if let CheatType::Conditional { .. } = cheat {
+            anyhow::bail!("Conditional cheats write their own value");
+        }
+        let process = self
+            .process
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Not attached to a process"))?;
+        let addr = self.resolve_cheat_address(cheat)?;
+        let value = read_mem_value(process.as_ref(), addr, cheat.mem_type())
+            .ok_or_else(|| anyhow::anyhow!("Failed to read a {} at 0x{:016x}", cheat.mem_type(), addr))?;
+        self.frozen_cheats.insert(id, (cheat.clone(), value.to_le_bytes()?));
+        Ok(value)
+    }
+
+    /// Re-writes the frozen value of every frozen cheat
+    fn write_frozen_cheats(&self) {
+        if let Some(process) = &self.process {
+            for (cheat, bytes) in self.frozen_cheats.values() {
+                if let CheatType::Conditional {
+                    watch_addr,
+                    compare,
+                    threshold,
+                    write_addr,
+                    write_value,
+                } = cheat
+                {
+                    let watch_type = MemType::from(threshold.clone());
+                    let triggered = read_mem_value(process.as_ref(), *watch_addr, watch_type)
+                        .is_some_and(|current| compare.evaluate(&current, threshold));
+                    if let (true, Ok(bytes)) = (triggered, write_value.to_le_bytes()) {
+                        let _ = process.write_memory_bytes(*write_addr, &bytes);
+                    }
+                    continue;
+                }
+                // The module may not be loaded yet, or the address may not be mapped yet (or anymore), so just try again next time
+                if let Ok(addr) = self.resolve_cheat_address(cheat) {
+                    let _ = process.write_memory_bytes(addr, bytes);
+                }
+            }
+        }
+    }
+}
+
+/// A consistent view of MemNinja Core, taken while holding the core lock once. See [`CoreController::snapshot`]
+#[derive(Debug, Clone, Default)]
+pub struct CoreSnapshot {
+    pub attach_status: AttachStatus,
+    pub scan_status: ScanStatus,
+    /// Progress of the current scan, if one is in progress
+    pub scan_progress: Option<ScanProgress>,
+    /// Statistics about the last completed scan. `None` while scanning
+    pub last_scan_stats: Option<ScanStats>,
+    /// The page of results asked for, if any, with how each value changed in the last refresh
+    pub results_page: Option<Vec<(u64, MemValue, std::cmp::Ordering)>>,
 }
 
 pub struct CoreController {
@@ -78,15 +402,28 @@ pub struct CoreController {
     core_thread: Option<JoinHandle<()>>,
     running: bool,
     core_tx: Option<crossbeam_channel::Sender<CoreCommand>>,
+    /// Cancels the current scan when set. Shared with the core
+    cancel_scan: Arc<AtomicBool>,
 }
 
 impl Default for CoreController {
     fn default() -> Self {
+        let core = Core::default();
+        let cancel_scan = core.cancel_scan.clone();
         Self {
-            core: Default::default(),
+            core: Arc::new(Mutex::new(core)),
             core_thread: None,
             running: false,
             core_tx: None,
+            cancel_scan,
+        }
+    }
+}
+
+impl Drop for CoreController {
+    fn drop(&mut self) {
+        if self.running {
+            let _ = self.stop();
         }
     }
 }
@@ -97,20 +434,40 @@ impl CoreController {
         let (tx, rx) = crossbeam_channel::unbounded::<CoreCommand>();
         self.core_tx = Some(tx);
         let core = self.core.clone();
+        let ticker = crossbeam_channel::tick(CORE_TICK_INTERVAL);
         self.core_thread = Some(std::thread::spawn(move || loop {
-            let command = rx.recv();
-            if let Ok(mut core) = core.lock() {
-                if let Ok(command) = command {
-                    let result = command.execute(&mut core);
-                    if let Err(err) = result {
-                        eprintln!("Failed to execute command {:?}. Error: {:?}", command, err);
+            let stop = crossbeam_channel::select! {
+                recv(rx) -> command => match command {
+                    // Stop once asked to, or once the controller is gone and no more commands can arrive
+                    Ok(CoreCommand::Stop) | Err(_) => true,
+                    Ok(command) => {
+                        let result = command.execute_shared(&core);
+                        if let Err(err) = result {
+                            tracing::warn!("Command failed: {:#}", err);
+                            match core.lock() {
+                                Ok(mut core) => core.last_error = Some(format!("{:#}", err)),
+                                Err(_) => tracing::warn!("MemNinja Core lock is poisoned, dropping error"),
+                            }
+                        }
+                        false
                     }
-                }
-            } else {
-                eprintln!(
-                    "Failed to accquire MemNinja Core lock. Dropping command: {:?}",
-                    command
-                );
+                },
+                recv(ticker) -> _ => {
+                    match core.lock() {
+                        Ok(mut core) => core.tick(),
+                        Err(_) => tracing::warn!("MemNinja Core lock is poisoned, skipping tick"),
+                    }
+                    if let Err(err) = Core::auto_scan(&core) {
+                        tracing::warn!("{:#}", err);
+                        if let Ok(mut core) = core.lock() {
+                            core.last_error = Some(format!("{:#}", err));
+                        }
+                    }
+                    false
+                },
+            };
+            if stop {
+                break;
             }
         }));
         self.running = true;
@@ -141,8 +498,14 @@ impl CoreController {
 
     /// Sends a command to MemNinja Core
     pub fn send_command(&self, command: CoreCommand) -> Result<()> {
-        if let Some(tx) = self.core_tx.as_ref() {
-            tx.send(command)?;
+        if let CoreCommand::CancelScan | CoreCommand::Detach | CoreCommand::Stop = command {
+            // The core thread is busy with the scan, so this has to take effect immediately.
+            // Detaching and stopping cancel the scan too, as they can't be processed until the scan stops
+            self.cancel_scan.store(true, Ordering::Relaxed);
+        }
+        match self.core_tx.as_ref() {
+            Some(tx) => tx.send(command)?,
+            None => tracing::warn!(?command, "MemNinja Core is not running, dropping command"),
         }
         Ok(())
     }
@@ -156,6 +519,24 @@ impl CoreController {
         }
     }
 
+    /// Gets why MemNinja Core last detached by itself, if it did since the last attach
+    pub fn get_detach_reason(&self) -> Option<String> {
+        if let Ok(core) = self.core.lock() {
+            core.detach_reason.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Takes the error from the last command which failed, so it is only returned once
+    pub fn take_last_error(&self) -> Option<String> {
+        if let Ok(mut core) = self.core.lock() {
+            core.last_error.take()
+        } else {
+            None
+        }
+    }
+
     /// Gets the scan status of MemNinja Core
     pub fn get_scan_status(&self) -> ScanStatus {
         if let Ok(core) = self.core.lock() {
@@ -165,33 +546,112 @@ impl CoreController {
         }
     }
 
-    /// Gets the first n results
-    pub fn get_first_results(&self, scan_type: MemType, n: usize) -> Vec<(u64, String)> {
+    /// Whether MemNinja Core is repeatedly scanning, after `CoreCommand::AutoScan`
+    pub fn is_auto_scanning(&self) -> bool {
+        self.core.lock().is_ok_and(|core| core.auto_scan.is_some())
+    }
+
+    /// Gets the progress of the current scan, if a scan is in progress
+    pub fn get_scan_progress(&self) -> Option<ScanProgress> {
         if let Ok(core) = self.core.lock() {
-            if let Some(scanner) = core.scanner.as_ref() {
-                match scan_type {
-                    MemType::U8 => scanner.get_first_results::<u8>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::U16 => scanner.get_first_results::<u16>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::U32 => scanner.get_first_results::<u32>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::U64 => scanner.get_first_results::<u32>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::I8 => scanner.get_first_results::<i8>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::I16 => scanner.get_first_results::<i16>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::I32 => scanner.get_first_results::<i32>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::I64 => scanner.get_first_results::<i64>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::F32 => scanner.get_first_results::<f32>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::F64 => scanner.get_first_results::<f64>(n).iter().map(|(addr, v)| (*addr, format!("{:#?}", v))).collect(),
-                    MemType::Unknown => vec![],
-                }
-            }
-            else {
-                vec![]
-            }
+            core.scan_progress
+        } else {
+            None
+        }
+    }
+
+    /// Gets statistics about the last completed scan. `None` while scanning, as the scanner is busy
+    pub fn get_last_scan_stats(&self) -> Option<ScanStats> {
+        let core = self.core.lock().ok()?;
+        core.scanner.as_ref()?.last_scan_stats()
+    }
+
+    /// Gets up to `limit` results starting from the result at index `offset`. See [`Core::results_page_values`]
+    pub fn get_results_page_values(&self, scan_type: MemType, offset: usize, limit: usize) -> Vec<(u64, MemValue)> {
+        if let Ok(core) = self.core.lock() {
+            core.results_page_values(scan_type, offset, limit)
         }
         else {
             vec![]
         }
     }
 
+    /// Gets the attach status, scan status and optionally a page of results, all from the same moment
+    ///
+    /// The getters for each lock the core separately, so a scan can finish between them. `results_page` is
+    /// the value type, offset and limit of the page to fetch, as for `get_results_page_values`
+    pub fn snapshot(&self, results_page: Option<(MemType, usize, usize)>) -> CoreSnapshot {
+        let Ok(mut core) = self.core.lock() else {
+            return CoreSnapshot::default();
+        };
+        CoreSnapshot {
+            attach_status: core.attach_status.clone(),
+            scan_status: core.scan_status.clone(),
+            scan_progress: core.scan_progress,
+            last_scan_stats: core.scanner.as_ref().and_then(|scanner| scanner.last_scan_stats()),
+            results_page: results_page.map(|(scan_type, offset, limit)| core.results_page_with_delta(scan_type, offset, limit)),
+        }
+    }
+
+    /// Gets up to `limit` results starting from the result at index `offset`, with how each value changed in the last
+    /// refresh. `Greater` means it increased and `Less` means it decreased. See [`Core::results_page_with_delta`]
+    pub fn get_results_with_delta(&self, scan_type: MemType, offset: usize, limit: usize) -> Vec<(u64, MemValue, std::cmp::Ordering)> {
+        match self.core.lock() {
+            Ok(mut core) => core.results_page_with_delta(scan_type, offset, limit),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Same as `get_results_page_values`, but with each value formatted for display
+    pub fn get_results_page(&self, scan_type: MemType, offset: usize, limit: usize, format: DisplayFormat) -> Vec<(u64, String)> {
+        self.get_results_page_values(scan_type, offset, limit)
+            .into_iter()
+            .map(|(addr, value)| (addr, format_result_value(&value, scan_type, format)))
+            .collect()
+    }
+
+    /// Reads the current value at `addr` from the attached process
+    ///
+    /// Returns `None` if not attached, the address can't be read, or `mem_type` has no fixed size
+    pub fn read_value(&self, addr: u64, mem_type: MemType) -> Option<MemValue> {
+        let core = self.core.lock().ok()?;
+        let process = core.process.as_ref()?;
+        read_mem_value(process.as_ref(), addr, mem_type)
+    }
+
+    /// Reads `len` bytes from the attached process starting at `addr`
+    ///
+    /// Returns `None` if not attached or the memory can't be read
+    pub fn read_bytes(&self, addr: u64, len: usize) -> Option<Vec<u8>> {
+        let core = self.core.lock().ok()?;
+        core.process.as_ref()?.read_memory_bytes(addr, len).ok()
+    }
+
+    /// Gets the address a cheat currently points at. See [`Core::resolve_cheat_address`]
+    pub fn resolve_cheat_address(&self, cheat: &CheatType) -> Result<u64> {
+        match self.core.lock() {
+            Ok(core) => core.resolve_cheat_address(cheat),
+            Err(_) => Err(anyhow::anyhow!("MemNinja Core lock is poisoned")),
+        }
+    }
+
+    /// Freezes a cheat at its current value, returning that value. See [`Core::freeze_at_current`]
+    pub fn freeze_at_current(&self, id: usize, cheat: &CheatType) -> Result<MemValue> {
+        match self.core.lock() {
+            Ok(mut core) => core.freeze_at_current(id, cheat),
+            Err(_) => Err(anyhow::anyhow!("MemNinja Core lock is poisoned")),
+        }
+    }
+
+    /// Gets the modules mapped into the attached process, ordered by address
+    pub fn get_modules(&self) -> Vec<Module> {
+        if let Ok(core) = self.core.lock() {
+            core.modules.clone()
+        } else {
+            vec![]
+        }
+    }
+
     /// Checks whether MemNinja core is currently attached to something
     pub fn check_attached(&self) -> bool {
         if let Ok(core) = self.core.lock() {
@@ -211,19 +671,62 @@ impl CoreController {
 pub enum CoreCommand {
     /// Attach to a process
     Attach(AttachTarget),
-    /// Detach from ther current process
+    /// Detach from ther current process. Cancels the scan in progress, if any
     Detach,
     /// The unknown command. Does nothing
     Unknown,
-    /// Shut down the MemNinja core thread
+    /// Shut down the MemNinja core thread. Cancels the scan in progress, if any
     Stop,
     /// Initializes a new scan
     NewScan,
     /// Performs a scan with the given `GenericScanFilter`
     Scan(GenericScanFilter),
+    /// Keeps writing the given bytes to the cheat's address, until unfrozen. Identified by a cheat id
+    FreezeCheat(usize, CheatType, Vec<u8>),
+    /// Stops writing the frozen value of the cheat with the given id
+    UnfreezeCheat(usize),
+    /// Sets how often frozen cheats are re-written
+    SetFreezeInterval(Duration),
+    /// Sets how often the values of scan results are re-read from memory
+    SetResultsRefreshInterval(Duration),
+    /// Only finds values at addresses which are a multiple of the given number of bytes
+    SetAlignment(u64),
+    /// Cancels the scan in progress, if any. Takes effect as soon as it is sent via `CoreController::send_command`
+    CancelScan,
+    /// Re-reads the values of the current scan results without narrowing them down
+    RefreshResults,
+    /// Writes a value to the scan result at the given address
+    WriteResult(u64, MemValue),
+    /// Restores the scan results from before the last scan
+    UndoScan,
+    /// Keeps scanning with the given filter every interval, such as `Unchanged` to weed out values until an in-game event
+    AutoScan(GenericScanFilter, Duration),
+    /// Stops auto scanning. A scan already in progress still finishes
+    StopAutoScan,
+    /// Starts a new scan whose only result is the value of the given type at the given address
+    SeedAddress(u64, MemType),
 }
 
 impl CoreCommand {
+    /// Executes this command on the shared core, only holding the core lock for as long as needed
+    pub fn execute_shared(&self, core: &Mutex<Core>) -> anyhow::Result<()> {
+        match self {
+            CoreCommand::Scan(filter) => Core::scan(core, filter),
+            _ => {
+                let mut core = core.lock().map_err(|_| {
+                    anyhow::anyhow!(
+                        "Failed to accquire MemNinja Core lock. Dropping command: {:?}",
+                        self
+                    )
+                })?;
+                self.execute(&mut core)
+            }
+        }
+    }
+
+    /// Executes this command on a core which is already locked
+    ///
+    /// Scans are rejected, as they need `execute_shared` to release the lock while scanning
     pub fn execute(&self, core: &mut Core) -> anyhow::Result<()> {
         match self {
             CoreCommand::Attach(target) => {
@@ -233,26 +736,62 @@ impl CoreCommand {
                 core.detach();
             },
             CoreCommand::Stop => {
-                // TODO: I guess something probably could be done here.
+                // Handled by the core thread, which stops instead of executing it
             }
             CoreCommand::Unknown => {
-                eprintln!("Attempted to run an unknown command");
+                tracing::warn!("Attempted to run an unknown command");
             },
             CoreCommand::NewScan => {
                 if let Some(scanner) = &mut core.scanner {
                     scanner.new_scan();
                 }
             },
-            CoreCommand::Scan(filter) => {
-                core.scan_status = ScanStatus::Scanning;
+            CoreCommand::Scan(_) => {
+                anyhow::bail!("Scans can only be run with execute_shared");
+            }
+            CoreCommand::FreezeCheat(id, cheat, bytes) => {
+                core.frozen_cheats.insert(*id, (cheat.clone(), bytes.clone()));
+                core.write_frozen_cheats();
+            }
+            CoreCommand::UnfreezeCheat(id) => {
+                core.frozen_cheats.remove(id);
+            }
+            CoreCommand::SetFreezeInterval(interval) => {
+                core.freeze_interval = *interval;
+            }
+            CoreCommand::SetResultsRefreshInterval(interval) => {
+                core.results_refresh_interval = *interval;
+            }
+            CoreCommand::SetAlignment(alignment) => {
+                if let Some(scanner) = &mut core.scanner {
+                    scanner.set_alignment(*alignment);
+                }
+            }
+            CoreCommand::CancelScan => {
+                // Already handled when the command was sent
+            }
+            CoreCommand::RefreshResults => {
+                core.refresh_results();
+            }
+            CoreCommand::WriteResult(addr, value) => {
+                core.write_result(*addr, value)?;
+            }
+            CoreCommand::AutoScan(filter, interval) => {
+                core.auto_scan = Some((filter.clone(), *interval));
+                core.last_auto_scan = Instant::now();
+            }
+            CoreCommand::StopAutoScan => {
+                core.auto_scan = None;
+            }
+            CoreCommand::SeedAddress(addr, mem_type) => {
+                core.seed_address(*addr, *mem_type)?;
+            }
+            CoreCommand::UndoScan => {
                 if let Some(scanner) = &mut core.scanner {
-                    let result = filter.scan(scanner);
-                    let num_results = scanner.count_results().unwrap_or(0);
-                    core.scan_status = match result {
-                        Ok(_) => {
-                            ScanStatus::Done(num_results as u64)
-                        },
-                        Err(err) => ScanStatus::Failed(err.to_string()),
+                    scanner.undo_scan()?;
+                    core.scan_status = match scanner.count_results() {
+                        Some(num_results) => ScanStatus::Done(num_results as u64),
+                        None => ScanStatus::Ready,
                     };
                 }
             }
@@ -260,3 +799,426 @@ impl CoreCommand {
         Ok(())
     }
 }
+
+/// Reads a `mem_type` value at `addr` from `process`
+///
+/// Returns `None` if the address can't be read, or `mem_type` has no fixed size
+fn read_mem_value(process: &dyn Process, addr: u64, mem_type: MemType) -> Option<MemValue> {
+    if let MemType::String | MemType::Unknown = mem_type {
+        return None;
+    }
+    let bytes = process.read_memory_bytes(addr, mem_type.size()).ok()?;
+    MemValue::from_le_bytes(mem_type, &bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hoodmem::mock::MockProcess;
+    use hoodmem::{GenericMemoryRead, ScannableMemoryRegions};
+
+    /// Base address of the first region of test processes
+    const BASE: u64 = 0x10000;
+
+    /// Attaches `core` to a mock process, as `Core::attach` would
+    fn attach_mock(core: &mut Core, process: MockProcess) -> Arc<MockProcess> {
+        let process = Arc::new(process);
+        core.process = Some(process.clone());
+        core.scanner = Some(hoodmem::scanner::Scanner::new(process.clone()));
+        core.modules = process.get_modules();
+        core.attach_status = AttachStatus::Attached(AttachTarget::Process(1));
+        process
+    }
+
+    fn read_u32(process: &MockProcess, addr: u64) -> u32 {
+        GenericMemoryRead::<u32>::read_memory(process, addr).unwrap()
+    }
+
+    #[test]
+    fn frozen_cheat_rewrites_externally_changed_value() {
+        let mut core = Core::default();
+        let process = attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 16]));
+        core.freeze_interval = Duration::ZERO;
+        let cheat = CheatType::Simple { addr: BASE + 4, mem_type: MemType::U32 };
+        CoreCommand::FreezeCheat(0, cheat, 100u32.to_le_bytes().to_vec()).execute(&mut core).unwrap();
+        assert_eq!(read_u32(&process, BASE + 4), 100);
+
+        for external in [1u32, 2, 3] {
+            process.set_value(BASE + 4, external);
+            core.tick();
+            assert_eq!(read_u32(&process, BASE + 4), 100);
+        }
+
+        CoreCommand::UnfreezeCheat(0).execute(&mut core).unwrap();
+        process.set_value(BASE + 4, 5u32);
+        core.tick();
+        assert_eq!(read_u32(&process, BASE + 4), 5);
+    }
+
+    #[test]
+    fn core_thread_keeps_frozen_cheat_written() {
+        let mut controller = CoreController::default();
+        let process = attach_mock(&mut controller.core.lock().unwrap(), MockProcess::new().with_region(BASE, vec![0; 16]));
+        controller.start().unwrap();
+        controller.send_command(CoreCommand::SetFreezeInterval(Duration::from_millis(1))).unwrap();
+        let cheat = CheatType::Simple { addr: BASE, mem_type: MemType::U32 };
+        controller.send_command(CoreCommand::FreezeCheat(0, cheat, 100u32.to_le_bytes().to_vec())).unwrap();
+
+        // Another thread keeps changing the value, and the core thread keeps writing it back without being asked to
+        for external in [1u32, 2, 3] {
+            process.set_value(BASE, external);
+            let started = Instant::now();
+            while read_u32(&process, BASE) != 100 {
+                assert!(started.elapsed() < Duration::from_secs(5), "frozen value was never written back");
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+        controller.stop().unwrap();
+    }
+
+    #[test]
+    fn module_relative_cheat_resolves_against_loaded_base() {
+        let mut core = Core::default();
+        attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 0x100]).with_module("game", BASE, 0x100));
+        let cheat = CheatType::for_address(BASE + 0x40, MemType::U32, &core.modules);
+        assert_eq!(cheat, CheatType::ModuleRelative { module: "game".into(), offset: 0x40, mem_type: MemType::U32 });
+        assert_eq!(core.resolve_cheat_address(&cheat).unwrap(), BASE + 0x40);
+
+        // After a restart the module is loaded somewhere else
+        attach_mock(&mut core, MockProcess::new().with_region(0x70000, vec![0; 0x100]).with_module("game", 0x70000, 0x100));
+        assert_eq!(core.resolve_cheat_address(&cheat).unwrap(), 0x70040);
+
+        attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 0x100]));
+        assert!(core.resolve_cheat_address(&cheat).is_err());
+    }
+
+    #[test]
+    fn cheat_outside_modules_stays_absolute() {
+        let mut core = Core::default();
+        attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 0x100]).with_module("game", BASE, 0x10));
+        let cheat = CheatType::for_address(BASE + 0x40, MemType::U16, &core.modules);
+        assert_eq!(cheat, CheatType::Simple { addr: BASE + 0x40, mem_type: MemType::U16 });
+        assert_eq!(core.resolve_cheat_address(&cheat).unwrap(), BASE + 0x40);
+    }
+
+    #[test]
+    fn read_value_reads_each_type_from_known_bytes() {
+        let controller = CoreController::default();
+        let bytes = vec![0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x40, 0x40];
+        attach_mock(&mut controller.core.lock().unwrap(), MockProcess::new().with_region(BASE, bytes));
+
+        let read = |mem_type| controller.read_value(BASE, mem_type);
+        assert_eq!(read(MemType::U8), Some(MemValue::U8(0)));
+        assert_eq!(read(MemType::I8), Some(MemValue::I8(0)));
+        assert_eq!(read(MemType::Bool), Some(MemValue::U8(0)));
+        assert_eq!(read(MemType::U16), Some(MemValue::U16(0)));
+        assert_eq!(read(MemType::I16), Some(MemValue::I16(0)));
+        assert_eq!(read(MemType::U32), Some(MemValue::U32(0x3f800000)));
+        assert_eq!(read(MemType::I32), Some(MemValue::I32(0x3f800000)));
+        assert_eq!(read(MemType::F32), Some(MemValue::F32(1.0)));
+        assert_eq!(read(MemType::U64), Some(MemValue::U64(0x40000000_3f800000)));
+        assert_eq!(read(MemType::I64), Some(MemValue::I64(0x40000000_3f800000)));
+        assert_eq!(read(MemType::F64), Some(MemValue::F64(f64::from_bits(0x40000000_3f800000))));
+        assert_eq!(read(MemType::Vec3F32), Some(MemValue::Vec3F32([1.0, 2.0, 3.0])));
+        assert_eq!(controller.read_value(BASE + 3, MemType::U8), Some(MemValue::U8(0x3f)));
+        assert_eq!(controller.read_value(BASE + 2, MemType::I8), Some(MemValue::I8(-128)));
+
+        // Types without a fixed size, and memory which runs out, can't be read
+        assert_eq!(read(MemType::String), None);
+        assert_eq!(controller.read_value(BASE + 8, MemType::U64), None);
+        assert_eq!(controller.read_value(0, MemType::U8), None);
+    }
+
+    #[test]
+    fn read_value_needs_an_attached_process() {
+        assert_eq!(CoreController::default().read_value(BASE, MemType::U32), None);
+    }
+
+    #[test]
+    fn tick_detaches_once_process_exits() {
+        let mut core = Core::default();
+        core.results_refresh_interval = Duration::ZERO;
+        let process = attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 16]));
+        core.tick();
+        assert!(matches!(core.attach_status, AttachStatus::Attached(_)));
+
+        process.set_alive(false);
+        core.tick();
+        assert!(matches!(core.attach_status, AttachStatus::Detached));
+        assert!(core.process.is_none() && core.scanner.is_none());
+        assert_eq!(core.detach_reason.as_deref(), Some("Process exited"));
+    }
+
+    #[test]
+    fn failed_attach_surfaces_error() {
+        let mut controller = CoreController::default();
+        controller.start().unwrap();
+        controller.send_command(CoreCommand::Attach(AttachTarget::Process(u32::MAX))).unwrap();
+        let started = Instant::now();
+        let err = loop {
+            if let Some(err) = controller.take_last_error() {
+                break err;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "attach error was never surfaced");
+            std::thread::sleep(Duration::from_millis(1));
+        };
+        assert!(!err.is_empty());
+        assert!(matches!(controller.get_attach_status(), AttachStatus::Detached));
+        // The error is only taken once
+        assert_eq!(controller.take_last_error(), None);
+        controller.stop().unwrap();
+    }
+
+    #[test]
+    fn detach_resets_attach_and_scan_status() {
+        let core = Mutex::new(Core::default());
+        attach_mock(&mut core.lock().unwrap(), MockProcess::new().with_region(BASE, vec![0; 16]));
+        CoreCommand::Scan(GenericScanFilter::U32(ScanFilter::Exact(0))).execute_shared(&core).unwrap();
+        assert!(matches!(core.lock().unwrap().scan_status, ScanStatus::Done(_)));
+
+        CoreCommand::Detach.execute_shared(&core).unwrap();
+        let core = core.lock().unwrap();
+        assert!(matches!(core.attach_status, AttachStatus::Detached));
+        assert!(matches!(core.scan_status, ScanStatus::Ready));
+        assert!(core.process.is_none() && core.scanner.is_none());
+    }
+
+    #[test]
+    fn detach_cancels_running_scan() {
+        let controller = CoreController::default();
+        // A scan in progress holds the scanner, so detaching has to cancel it before the command is processed
+        controller.send_command(CoreCommand::Detach).unwrap();
+        assert!(controller.cancel_scan.load(Ordering::Relaxed));
+        assert!(controller.core.lock().unwrap().cancel_scan.load(Ordering::Relaxed));
+    }
+
+    /// Runs `f` on another thread, failing if it doesn't return within a few seconds
+    fn within_timeout(f: impl FnOnce() + Send + 'static) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            f();
+            let _ = tx.send(());
+        });
+        rx.recv_timeout(Duration::from_secs(5)).expect("timed out");
+    }
+
+    #[test]
+    fn stop_ends_core_thread() {
+        within_timeout(|| {
+            let mut controller = CoreController::default();
+            controller.start().unwrap();
+            controller.stop().unwrap();
+            assert!(!controller.running);
+            assert!(controller.core_thread.is_none());
+        });
+    }
+
+    #[test]
+    fn dropping_running_controller_stops_it() {
+        within_timeout(|| {
+            let mut controller = CoreController::default();
+            controller.start().unwrap();
+            drop(controller);
+        });
+    }
+
+    #[test]
+    fn parsed_value_is_written_to_result() {
+        let mut core = Core::default();
+        let process = attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 8]));
+        core.scanner.as_mut().unwrap().set_alignment(4);
+        core.scanner.as_mut().unwrap().scan(ScanFilter::Exact(0u32)).unwrap();
+
+        let value = MemType::U32.parse_value("1234").unwrap();
+        CoreCommand::WriteResult(BASE + 4, value).execute(&mut core).unwrap();
+        assert_eq!(read_u32(&process, BASE + 4), 1234);
+        // The results show the new value without waiting for a refresh
+        assert_eq!(core.results_page_values(MemType::U32, 0, 10), vec![(BASE, MemValue::U32(0)), (BASE + 4, MemValue::U32(1234))]);
+
+        // Values which don't parse are never written, and strings can't be written to a result
+        assert!(MemType::U32.parse_value("abc").is_err());
+        assert!(CoreCommand::WriteResult(BASE, MemValue::String("abc".into())).execute(&mut core).is_err());
+        assert_eq!(read_u32(&process, BASE), 0);
+    }
+
+    #[test]
+    fn conditional_cheat_writes_only_when_triggered() {
+        let mut core = Core::default();
+        let process = attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 16]));
+        core.freeze_interval = Duration::ZERO;
+        process.set_value(BASE, 50u32);
+        let cheat = CheatType::Conditional { watch_addr: BASE, compare: Comparison::Lt, threshold: MemValue::U32(20), write_addr: BASE + 4, write_value: MemValue::U32(100) };
+        CoreCommand::FreezeCheat(0, cheat, vec![]).execute(&mut core).unwrap();
+        core.tick();
+        assert_eq!(read_u32(&process, BASE + 4), 0);
+
+        // Health drops below the threshold
+        process.set_value(BASE, 10u32);
+        core.tick();
+        assert_eq!(read_u32(&process, BASE + 4), 100);
+    }
+
+    #[test]
+    fn results_page_values_are_typed_by_mem_type() {
+        let cases = [
+            (MemType::U8, "200"),
+            (MemType::Bool, "true"),
+            (MemType::U16, "60000"),
+            (MemType::U32, "4000000000"),
+            (MemType::U64, "18000000000000000000"),
+            (MemType::I8, "-5"),
+            (MemType::I16, "-300"),
+            (MemType::I32, "-70000"),
+            (MemType::I64, "-5000000000"),
+            (MemType::F32, "1.5"),
+            (MemType::F64, "-2.25"),
+            (MemType::Vec3F32, "1, 2.5, -3"),
+        ];
+        for (mem_type, text) in cases {
+            let core = Mutex::new(Core::default());
+            let process = attach_mock(&mut core.lock().unwrap(), MockProcess::new().with_region(BASE, vec![0; 32]));
+            let value = mem_type.parse_value(text).unwrap();
+            process.set_bytes(BASE + 8, &value.to_le_bytes().unwrap());
+            let filter = GenericScanFilter::new(ScanType::Exact, mem_type, Some(value.clone()), None).unwrap();
+            CoreCommand::Scan(filter).execute_shared(&core).unwrap();
+            assert_eq!(core.lock().unwrap().results_page_values(mem_type, 0, 10), vec![(BASE + 8, value)], "{:?}", mem_type);
+        }
+    }
+
+    #[test]
+    fn execute_rejects_scans() {
+        let mut core = Core::default();
+        attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 16]));
+        assert!(CoreCommand::Scan(GenericScanFilter::U32(ScanFilter::Exact(0))).execute(&mut core).is_err());
+        assert!(core.scanner.as_ref().unwrap().count_results().is_none());
+    }
+
+    #[test]
+    fn freeze_at_current_keeps_value_read_when_frozen() {
+        let mut core = Core::default();
+        let process = attach_mock(&mut core, MockProcess::new().with_region(BASE, vec![0; 16]));
+        core.freeze_interval = Duration::ZERO;
+        process.set_value(BASE + 4, 1234u32);
+        let cheat = CheatType::Simple { addr: BASE + 4, mem_type: MemType::U32 };
+
+        assert_eq!(core.freeze_at_current(0, &cheat).unwrap(), MemValue::U32(1234));
+        assert_eq!(core.frozen_cheats[&0], (cheat, 1234u32.to_le_bytes().to_vec()));
+        process.set_value(BASE + 4, 99u32);
+        core.tick();
+        assert_eq!(read_u32(&process, BASE + 4), 1234);
+
+        let conditional = CheatType::Conditional { watch_addr: BASE, compare: Comparison::Lt, threshold: MemValue::U32(20), write_addr: BASE + 8, write_value: MemValue::U32(100) };
+        assert!(core.freeze_at_current(1, &conditional).is_err());
+        let unreadable = CheatType::Simple { addr: BASE + 0x1000, mem_type: MemType::U32 };
+        assert!(core.freeze_at_current(2, &unreadable).is_err());
+        assert_eq!(core.frozen_cheats.len(), 1);
+    }
+
+    #[test]
+    fn snapshot_results_match_scan_status() {
+        let controller = CoreController::default();
+        assert!(matches!(controller.snapshot(Some((MemType::U8, 0, 100))).attach_status, AttachStatus::Detached));
+        attach_mock(&mut controller.core.lock().unwrap(), MockProcess::new().with_region(BASE, vec![7, 0, 7, 7, 0, 7]));
+        let snapshot = controller.snapshot(Some((MemType::U8, 0, 100)));
+        assert!(matches!(snapshot.scan_status, ScanStatus::Ready));
+        assert_eq!(snapshot.results_page, Some(vec![]));
+
+        CoreCommand::Scan(GenericScanFilter::U8(ScanFilter::Exact(7))).execute_shared(&controller.core).unwrap();
+        let snapshot = controller.snapshot(Some((MemType::U8, 0, 100)));
+        let ScanStatus::Done(count) = snapshot.scan_status else {
+            panic!("scan should be done, but is {:?}", snapshot.scan_status);
+        };
+        assert_eq!(count, 4);
+        assert_eq!(snapshot.results_page.unwrap().len() as u64, count);
+        assert!(snapshot.last_scan_stats.is_some());
+        // No page is read unless asked for
+        assert!(controller.snapshot(None).results_page.is_none());
+    }
+
+    #[test]
+    fn auto_scan_narrows_results_each_interval() {
+        let core = Mutex::new(Core::default());
+        let process = attach_mock(&mut core.lock().unwrap(), MockProcess::new().with_region(BASE, vec![0; 16]));
+        let count = || core.lock().unwrap().scanner.as_ref().unwrap().count_results();
+        CoreCommand::SetAlignment(4).execute_shared(&core).unwrap();
+        CoreCommand::Scan(GenericScanFilter::U32(ScanFilter::Changed)).execute_shared(&core).unwrap();
+        assert_eq!(count(), Some(4));
+
+        CoreCommand::AutoScan(GenericScanFilter::U32(ScanFilter::Unchanged), Duration::ZERO).execute_shared(&core).unwrap();
+        process.set_value(BASE, 1u32);
+        Core::auto_scan(&core).unwrap();
+        assert_eq!(count(), Some(3));
+        process.set_value(BASE + 8, 1u32);
+        Core::auto_scan(&core).unwrap();
+        assert_eq!(count(), Some(2));
+
+        // Nothing is scanned until the interval has passed
+        CoreCommand::AutoScan(GenericScanFilter::U32(ScanFilter::Unchanged), Duration::from_secs(3600)).execute_shared(&core).unwrap();
+        process.set_value(BASE + 4, 1u32);
+        Core::auto_scan(&core).unwrap();
+        assert_eq!(count(), Some(2));
+
+        CoreCommand::AutoScan(GenericScanFilter::U32(ScanFilter::Unchanged), Duration::ZERO).execute_shared(&core).unwrap();
+        CoreCommand::StopAutoScan.execute_shared(&core).unwrap();
+        Core::auto_scan(&core).unwrap();
+        assert_eq!(count(), Some(2));
+        assert!(core.lock().unwrap().auto_scan.is_none());
+    }
+
+    #[test]
+    fn seeded_address_narrows_with_changed_scan() {
+        let core = Mutex::new(Core::default());
+        let process = attach_mock(&mut core.lock().unwrap(), MockProcess::new().with_region(BASE, vec![0; 16]));
+        process.set_value(BASE + 6, 100u32);
+        CoreCommand::SeedAddress(BASE + 6, MemType::U32).execute_shared(&core).unwrap();
+        assert!(matches!(core.lock().unwrap().scan_status, ScanStatus::Done(1)));
+
+        // The GUI sets the alignment before every scan, which mustn't drop the unaligned seeded address
+        process.set_value(BASE + 6, 90u32);
+        CoreCommand::SetAlignment(4).execute_shared(&core).unwrap();
+        CoreCommand::Scan(GenericScanFilter::U32(ScanFilter::Changed)).execute_shared(&core).unwrap();
+        assert!(matches!(core.lock().unwrap().scan_status, ScanStatus::Done(1)));
+        assert_eq!(core.lock().unwrap().results_page_values(MemType::U32, 0, 10), vec![(BASE + 6, MemValue::U32(90))]);
+
+        CoreCommand::Scan(GenericScanFilter::U32(ScanFilter::Changed)).execute_shared(&core).unwrap();
+        assert!(matches!(core.lock().unwrap().scan_status, ScanStatus::Done(0)));
+        assert!(CoreCommand::SeedAddress(BASE + 0x1000, MemType::U32).execute_shared(&core).is_err());
+    }
+
+    #[test]
+    fn scan_progress_increases_to_one() {
+        let process = (0..64).fold(MockProcess::new(), |process, i| process.with_region(BASE + i * 0x1000, vec![0; 16]));
+        let core = Mutex::new(Core::default());
+        attach_mock(&mut core.lock().unwrap(), process);
+        let filter = GenericScanFilter::U32(ScanFilter::Exact(0));
+
+        // Every progress update, in the order the scan reports them
+        let fractions = Mutex::new(vec![]);
+        let mut scanner = core.lock().unwrap().scanner.take().unwrap();
+        filter.scan_cancellable(&mut scanner, &AtomicBool::new(false), |progress| fractions.lock().unwrap().push(progress.fraction())).unwrap();
+        core.lock().unwrap().scanner = Some(scanner);
+        let fractions = fractions.into_inner().unwrap();
+        assert!(fractions.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", fractions);
+        assert_eq!(fractions.last(), Some(&1.0));
+
+        // The status another thread sees while the core scans never goes backwards either
+        let done = AtomicBool::new(false);
+        let seen = std::thread::scope(|scope| {
+            let watcher = scope.spawn(|| {
+                let mut seen = vec![];
+                while !done.load(Ordering::Relaxed) {
+                    if let ScanStatus::Scanning(fraction) = core.lock().unwrap().scan_status {
+                        seen.push(fraction);
+                    }
+                }
+                seen
+            });
+            CoreCommand::Scan(filter.clone()).execute_shared(&core).unwrap();
+            done.store(true, Ordering::Relaxed);
+            watcher.join().unwrap()
+        });
+        assert!(seen.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", seen);
+        assert!(seen.iter().all(|fraction| (0.0..=1.0).contains(fraction)));
+        assert!(matches!(core.lock().unwrap().scan_status, ScanStatus::Done(_)));
+    }
+}
@@ -1,8 +1,16 @@
+use super::CoreCommand;
+use hoodmem::{find_module, Module};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use strum::EnumIter;
+
 #[derive(Debug, Clone)]
 pub enum AttachTarget {
     Process(u32),
     Window(String),
-    Other(String),
+    /// The process running the executable at a path
+    ExecutablePath(String),
 }
 
 #[derive(Debug, Clone)]
@@ -24,12 +32,14 @@ pub enum ScanStatus {
     /// Ready to scan
     #[default]
     Ready,
-    /// A scan is currently in progress
-    Scanning,
+    /// A scan is currently in progress, and this far along from 0.0 to 1.0
+    Scanning(f32),
     /// Done scanning.
     Done(u64),
     /// Scan failed for some reason
     Failed(String),
+    /// Scan was cancelled before it finished
+    Cancelled,
     /// Unknown status
     Unknown
 }
@@ -39,9 +49,10 @@ impl std::fmt::Display for ScanStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ScanStatus::Ready => write!(f, "Ready to scan"),
-            ScanStatus::Scanning => write!(f, "Scanning..."),
+            ScanStatus::Scanning(_) => write!(f, "Scanning..."),
             ScanStatus::Done(num_results) => write!(f, "Scan complete ({} Results)", num_results),
             ScanStatus::Failed(reason) => write!(f, "Scan Failed ({})", reason),
+            ScanStatus::Cancelled => write!(f, "Scan Cancelled"),
             ScanStatus::Unknown => write!(f, ""),
         }
     }
@@ -53,15 +64,54 @@ pub enum AttachType {
     #[default]
     ByPID,
     ByWindowName,
+    ByExecutablePath,
 }
 
-#[derive(Default, PartialEq, Debug, Clone, Copy)]
+#[derive(Default, PartialEq, Debug, Clone, Copy, EnumIter, Serialize, Deserialize)]
 pub enum ScanType {
     #[default]
     Exact,
+    /// Equal to any of several comma separated values
+    ExactAny,
+    /// Within a threshold of a value. The threshold is entered as the secondary value
+    Approximate,
     Unknown,
     Increased,
     Decreased,
+    Changed,
+    Unchanged,
+    Between,
+    NotEqual,
+    /// Floats equal to the value when rounded to a number of decimal places
+    Rounded,
+    /// Values at about a percentage of a known maximum, such as health at 75%
+    Percent,
+    /// Integers which are a multiple of the value, such as timers counting in steps
+    DivisibleBy,
+    /// A single result at a known address, which later scans narrow down. The address is entered as the value
+    ExactAddress,
+}
+
+impl ScanType {
+    /// Whether this scan type needs a value, rather than comparing with the previous scan
+    pub fn needs_value(&self) -> bool {
+        !matches!(
+            self,
+            ScanType::Unknown
+                | ScanType::Increased
+                | ScanType::Decreased
+                | ScanType::Changed
+                | ScanType::Unchanged
+        )
+    }
+
+    /// Whether this scan type needs a second value, entered separately from the main scan value
+    pub fn needs_secondary_value(&self) -> bool {
+        matches!(
+            self,
+            ScanType::Approximate | ScanType::Between | ScanType::Rounded | ScanType::Percent
+        )
+    }
 }
 
 impl std::fmt::Display for ScanType {
@@ -72,13 +122,37 @@ impl std::fmt::Display for ScanType {
             "{}",
             match self {
                 ScanType::Exact => "Exact",
+                ScanType::ExactAny => "Exact (Any Of)",
                 ScanType::Unknown => "Unknown",
+                ScanType::NotEqual => "Not Equal",
+                ScanType::Rounded => "Float Rounded",
+                ScanType::Percent => "Percent of Max",
+                ScanType::DivisibleBy => "Divisible By",
+                ScanType::ExactAddress => "Exact Address",
                 _ => &fallback,
             }
         )
     }
 }
 
+/// How values are shown in the results and cheats tables
+#[derive(Default, PartialEq, Debug, Clone, Copy, EnumIter)]
+pub enum DisplayFormat {
+    #[default]
+    Decimal,
+    /// Integers in hex, with signed integers as two's complement. Floats show their bits
+    Hex,
+    /// Decimal followed by hex
+    Both,
+}
+
+impl std::fmt::Display for DisplayFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MemValue {
     U8(u8),
     U16(u16),
@@ -90,9 +164,81 @@ pub enum MemValue {
     I64(i64),
     F32(f32),
     F64(f64),
+    /// Three contiguous floats, such as an x, y, z position
+    Vec3F32([f32; 3]),
+    String(String),
     Null,
 }
 
+impl MemValue {
+    /// Gets the little endian bytes this value is stored as in memory. Strings are their UTF-8 bytes
+    pub fn to_le_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            MemValue::U8(x) => x.to_le_bytes().to_vec(),
+            MemValue::U16(x) => x.to_le_bytes().to_vec(),
+            MemValue::U32(x) => x.to_le_bytes().to_vec(),
+            MemValue::U64(x) => x.to_le_bytes().to_vec(),
+            MemValue::I8(x) => x.to_le_bytes().to_vec(),
+            MemValue::I16(x) => x.to_le_bytes().to_vec(),
+            MemValue::I32(x) => x.to_le_bytes().to_vec(),
+            MemValue::I64(x) => x.to_le_bytes().to_vec(),
+            MemValue::F32(x) => x.to_le_bytes().to_vec(),
+            MemValue::F64(x) => x.to_le_bytes().to_vec(),
+            MemValue::Vec3F32(x) => x.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            MemValue::String(x) => x.as_bytes().to_vec(),
+            MemValue::Null => anyhow::bail!("null has no bytes"),
+        })
+    }
+
+    /// Reads a `mem_type` value from its little endian bytes in memory
+    ///
+    /// Bools are read as `MemValue::U8`. Strings take all of `bytes`, replacing invalid UTF-8.
+    /// Other types must be given exactly `mem_type.size()` bytes
+    pub fn from_le_bytes(mem_type: MemType, bytes: &[u8]) -> anyhow::Result<MemValue> {
+        if !matches!(mem_type, MemType::String | MemType::Unknown) && bytes.len() != mem_type.size() {
+            anyhow::bail!("Expected {} bytes for {} but got {}", mem_type.size(), mem_type, bytes.len());
+        }
+        Ok(match mem_type {
+            MemType::U8 | MemType::Bool => MemValue::U8(bytes[0]),
+            MemType::U16 => MemValue::U16(u16::from_le_bytes(bytes.try_into()?)),
+            MemType::U32 => MemValue::U32(u32::from_le_bytes(bytes.try_into()?)),
+            MemType::U64 => MemValue::U64(u64::from_le_bytes(bytes.try_into()?)),
+            MemType::I8 => MemValue::I8(bytes[0] as i8),
+            MemType::I16 => MemValue::I16(i16::from_le_bytes(bytes.try_into()?)),
+            MemType::I32 => MemValue::I32(i32::from_le_bytes(bytes.try_into()?)),
+            MemType::I64 => MemValue::I64(i64::from_le_bytes(bytes.try_into()?)),
+            MemType::F32 => MemValue::F32(f32::from_le_bytes(bytes.try_into()?)),
+            MemType::F64 => MemValue::F64(f64::from_le_bytes(bytes.try_into()?)),
+            MemType::Vec3F32 => MemValue::Vec3F32([
+                f32::from_le_bytes(bytes[0..4].try_into()?),
+                f32::from_le_bytes(bytes[4..8].try_into()?),
+                f32::from_le_bytes(bytes[8..12].try_into()?),
+            ]),
+            MemType::String => MemValue::String(String::from_utf8_lossy(bytes).into_owned()),
+            MemType::Unknown => anyhow::bail!("Cannot read the unknown type"),
+        })
+    }
+}
+
+impl MemValue {
+    /// Gets this value as a float, for plotting. Strings and null have no numeric value
+    pub fn to_f64(&self) -> Option<f64> {
+        Some(match self {
+            MemValue::U8(x) => *x as f64,
+            MemValue::U16(x) => *x as f64,
+            MemValue::U32(x) => *x as f64,
+            MemValue::U64(x) => *x as f64,
+            MemValue::I8(x) => *x as f64,
+            MemValue::I16(x) => *x as f64,
+            MemValue::I32(x) => *x as f64,
+            MemValue::I64(x) => *x as f64,
+            MemValue::F32(x) => *x as f64,
+            MemValue::F64(x) => *x,
+            MemValue::Vec3F32(_) | MemValue::String(_) | MemValue::Null => return None,
+        })
+    }
+}
+
 impl std::fmt::Display for MemValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -106,12 +252,14 @@ impl std::fmt::Display for MemValue {
             MemValue::I64(x) => write!(f, "{}", x),
             MemValue::F32(x) => write!(f, "{}", x),
             MemValue::F64(x) => write!(f, "{}", x),
+            MemValue::Vec3F32([x, y, z]) => write!(f, "({}, {}, {})", x, y, z),
+            MemValue::String(x) => write!(f, "{}", x),
             MemValue::Null => write!(f, "null"),
         }
     }
 }
 
-#[derive(Default, PartialEq, Clone, Copy)]
+#[derive(Default, PartialEq, Debug, Clone, Copy, EnumIter, Serialize, Deserialize)]
 pub enum MemType {
     #[default]
     U8,
@@ -124,27 +272,109 @@ pub enum MemType {
     I64,
     F32,
     F64,
+    String,
+    /// A flag stored as a byte, which is either 0 or 1. Scanned as a `u8`
+    Bool,
+    /// Three contiguous `f32`s, such as an x, y, z position
+    Vec3F32,
     Unknown,
 }
 
 impl MemType {
+    /// Size of a value of this type in bytes. Strings and unknown values are treated as single bytes
+    pub fn size(&self) -> usize {
+        match self {
+            MemType::U8 | MemType::I8 | MemType::Bool => 1,
+            MemType::U16 | MemType::I16 => 2,
+            MemType::U32 | MemType::I32 | MemType::F32 => 4,
+            MemType::U64 | MemType::I64 | MemType::F64 => 8,
+            MemType::Vec3F32 => 12,
+            MemType::String | MemType::Unknown => 1,
+        }
+    }
+
+    /// Alignment of a value of this type in bytes, which is its size except for vectors, which are aligned like their components
+    pub fn alignment(&self) -> usize {
+        match self {
+            MemType::Vec3F32 => MemType::F32.size(),
+            _ => self.size(),
+        }
+    }
+
     pub fn parse_value(&self, value: &str) -> anyhow::Result<MemValue> {
         Ok(match self {
             MemType::U8 => MemValue::U8(value.parse()?),
-            MemType::U16 => MemValue::U8(value.parse()?),
-            MemType::U32 => MemValue::U8(value.parse()?),
-            MemType::U64 => MemValue::U8(value.parse()?),
-            MemType::I8 => MemValue::U8(value.parse()?),
-            MemType::I16 => MemValue::U8(value.parse()?),
-            MemType::I32 => MemValue::U8(value.parse()?),
-            MemType::I64 => MemValue::U8(value.parse()?),
-            MemType::F32 => MemValue::U8(value.parse()?),
-            MemType::F64 => MemValue::U8(value.parse()?),
+            MemType::U16 => MemValue::U16(value.parse()?),
+            MemType::U32 => MemValue::U32(value.parse()?),
+            MemType::U64 => MemValue::U64(value.parse()?),
+            MemType::I8 => MemValue::I8(value.parse()?),
+            MemType::I16 => MemValue::I16(value.parse()?),
+            MemType::I32 => MemValue::I32(value.parse()?),
+            MemType::I64 => MemValue::I64(value.parse()?),
+            MemType::F32 => MemValue::F32(value.parse()?),
+            MemType::F64 => MemValue::F64(value.parse()?),
+            MemType::Vec3F32 => {
+                let components = parse_vec3(value)?;
+                match components {
+                    [Some(x), Some(y), Some(z)] => MemValue::Vec3F32([x, y, z]),
+                    _ => anyhow::bail!("Expected all 3 components of x, y, z"),
+                }
+            }
+            MemType::String => MemValue::String(value.to_string()),
+            MemType::Bool => match value.trim().to_lowercase().as_str() {
+                "true" | "1" => MemValue::U8(1),
+                "false" | "0" => MemValue::U8(0),
+                _ => anyhow::bail!("Expected true, false, 1 or 0 but got '{}'", value.trim()),
+            },
+            MemType::Unknown => anyhow::bail!("Cannot parse the unknown type"),
+        })
+    }
+
+    /// Parses a value, treating integers as hex (with an optional `0x` prefix) if `hex` is set
+    pub fn parse_value_radix(&self, value: &str, hex: bool) -> anyhow::Result<MemValue> {
+        if !hex {
+            return self.parse_value(value);
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+            .unwrap_or(value);
+        Ok(match self {
+            MemType::U8 => MemValue::U8(u8::from_str_radix(value, 16)?),
+            MemType::U16 => MemValue::U16(u16::from_str_radix(value, 16)?),
+            MemType::U32 => MemValue::U32(u32::from_str_radix(value, 16)?),
+            MemType::U64 => MemValue::U64(u64::from_str_radix(value, 16)?),
+            MemType::I8 => MemValue::I8(i8::from_str_radix(value, 16)?),
+            MemType::I16 => MemValue::I16(i16::from_str_radix(value, 16)?),
+            MemType::I32 => MemValue::I32(i32::from_str_radix(value, 16)?),
+            MemType::I64 => MemValue::I64(i64::from_str_radix(value, 16)?),
+            MemType::F32 | MemType::F64 | MemType::Vec3F32 => {
+                anyhow::bail!("Hex values are not supported for {}", self)
+            }
+            MemType::String => anyhow::bail!("Hex values are not supported for strings"),
+            // Hex makes no difference to true/false/1/0
+            MemType::Bool => return self.parse_value(value),
             MemType::Unknown => anyhow::bail!("Cannot parse the unknown type"),
         })
     }
 }
 
+/// Parses the components of a vector written as `x, y, z`. Components written as `?` are unknown
+pub fn parse_vec3(value: &str) -> anyhow::Result<[Option<f32>; 3]> {
+    let components = value
+        .split(',')
+        .map(|component| match component.trim() {
+            "?" => Ok(None),
+            component => Ok(Some(component.parse::<f32>()?)),
+        })
+        .collect::<anyhow::Result<Vec<Option<f32>>>>()?;
+    match components[..] {
+        [x, y, z] => Ok([x, y, z]),
+        _ => anyhow::bail!("Expected 3 components written as x, y, z, but got {}", components.len()),
+    }
+}
+
 impl From<MemValue> for MemType {
     fn from(value: MemValue) -> Self {
         match value {
@@ -158,6 +388,8 @@ impl From<MemValue> for MemType {
             MemValue::I64(_) => Self::I64,
             MemValue::F32(_) => Self::F32,
             MemValue::F64(_) => Self::F64,
+            MemValue::Vec3F32(_) => Self::Vec3F32,
+            MemValue::String(_) => Self::String,
             MemValue::Null => Self::Unknown,
         }
     }
@@ -179,14 +411,117 @@ impl std::fmt::Display for MemType {
                 MemType::I64 => "64-bit Integer (signed)",
                 MemType::F32 => "Float (32-bit)",
                 MemType::F64 => "Float (64-bit)",
+                MemType::Vec3F32 => "Vector (3 x 32-bit Float)",
+                MemType::String => "String (UTF-8)",
+                MemType::Bool => "Boolean",
                 MemType::Unknown => "Unknown",
             }
         )
     }
 }
 
+/// How a conditional cheat compares the watched value with its threshold
+#[derive(Default, PartialEq, Debug, Clone, Copy, EnumIter, Serialize, Deserialize)]
+pub enum Comparison {
+    #[default]
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+    Ne,
+}
+
+impl Comparison {
+    /// Checks whether `value` compares to `threshold` this way. Values of different types never match
+    pub fn evaluate(&self, value: &MemValue, threshold: &MemValue) -> bool {
+        let ordering = match (value, threshold) {
+            (MemValue::U8(a), MemValue::U8(b)) => a.partial_cmp(b),
+            (MemValue::U16(a), MemValue::U16(b)) => a.partial_cmp(b),
+            (MemValue::U32(a), MemValue::U32(b)) => a.partial_cmp(b),
+            (MemValue::U64(a), MemValue::U64(b)) => a.partial_cmp(b),
+            (MemValue::I8(a), MemValue::I8(b)) => a.partial_cmp(b),
+            (MemValue::I16(a), MemValue::I16(b)) => a.partial_cmp(b),
+            (MemValue::I32(a), MemValue::I32(b)) => a.partial_cmp(b),
+            (MemValue::I64(a), MemValue::I64(b)) => a.partial_cmp(b),
+            (MemValue::F32(a), MemValue::F32(b)) => a.partial_cmp(b),
+            (MemValue::F64(a), MemValue::F64(b)) => a.partial_cmp(b),
+            (MemValue::String(a), MemValue::String(b)) => a.partial_cmp(b),
+            // Vectors only have equality
+            (MemValue::Vec3F32(a), MemValue::Vec3F32(b)) if a == b => Some(std::cmp::Ordering::Equal),
+            (MemValue::Vec3F32(_), MemValue::Vec3F32(_)) => {
+                return *self == Comparison::Ne;
+            }
+            _ => None,
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self {
+            Comparison::Lt => ordering.is_lt(),
+            Comparison::Le => ordering.is_le(),
+            Comparison::Eq => ordering.is_eq(),
+            Comparison::Ge => ordering.is_ge(),
+            Comparison::Gt => ordering.is_gt(),
+            Comparison::Ne => ordering.is_ne(),
+        }
+    }
+}
+
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Comparison::Lt => "<",
+                Comparison::Le => "<=",
+                Comparison::Eq => "==",
+                Comparison::Ge => ">=",
+                Comparison::Gt => ">",
+                Comparison::Ne => "!=",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CheatType {
     Simple { addr: u64, mem_type: MemType },
+    /// An offset from the base of a module, which stays valid across restarts even when the module is loaded somewhere else
+    ModuleRelative { module: String, offset: u64, mem_type: MemType },
+    /// Writes `write_value` to `write_addr` whenever the value at `watch_addr` compares to `threshold`, such as to heal when health is low
+    ///
+    /// The watched value is read as the type of `threshold`
+    Conditional {
+        watch_addr: u64,
+        compare: Comparison,
+        threshold: MemValue,
+        write_addr: u64,
+        write_value: MemValue,
+    },
+}
+
+impl CheatType {
+    /// Creates a cheat type for `addr`, relative to the module it falls inside if there is one
+    pub fn for_address(addr: u64, mem_type: MemType, modules: &[Module]) -> Self {
+        match find_module(modules, addr) {
+            Some(module) => CheatType::ModuleRelative {
+                module: module.name.clone(),
+                offset: addr - module.base_address,
+                mem_type,
+            },
+            None => CheatType::Simple { addr, mem_type },
+        }
+    }
+
+    pub fn mem_type(&self) -> MemType {
+        match self {
+            CheatType::Simple { mem_type, .. } => *mem_type,
+            CheatType::ModuleRelative { mem_type, .. } => *mem_type,
+            CheatType::Conditional { threshold, .. } => MemType::from(threshold.clone()),
+        }
+    }
 }
 
 pub trait CheatSummary {
@@ -197,6 +532,21 @@ impl CheatSummary for CheatType {
     fn get_summary(&self) -> String {
         match self {
             CheatType::Simple { addr, mem_type } => format!("[{}] 0x{:016x}", mem_type, addr),
+            CheatType::ModuleRelative {
+                module,
+                offset,
+                mem_type,
+            } => format!("[{}] {}+0x{:x}", mem_type, module, offset),
+            CheatType::Conditional {
+                watch_addr,
+                compare,
+                threshold,
+                write_addr,
+                write_value,
+            } => format!(
+                "If 0x{:016x} {} {}, write {} to 0x{:016x}",
+                watch_addr, compare, threshold, write_value, write_addr
+            ),
         }
     }
 }
@@ -205,14 +555,86 @@ impl std::fmt::Display for CheatType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             CheatType::Simple { addr, mem_type } => write!(f, "Simple ({})", mem_type),
+            CheatType::ModuleRelative { mem_type, .. } => write!(f, "Module Relative ({})", mem_type),
+            CheatType::Conditional { write_value, .. } => {
+                write!(f, "Conditional ({})", MemType::from(write_value.clone()))
+            }
         }
     }
 }
 
+/// A cheat in the cheats list. Cheats lists are saved as JSON cheat tables
+#[derive(Serialize, Deserialize)]
 pub struct Cheat {
+    /// Cheats are always loaded disabled, so loading a table doesn't write to the process straight away
+    #[serde(skip)]
     pub enabled: bool,
     pub name: String,
     pub cheat_type: CheatType,
+    /// The value to keep writing to the cheat's address while enabled
+    pub frozen_value: Option<MemValue>,
+    /// The frozen value as entered by the user
+    pub frozen_input: String,
+    /// Recent readings of the cheat's value, oldest first
+    #[serde(skip)]
+    pub history: VecDeque<f64>,
+    /// Shortcut which toggles whether the cheat is enabled
+    pub hotkey: Option<egui::KeyboardShortcut>,
+}
+
+impl Cheat {
+    /// Writes `cheats` to a cheat table file at `path`
+    pub fn save_table(cheats: &[Cheat], path: &Path) -> anyhow::Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), cheats)?;
+        Ok(())
+    }
+
+    /// Reads the cheats from a cheat table file at `path`
+    ///
+    /// Module relative cheats are stored as module offsets, so they resolve against whichever process is attached
+    pub fn load_table(path: &Path) -> anyhow::Result<Vec<Cheat>> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    /// Gets the indices of the cheats bound to `shortcut`
+    pub fn bound_to(cheats: &[Cheat], shortcut: &egui::KeyboardShortcut) -> Vec<usize> {
+        cheats
+            .iter()
+            .enumerate()
+            .filter(|(_, cheat)| cheat.hotkey.as_ref() == Some(shortcut))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// How many readings are kept in a cheat's history
+    pub const HISTORY_LEN: usize = 60;
+
+    /// Adds a reading to the cheat's history, dropping the oldest once it is full
+    pub fn record_value(&mut self, value: f64) {
+        if self.history.len() >= Self::HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(value);
+    }
+
+    /// Gets the command which brings MemNinja Core in sync with this cheat's freeze state
+    ///
+    /// `id` identifies the cheat to the core, and should be stable for as long as the cheat exists
+    pub fn freeze_command(&self, id: usize) -> CoreCommand {
+        if let CheatType::Conditional { .. } = self.cheat_type {
+            // Conditional cheats write their own value, so only need enabling
+            return match self.enabled {
+                true => CoreCommand::FreezeCheat(id, self.cheat_type.clone(), vec![]),
+                false => CoreCommand::UnfreezeCheat(id),
+            };
+        }
+        match self.frozen_value.as_ref().and_then(|value| value.to_le_bytes().ok()) {
+            Some(bytes) if self.enabled => CoreCommand::FreezeCheat(id, self.cheat_type.clone(), bytes),
+            _ => CoreCommand::UnfreezeCheat(id),
+        }
+    }
 }
 
 impl CheatSummary for Cheat {
@@ -220,3 +642,167 @@ impl CheatSummary for Cheat {
         self.cheat_type.get_summary()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_values_parse_with_or_without_prefix() {
+        assert_eq!(MemType::U8.parse_value_radix("0xFF", true).unwrap(), MemValue::U8(255));
+        assert_eq!(MemType::U32.parse_value_radix("ff", true).unwrap(), MemValue::U32(255));
+        // Without hex mode, `ff` isn't a number
+        assert!(MemType::U32.parse_value_radix("ff", false).is_err());
+        assert!(MemType::U8.parse_value_radix("0x100", true).is_err());
+    }
+
+    #[test]
+    fn hex_values_are_rejected_for_floats() {
+        let err = MemType::F32.parse_value_radix("0x3f800000", true).unwrap_err();
+        assert!(err.to_string().contains("Hex values are not supported"));
+        assert_eq!(MemType::F32.parse_value_radix("1.5", false).unwrap(), MemValue::F32(1.5));
+    }
+
+    #[test]
+    fn bools_parse_from_words_and_digits() {
+        assert_eq!(MemType::Bool.parse_value("true").unwrap(), MemValue::U8(1));
+        assert_eq!(MemType::Bool.parse_value(" FALSE ").unwrap(), MemValue::U8(0));
+        assert_eq!(MemType::Bool.parse_value("1").unwrap(), MemValue::U8(1));
+        assert_eq!(MemType::Bool.parse_value("0").unwrap(), MemValue::U8(0));
+        assert!(MemType::Bool.parse_value("2").is_err());
+        assert!(MemType::Bool.parse_value("yes").is_err());
+        assert_eq!(MemType::Bool.size(), 1);
+    }
+
+    #[test]
+    fn iterators_yield_every_variant() {
+        use strum::IntoEnumIterator;
+        use ScanType::*;
+        let scan_types: Vec<ScanType> = ScanType::iter().collect();
+        assert_eq!(scan_types, vec![Exact, ExactAny, Approximate, Unknown, Increased, Decreased, Changed, Unchanged, Between, NotEqual, Rounded, Percent, DivisibleBy, ExactAddress]);
+        let mem_types: Vec<MemType> = MemType::iter().collect();
+        assert_eq!(mem_types, vec![MemType::U8, MemType::U16, MemType::U32, MemType::U64, MemType::I8, MemType::I16, MemType::I32, MemType::I64, MemType::F32, MemType::F64, MemType::String, MemType::Bool, MemType::Vec3F32, MemType::Unknown]);
+    }
+
+    #[test]
+    fn vec3_parses_with_unknown_components() {
+        assert_eq!(parse_vec3("1, 2.5, -3").unwrap(), [Some(1.0), Some(2.5), Some(-3.0)]);
+        assert_eq!(parse_vec3(" ? ,2, ?").unwrap(), [None, Some(2.0), None]);
+        assert!(parse_vec3("1, 2").is_err());
+        assert!(parse_vec3("1, x, 3").is_err());
+        assert_eq!(MemValue::Vec3F32([1.0, 2.5, -3.0]).to_string(), "(1, 2.5, -3)");
+    }
+
+    #[test]
+    fn comparisons_evaluate_for_each_operator() {
+        use strum::IntoEnumIterator;
+        use Comparison::*;
+        // (value, threshold, operators which hold)
+        let cases = [
+            (MemValue::U8(1), MemValue::U8(2), vec![Lt, Le, Ne]),
+            (MemValue::I32(-5), MemValue::I32(-5), vec![Le, Eq, Ge]),
+            (MemValue::U64(9), MemValue::U64(3), vec![Ge, Gt, Ne]),
+            (MemValue::F32(19.5), MemValue::F32(20.0), vec![Lt, Le, Ne]),
+            (MemValue::F64(1.0), MemValue::F64(1.0), vec![Le, Eq, Ge]),
+            (MemValue::String("b".into()), MemValue::String("a".into()), vec![Ge, Gt, Ne]),
+        ];
+        for (value, threshold, holds) in cases {
+            for comparison in Comparison::iter() {
+                assert_eq!(comparison.evaluate(&value, &threshold), holds.contains(&comparison), "{:?} {:?} {:?}", value, comparison, threshold);
+            }
+        }
+    }
+
+    #[test]
+    fn comparisons_of_mismatched_or_unordered_values_only_match_sensibly() {
+        use strum::IntoEnumIterator;
+        use Comparison::*;
+        // Different types never match, not even as not equal
+        for comparison in Comparison::iter() {
+            assert!(!comparison.evaluate(&MemValue::U8(1), &MemValue::U16(1)));
+            assert!(!comparison.evaluate(&MemValue::F32(f32::NAN), &MemValue::F32(1.0)));
+        }
+        // Vectors are only equal or not
+        let a = MemValue::Vec3F32([1.0, 2.0, 3.0]);
+        let b = MemValue::Vec3F32([1.0, 2.0, 4.0]);
+        assert!(Eq.evaluate(&a, &a) && Le.evaluate(&a, &a) && !Ne.evaluate(&a, &a));
+        assert!(Ne.evaluate(&a, &b) && !Eq.evaluate(&a, &b) && !Lt.evaluate(&a, &b));
+    }
+
+    /// Gets a disabled cheat bound to `hotkey`
+    fn cheat_with_hotkey(name: &str, hotkey: Option<egui::KeyboardShortcut>) -> Cheat {
+        Cheat { enabled: false, name: name.into(), cheat_type: CheatType::Simple { addr: 0x1000, mem_type: MemType::U32 }, frozen_value: None, frozen_input: String::new(), history: VecDeque::new(), hotkey }
+    }
+
+    #[test]
+    fn hotkeys_find_every_cheat_bound_to_them() {
+        let f1 = egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F1);
+        let ctrl_f1 = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::F1);
+        let f2 = egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F2);
+        let cheats = vec![cheat_with_hotkey("health", Some(f1)), cheat_with_hotkey("ammo", Some(ctrl_f1)), cheat_with_hotkey("unbound", None), cheat_with_hotkey("shield", Some(f1))];
+        // Cheats sharing a hotkey are all found, and modifiers have to match
+        assert_eq!(Cheat::bound_to(&cheats, &f1), vec![0, 3]);
+        assert_eq!(Cheat::bound_to(&cheats, &ctrl_f1), vec![1]);
+        assert!(Cheat::bound_to(&cheats, &f2).is_empty());
+    }
+
+    #[test]
+    fn cheat_table_round_trips_through_file() {
+        let path = std::env::temp_dir().join(format!("memninja-cheats-{}.json", std::process::id()));
+        let ctrl_f1 = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::F1);
+        let mut health = cheat_with_hotkey("health", Some(ctrl_f1));
+        health.enabled = true;
+        health.frozen_value = Some(MemValue::U32(100));
+        health.frozen_input = "100".into();
+        health.history.push_back(5.0);
+        let mut position = cheat_with_hotkey("position", None);
+        position.cheat_type = CheatType::ModuleRelative { module: "game.exe".into(), offset: 0x40, mem_type: MemType::Vec3F32 };
+        position.frozen_value = Some(MemValue::Vec3F32([1.0, 2.0, 3.0]));
+
+        Cheat::save_table(&[health, position], &path).unwrap();
+        let loaded = Cheat::load_table(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].name, "health");
+        assert_eq!(loaded[0].cheat_type, CheatType::Simple { addr: 0x1000, mem_type: MemType::U32 });
+        assert_eq!(loaded[0].frozen_value, Some(MemValue::U32(100)));
+        assert_eq!(loaded[0].frozen_input, "100");
+        assert_eq!(loaded[0].hotkey, Some(ctrl_f1));
+        assert_eq!(loaded[1].cheat_type, CheatType::ModuleRelative { module: "game.exe".into(), offset: 0x40, mem_type: MemType::Vec3F32 });
+        assert_eq!(loaded[1].frozen_value, Some(MemValue::Vec3F32([1.0, 2.0, 3.0])));
+        // Loaded cheats start disabled, without the history of the session they were saved from
+        assert!(loaded.iter().all(|cheat| !cheat.enabled && cheat.history.is_empty()));
+    }
+
+    #[test]
+    fn mem_values_round_trip_through_bytes() {
+        use strum::IntoEnumIterator;
+        for mem_type in MemType::iter() {
+            let text = match mem_type {
+                MemType::U8 | MemType::U16 | MemType::U32 | MemType::U64 => "200",
+                MemType::I8 | MemType::I16 | MemType::I32 | MemType::I64 => "-100",
+                MemType::F32 | MemType::F64 => "-1.5",
+                MemType::Vec3F32 => "1, -2.5, 3",
+                MemType::Bool => "true",
+                MemType::String => "héllo",
+                MemType::Unknown => continue,
+            };
+            let value = mem_type.parse_value(text).unwrap();
+            let bytes = value.to_le_bytes().unwrap();
+            let expected_len = if mem_type == MemType::String { "héllo".len() } else { mem_type.size() };
+            assert_eq!(bytes.len(), expected_len, "{:?}", mem_type);
+            assert_eq!(MemValue::from_le_bytes(mem_type, &bytes).unwrap(), value, "{:?}", mem_type);
+        }
+        assert_eq!(MemValue::I16(-2).to_le_bytes().unwrap(), vec![0xfe, 0xff]);
+    }
+
+    #[test]
+    fn mem_value_bytes_reject_null_unknown_and_wrong_lengths() {
+        assert!(MemValue::Null.to_le_bytes().is_err());
+        assert!(MemValue::from_le_bytes(MemType::Unknown, &[0; 4]).is_err());
+        assert!(MemValue::from_le_bytes(MemType::U32, &[0; 3]).is_err());
+        assert!(MemValue::from_le_bytes(MemType::U8, &[]).is_err());
+        assert!(MemValue::from_le_bytes(MemType::Vec3F32, &[0; 8]).is_err());
+    }
+}
@@ -1,7 +1,53 @@
-use crate::{MemType, MemValue, ScanType};
-use hoodmem::scanner::{ScanFilter, Scanner};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 
-#[derive(Debug)]
+use crate::{parse_vec3, DisplayFormat, MemType, MemValue, ScanType};
+use hoodmem::scanner::{
+    ScanFilter, ScanOutcome, ScanProgress, Scanner, StringEncoding, Vec3Filter,
+};
+
+/// Threshold used by approximate scans on floats when no threshold is given. Integers default to exact matches
+const APPROXIMATE_FLOAT_THRESHOLD: f64 = 0.001;
+
+/// Percentage points either side of the entered percentage a percent scan accepts
+const PERCENT_SCAN_TOLERANCE: f64 = 2.0;
+
+/// Formats `value` for display in `format`. Strings and null are always shown as they are
+pub fn format_mem_value(value: &MemValue, format: DisplayFormat) -> String {
+    let hex = match value {
+        MemValue::U8(x) => format!("0x{:x}", x),
+        MemValue::U16(x) => format!("0x{:x}", x),
+        MemValue::U32(x) => format!("0x{:x}", x),
+        MemValue::U64(x) => format!("0x{:x}", x),
+        // Hex formatting of signed integers is two's complement
+        MemValue::I8(x) => format!("0x{:x}", x),
+        MemValue::I16(x) => format!("0x{:x}", x),
+        MemValue::I32(x) => format!("0x{:x}", x),
+        MemValue::I64(x) => format!("0x{:x}", x),
+        MemValue::F32(x) => format!("0x{:x}", x.to_bits()),
+        MemValue::F64(x) => format!("0x{:x}", x.to_bits()),
+        MemValue::Vec3F32(_) | MemValue::String(_) | MemValue::Null => return value.to_string(),
+    };
+    match format {
+        DisplayFormat::Decimal => value.to_string(),
+        DisplayFormat::Hex => hex,
+        DisplayFormat::Both => format!("{} ({})", value, hex),
+    }
+}
+
+/// Formats a scan result read as `mem_type` for display in `format`
+///
+/// Bools are shown as true or false, and string results, which have no value of their own, as a match
+pub fn format_result_value(value: &MemValue, mem_type: MemType, format: DisplayFormat) -> String {
+    match (mem_type, value) {
+        (MemType::Bool, MemValue::U8(value)) => format!("{}", *value != 0),
+        (MemType::String, _) => "String match".into(),
+        _ => format_mem_value(value, format),
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum GenericScanFilter {
     U8(ScanFilter<u8>),
     U16(ScanFilter<u16>),
@@ -13,30 +59,119 @@ pub enum GenericScanFilter {
     I64(ScanFilter<i64>),
     F32(ScanFilter<f32>),
     F64(ScanFilter<f64>),
+    Vec3F32(Vec3Filter),
+    String(String, StringEncoding),
 }
 
 impl GenericScanFilter {
-    /// Performs a new scan
-    pub fn scan(&self, scanner: &mut Scanner) -> anyhow::Result<()> {
+    /// Performs a new scan, calling `progress` as each region is scanned and stopping early once `cancel` is set
+    ///
+    /// Vector and string scans can't be cancelled, so always complete
+    pub fn scan_cancellable(
+        &self,
+        scanner: &mut Scanner,
+        cancel: &AtomicBool,
+        progress: impl Fn(ScanProgress),
+    ) -> anyhow::Result<ScanOutcome> {
         match self {
-            GenericScanFilter::U8(s) => scanner.scan(*s),
-            GenericScanFilter::U16(s) => scanner.scan(*s),
-            GenericScanFilter::U32(s) => scanner.scan(*s),
-            GenericScanFilter::U64(s) => scanner.scan(*s),
-            GenericScanFilter::I8(s) => scanner.scan(*s),
-            GenericScanFilter::I16(s) => scanner.scan(*s),
-            GenericScanFilter::I32(s) => scanner.scan(*s),
-            GenericScanFilter::I64(s) => scanner.scan(*s),
-            GenericScanFilter::F32(s) => scanner.scan(*s),
-            GenericScanFilter::F64(s) => scanner.scan(*s),
+            GenericScanFilter::U8(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::U16(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::U32(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::U64(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::I8(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::I16(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::I32(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::I64(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::F32(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::F64(s) => {
+                scanner.scan_cancellable_with_progress(s.clone(), cancel, &progress)
+            }
+            GenericScanFilter::Vec3F32(filter) => {
+                scanner.scan_vec3(filter)?;
+                Ok(ScanOutcome::Completed)
+            }
+            GenericScanFilter::String(needle, encoding) => {
+                scanner.scan_string(needle, *encoding)?;
+                Ok(ScanOutcome::Completed)
+            }
+        }
+    }
+
+    /// Create a filter matching values equal to any of `values`, which must all have the same type
+    pub fn exact_any(values: Vec<MemValue>) -> anyhow::Result<Self> {
+        macro_rules! collect_values {
+            ($variant:ident) => {
+                Self::$variant(ScanFilter::ExactAny(
+                    values
+                        .iter()
+                        .map(|value| match value {
+                            MemValue::$variant(v) => Ok(*v),
+                            _ => Err(anyhow::anyhow!(
+                                "Exact scan values must all have the same type"
+                            )),
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()?,
+                ))
+            };
         }
+        Ok(match values.first() {
+            Some(MemValue::U8(_)) => collect_values!(U8),
+            Some(MemValue::U16(_)) => collect_values!(U16),
+            Some(MemValue::U32(_)) => collect_values!(U32),
+            Some(MemValue::U64(_)) => collect_values!(U64),
+            Some(MemValue::I8(_)) => collect_values!(I8),
+            Some(MemValue::I16(_)) => collect_values!(I16),
+            Some(MemValue::I32(_)) => collect_values!(I32),
+            Some(MemValue::I64(_)) => collect_values!(I64),
+            Some(MemValue::F32(_)) => collect_values!(F32),
+            Some(MemValue::F64(_)) => collect_values!(F64),
+            Some(MemValue::Vec3F32(_)) => {
+                anyhow::bail!("Cannot scan for any of several vectors")
+            }
+            Some(MemValue::String(_)) => {
+                anyhow::bail!("Cannot scan for any of several strings")
+            }
+            Some(MemValue::Null) => anyhow::bail!("Cannot scan for unknown type"),
+            None => anyhow::bail!("Cannot perform exact scan without a value"),
+        })
+    }
+
+    /// Create a filter for a vector written as `x, y, z`, where unknown components are written as `?`
+    ///
+    /// Each known component must be within `tolerance` of its value, which defaults to a small threshold
+    pub fn vec3(value: &str, tolerance: Option<f32>) -> anyhow::Result<Self> {
+        Ok(Self::Vec3F32(Vec3Filter {
+            components: parse_vec3(value)?,
+            tolerance: tolerance.unwrap_or(APPROXIMATE_FLOAT_THRESHOLD as f32),
+        }))
     }
 
     /// Create a scan filter for the given memory type, and optionally a value
+    ///
+    /// `secondary_value` is only used by scan types needing a second value, such as the upper bound of `Between`
     pub fn new(
         scan_type: ScanType,
         mem_type: MemType,
         mem_value: Option<MemValue>,
+        secondary_value: Option<MemValue>,
     ) -> anyhow::Result<Self> {
         match scan_type {
             ScanType::Exact => {
@@ -52,14 +187,95 @@ impl GenericScanFilter {
                         MemValue::I64(v) => Self::I64(ScanFilter::Exact(v)),
                         MemValue::F32(v) => Self::F32(ScanFilter::Exact(v)),
                         MemValue::F64(v) => Self::F64(ScanFilter::Exact(v)),
+                        MemValue::Vec3F32(v) => Self::Vec3F32(Vec3Filter {
+                            components: v.map(Some),
+                            tolerance: 0.0,
+                        }),
+                        MemValue::String(v) => Self::String(v, StringEncoding::Utf8),
                         MemValue::Null => anyhow::bail!("Cannot scan for unknown type"),
                     })
                 } else {
                     anyhow::bail!("Cannot perform exact scan without a value");
                 }
             }
+            // A single value, see `exact_any` for several
+            ScanType::ExactAny => match mem_value {
+                Some(value) => Self::exact_any(vec![value]),
+                None => anyhow::bail!("Cannot perform exact scan without a value"),
+            },
+            ScanType::Approximate => {
+                if let Some(value) = mem_value {
+                    // An empty threshold falls back to a default for the type
+                    Ok(match (value, secondary_value) {
+                        (MemValue::U8(v), None) => Self::U8(ScanFilter::Approximate(v, 0)),
+                        (MemValue::U8(v), Some(MemValue::U8(threshold))) => {
+                            Self::U8(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::U16(v), None) => Self::U16(ScanFilter::Approximate(v, 0)),
+                        (MemValue::U16(v), Some(MemValue::U16(threshold))) => {
+                            Self::U16(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::U32(v), None) => Self::U32(ScanFilter::Approximate(v, 0)),
+                        (MemValue::U32(v), Some(MemValue::U32(threshold))) => {
+                            Self::U32(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::U64(v), None) => Self::U64(ScanFilter::Approximate(v, 0)),
+                        (MemValue::U64(v), Some(MemValue::U64(threshold))) => {
+                            Self::U64(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::I8(v), None) => Self::I8(ScanFilter::Approximate(v, 0)),
+                        (MemValue::I8(v), Some(MemValue::I8(threshold))) => {
+                            Self::I8(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::I16(v), None) => Self::I16(ScanFilter::Approximate(v, 0)),
+                        (MemValue::I16(v), Some(MemValue::I16(threshold))) => {
+                            Self::I16(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::I32(v), None) => Self::I32(ScanFilter::Approximate(v, 0)),
+                        (MemValue::I32(v), Some(MemValue::I32(threshold))) => {
+                            Self::I32(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::I64(v), None) => Self::I64(ScanFilter::Approximate(v, 0)),
+                        (MemValue::I64(v), Some(MemValue::I64(threshold))) => {
+                            Self::I64(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::F32(v), None) => Self::F32(ScanFilter::Approximate(
+                            v,
+                            APPROXIMATE_FLOAT_THRESHOLD as f32,
+                        )),
+                        (MemValue::F32(v), Some(MemValue::F32(threshold))) => {
+                            Self::F32(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::F64(v), None) => {
+                            Self::F64(ScanFilter::Approximate(v, APPROXIMATE_FLOAT_THRESHOLD))
+                        }
+                        (MemValue::F64(v), Some(MemValue::F64(threshold))) => {
+                            Self::F64(ScanFilter::Approximate(v, threshold))
+                        }
+                        (MemValue::Vec3F32(v), None) => Self::Vec3F32(Vec3Filter {
+                            components: v.map(Some),
+                            tolerance: APPROXIMATE_FLOAT_THRESHOLD as f32,
+                        }),
+                        (MemValue::Vec3F32(v), Some(MemValue::F32(threshold))) => {
+                            Self::Vec3F32(Vec3Filter {
+                                components: v.map(Some),
+                                tolerance: threshold,
+                            })
+                        }
+                        (MemValue::String(_), _) => {
+                            anyhow::bail!("Cannot perform an approximate scan on strings")
+                        }
+                        (MemValue::Null, _) => anyhow::bail!("Cannot scan for unknown type"),
+                        _ => anyhow::bail!(
+                            "Approximate scan threshold must have the same type as the value"
+                        ),
+                    })
+                } else {
+                    anyhow::bail!("Cannot perform approximate scan without a value");
+                }
+            }
             ScanType::Unknown => Ok(match mem_type {
-                MemType::U8 => Self::U8(ScanFilter::Unknown::<u8>),
+                MemType::U8 | MemType::Bool => Self::U8(ScanFilter::Unknown::<u8>),
                 MemType::U16 => Self::U16(ScanFilter::Unknown::<u16>),
                 MemType::U32 => Self::U32(ScanFilter::Unknown::<u32>),
                 MemType::U64 => Self::U64(ScanFilter::Unknown::<u64>),
@@ -69,10 +285,14 @@ impl GenericScanFilter {
                 MemType::I64 => Self::I64(ScanFilter::Unknown::<i64>),
                 MemType::F32 => Self::F32(ScanFilter::Unknown::<f32>),
                 MemType::F64 => Self::F64(ScanFilter::Unknown::<f64>),
+                MemType::Vec3F32 => {
+                    anyhow::bail!("Cannot perform an unknown value scan on vectors")
+                }
+                MemType::String => anyhow::bail!("Cannot perform an unknown value scan on strings"),
                 MemType::Unknown => anyhow::bail!("Cannot scan for unknown type"),
             }),
             ScanType::Increased => Ok(match mem_type {
-                MemType::U8 => Self::U8(ScanFilter::Increased::<u8>),
+                MemType::U8 | MemType::Bool => Self::U8(ScanFilter::Increased::<u8>),
                 MemType::U16 => Self::U16(ScanFilter::Increased::<u16>),
                 MemType::U32 => Self::U32(ScanFilter::Increased::<u32>),
                 MemType::U64 => Self::U64(ScanFilter::Increased::<u64>),
@@ -82,10 +302,12 @@ impl GenericScanFilter {
                 MemType::I64 => Self::I64(ScanFilter::Increased::<i64>),
                 MemType::F32 => Self::F32(ScanFilter::Increased::<f32>),
                 MemType::F64 => Self::F64(ScanFilter::Increased::<f64>),
+                MemType::Vec3F32 => anyhow::bail!("Cannot perform an increased scan on vectors"),
+                MemType::String => anyhow::bail!("Cannot perform an increased scan on strings"),
                 MemType::Unknown => anyhow::bail!("Cannot scan for Increased type"),
             }),
             ScanType::Decreased => Ok(match mem_type {
-                MemType::U8 => Self::U8(ScanFilter::Decreased::<u8>),
+                MemType::U8 | MemType::Bool => Self::U8(ScanFilter::Decreased::<u8>),
                 MemType::U16 => Self::U16(ScanFilter::Decreased::<u16>),
                 MemType::U32 => Self::U32(ScanFilter::Decreased::<u32>),
                 MemType::U64 => Self::U64(ScanFilter::Decreased::<u64>),
@@ -95,8 +317,665 @@ impl GenericScanFilter {
                 MemType::I64 => Self::I64(ScanFilter::Decreased::<i64>),
                 MemType::F32 => Self::F32(ScanFilter::Decreased::<f32>),
                 MemType::F64 => Self::F64(ScanFilter::Decreased::<f64>),
+                MemType::Vec3F32 => anyhow::bail!("Cannot perform a decreased scan on vectors"),
+                MemType::String => anyhow::bail!("Cannot perform a decreased scan on strings"),
                 MemType::Unknown => anyhow::bail!("Cannot scan for Decreased type"),
             }),
+            ScanType::Changed => Ok(match mem_type {
+                MemType::U8 | MemType::Bool => Self::U8(ScanFilter::Changed::<u8>),
+                MemType::U16 => Self::U16(ScanFilter::Changed::<u16>),
+                MemType::U32 => Self::U32(ScanFilter::Changed::<u32>),
+                MemType::U64 => Self::U64(ScanFilter::Changed::<u64>),
+                MemType::I8 => Self::I8(ScanFilter::Changed::<i8>),
+                MemType::I16 => Self::I16(ScanFilter::Changed::<i16>),
+                MemType::I32 => Self::I32(ScanFilter::Changed::<i32>),
+                MemType::I64 => Self::I64(ScanFilter::Changed::<i64>),
+                MemType::F32 => Self::F32(ScanFilter::Changed::<f32>),
+                MemType::F64 => Self::F64(ScanFilter::Changed::<f64>),
+                MemType::Vec3F32 => anyhow::bail!("Cannot perform a changed scan on vectors"),
+                MemType::String => anyhow::bail!("Cannot perform a changed scan on strings"),
+                MemType::Unknown => anyhow::bail!("Cannot scan for Changed type"),
+            }),
+            ScanType::Unchanged => Ok(match mem_type {
+                MemType::U8 | MemType::Bool => Self::U8(ScanFilter::Unchanged::<u8>),
+                MemType::U16 => Self::U16(ScanFilter::Unchanged::<u16>),
+                MemType::U32 => Self::U32(ScanFilter::Unchanged::<u32>),
+                MemType::U64 => Self::U64(ScanFilter::Unchanged::<u64>),
+                MemType::I8 => Self::I8(ScanFilter::Unchanged::<i8>),
+                MemType::I16 => Self::I16(ScanFilter::Unchanged::<i16>),
+                MemType::I32 => Self::I32(ScanFilter::Unchanged::<i32>),
+                MemType::I64 => Self::I64(ScanFilter::Unchanged::<i64>),
+                MemType::F32 => Self::F32(ScanFilter::Unchanged::<f32>),
+                MemType::F64 => Self::F64(ScanFilter::Unchanged::<f64>),
+                MemType::Vec3F32 => anyhow::bail!("Cannot perform an unchanged scan on vectors"),
+                MemType::String => anyhow::bail!("Cannot perform an unchanged scan on strings"),
+                MemType::Unknown => anyhow::bail!("Cannot scan for Unchanged type"),
+            }),
+            ScanType::Between => match (mem_value, secondary_value) {
+                (Some(min), Some(max)) => Ok(match (min, max) {
+                    (MemValue::U8(min), MemValue::U8(max)) => {
+                        Self::U8(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::U16(min), MemValue::U16(max)) => {
+                        Self::U16(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::U32(min), MemValue::U32(max)) => {
+                        Self::U32(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::U64(min), MemValue::U64(max)) => {
+                        Self::U64(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::I8(min), MemValue::I8(max)) => {
+                        Self::I8(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::I16(min), MemValue::I16(max)) => {
+                        Self::I16(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::I32(min), MemValue::I32(max)) => {
+                        Self::I32(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::I64(min), MemValue::I64(max)) => {
+                        Self::I64(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::F32(min), MemValue::F32(max)) => {
+                        Self::F32(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::F64(min), MemValue::F64(max)) => {
+                        Self::F64(ScanFilter::Between(min, max))
+                    }
+                    (MemValue::String(_), MemValue::String(_)) => {
+                        anyhow::bail!("Cannot perform a between scan on strings")
+                    }
+                    _ => anyhow::bail!("Between scan bounds must have the same type"),
+                }),
+                _ => {
+                    anyhow::bail!("Cannot perform between scan without a minimum and maximum value")
+                }
+            },
+            ScanType::NotEqual => {
+                if let Some(value) = mem_value {
+                    Ok(match value {
+                        MemValue::U8(v) => Self::U8(ScanFilter::NotEqual(v)),
+                        MemValue::U16(v) => Self::U16(ScanFilter::NotEqual(v)),
+                        MemValue::U32(v) => Self::U32(ScanFilter::NotEqual(v)),
+                        MemValue::U64(v) => Self::U64(ScanFilter::NotEqual(v)),
+                        MemValue::I8(v) => Self::I8(ScanFilter::NotEqual(v)),
+                        MemValue::I16(v) => Self::I16(ScanFilter::NotEqual(v)),
+                        MemValue::I32(v) => Self::I32(ScanFilter::NotEqual(v)),
+                        MemValue::I64(v) => Self::I64(ScanFilter::NotEqual(v)),
+                        MemValue::F32(v) => Self::F32(ScanFilter::NotEqual(v)),
+                        MemValue::F64(v) => Self::F64(ScanFilter::NotEqual(v)),
+                        MemValue::Vec3F32(_) => {
+                            anyhow::bail!("Cannot perform a not equal scan on vectors")
+                        }
+                        MemValue::String(_) => {
+                            anyhow::bail!("Cannot perform a not equal scan on strings")
+                        }
+                        MemValue::Null => anyhow::bail!("Cannot scan for unknown type"),
+                    })
+                } else {
+                    anyhow::bail!("Cannot perform not equal scan without a value");
+                }
+            }
+            ScanType::Rounded => match (mem_value, secondary_value) {
+                (Some(value), Some(MemValue::U32(places))) => Ok(match value {
+                    MemValue::F32(v) => Self::F32(ScanFilter::RoundedTo(v, places)),
+                    MemValue::F64(v) => Self::F64(ScanFilter::RoundedTo(v, places)),
+                    MemValue::String(_) => {
+                        anyhow::bail!("Cannot perform a rounded scan on strings")
+                    }
+                    _ => anyhow::bail!("Rounded scans are only supported for floats"),
+                }),
+                _ => anyhow::bail!(
+                    "Cannot perform rounded scan without a value and a number of decimal places"
+                ),
+            },
+            ScanType::Percent => match (mem_value, secondary_value) {
+                (Some(MemValue::F64(percent)), Some(max)) => {
+                    let tolerance = PERCENT_SCAN_TOLERANCE;
+                    Ok(match max {
+                        MemValue::U8(max) => Self::U8(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::U16(max) => Self::U16(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::U32(max) => Self::U32(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::U64(max) => Self::U64(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::I8(max) => Self::I8(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::I16(max) => Self::I16(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::I32(max) => Self::I32(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::I64(max) => Self::I64(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::F32(max) => Self::F32(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::F64(max) => Self::F64(ScanFilter::PercentOf {
+                            max,
+                            percent,
+                            tolerance,
+                        }),
+                        MemValue::Vec3F32(_) => {
+                            anyhow::bail!("Cannot perform a percent scan on vectors")
+                        }
+                        MemValue::String(_) => {
+                            anyhow::bail!("Cannot perform a percent scan on strings")
+                        }
+                        MemValue::Null => anyhow::bail!("Cannot scan for unknown type"),
+                    })
+                }
+                _ => anyhow::bail!(
+                    "Cannot perform percent scan without a percentage and a maximum value"
+                ),
+            },
+            ScanType::DivisibleBy => match mem_value {
+                Some(value) => Ok(match value {
+                    MemValue::U8(v) => Self::U8(ScanFilter::divisible_by(v)?),
+                    MemValue::U16(v) => Self::U16(ScanFilter::divisible_by(v)?),
+                    MemValue::U32(v) => Self::U32(ScanFilter::divisible_by(v)?),
+                    MemValue::U64(v) => Self::U64(ScanFilter::divisible_by(v)?),
+                    MemValue::I8(v) => Self::I8(ScanFilter::divisible_by(v)?),
+                    MemValue::I16(v) => Self::I16(ScanFilter::divisible_by(v)?),
+                    MemValue::I32(v) => Self::I32(ScanFilter::divisible_by(v)?),
+                    MemValue::I64(v) => Self::I64(ScanFilter::divisible_by(v)?),
+                    MemValue::String(_) => {
+                        anyhow::bail!("Cannot perform a divisible by scan on strings")
+                    }
+                    _ => anyhow::bail!("Divisible by scans are only supported for integers"),
+                }),
+                None => anyhow::bail!("Cannot perform divisible by scan without a divisor"),
+            },
+            ScanType::ExactAddress => {
+                anyhow::bail!(
+                    "Exact address is not a filter. Seed the address with CoreCommand::SeedAddress"
+                )
+            }
+        }
+    }
+}
+
+/// Tracks how the values of scan results changed between refreshes, so changed values can be highlighted
+///
+/// Only results which have been looked at are tracked, and results which aren't looked at again are forgotten
+#[derive(Debug, Default)]
+pub struct ResultDeltas {
+    /// Counts refreshes of the results, so a value looked at twice in one refresh keeps its change
+    refresh: u64,
+    /// Each result's value when last looked at, the refresh it was looked at in, and how it had changed then
+    seen: HashMap<u64, (MemValue, u64, Ordering)>,
+}
+
+impl ResultDeltas {
+    /// Marks that the result values have been read from memory again
+    pub fn next_refresh(&mut self) {
+        self.refresh += 1;
+    }
+
+    /// Forgets every value, such as when detaching
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+
+    /// Pairs each result with how its value changed in the last refresh: `Greater` if it increased, `Less` if it
+    /// decreased, and `Equal` if it didn't change, was first seen this refresh, or can't be compared
+    pub fn apply(&mut self, results: Vec<(u64, MemValue)>) -> Vec<(u64, MemValue, Ordering)> {
+        let mut seen = HashMap::with_capacity(results.len());
+        let results = results
+            .into_iter()
+            .map(|(addr, value)| {
+                let delta = match self.seen.get(&addr) {
+                    Some((old_value, _, _)) if *old_value != value => value
+                        .to_f64()
+                        .zip(old_value.to_f64())
+                        .and_then(|(new, old)| new.partial_cmp(&old))
+                        .unwrap_or(Ordering::Equal),
+                    // Unchanged since it was last looked at. It only still counts as changed during the same refresh
+                    Some((_, refresh, delta)) if *refresh == self.refresh => *delta,
+                    _ => Ordering::Equal,
+                };
+                seen.insert(addr, (value.clone(), self.refresh, delta));
+                (addr, value, delta)
+            })
+            .collect();
+        self.seen = seen;
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn between_filter_keeps_bounds_in_order_given() {
+        let filter = GenericScanFilter::new(
+            ScanType::Between,
+            MemType::U32,
+            Some(MemValue::U32(20)),
+            Some(MemValue::U32(10)),
+        )
+        .unwrap();
+        assert!(matches!(
+            filter,
+            GenericScanFilter::U32(ScanFilter::Between(20, 10))
+        ));
+        assert!(GenericScanFilter::new(
+            ScanType::Between,
+            MemType::U32,
+            Some(MemValue::U32(1)),
+            None
+        )
+        .is_err());
+        assert!(GenericScanFilter::new(
+            ScanType::Between,
+            MemType::U32,
+            Some(MemValue::U32(1)),
+            Some(MemValue::I32(2))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn not_equal_filter_matches_value_type() {
+        let filter = GenericScanFilter::new(
+            ScanType::NotEqual,
+            MemType::I16,
+            Some(MemValue::I16(-3)),
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            filter,
+            GenericScanFilter::I16(ScanFilter::NotEqual(-3))
+        ));
+        assert!(GenericScanFilter::new(ScanType::NotEqual, MemType::I16, None, None).is_err());
+    }
+
+    #[test]
+    fn rounded_filter_is_only_for_floats() {
+        let filter = GenericScanFilter::new(
+            ScanType::Rounded,
+            MemType::F32,
+            Some(MemValue::F32(2.5)),
+            Some(MemValue::U32(2)),
+        )
+        .unwrap();
+        assert!(matches!(
+            filter,
+            GenericScanFilter::F32(ScanFilter::RoundedTo(_, 2))
+        ));
+        assert!(GenericScanFilter::new(
+            ScanType::Rounded,
+            MemType::U32,
+            Some(MemValue::U32(3)),
+            Some(MemValue::U32(2))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn bool_scan_round_trips_through_u8_filter() {
+        let value = MemType::Bool.parse_value("true").unwrap();
+        let filter =
+            GenericScanFilter::new(ScanType::Exact, MemType::Bool, Some(value), None).unwrap();
+        assert!(matches!(
+            filter,
+            GenericScanFilter::U8(ScanFilter::Exact(1))
+        ));
+
+        let process = hoodmem::mock::MockProcess::new().with_region(0x1000, vec![0, 1, 2, 1]);
+        let mut scanner = Scanner::new(std::sync::Arc::new(process));
+        filter
+            .scan_cancellable(&mut scanner, &AtomicBool::new(false), |_| {})
+            .unwrap();
+        let results = scanner.get_results::<u8>();
+        assert_eq!(results, vec![(0x1001, 1), (0x1003, 1)]);
+        assert_eq!(
+            format_result_value(
+                &MemValue::U8(results[0].1),
+                MemType::Bool,
+                DisplayFormat::Decimal
+            ),
+            "true"
+        );
+        assert_eq!(
+            format_result_value(&MemValue::U8(0), MemType::Bool, DisplayFormat::Decimal),
+            "false"
+        );
+    }
+
+    /// Gets the name of the `GenericScanFilter` variant scans of `mem_type` use
+    fn filter_variant(mem_type: MemType) -> String {
+        match mem_type {
+            MemType::Bool => "U8".into(),
+            _ => format!("{:?}", mem_type),
+        }
+    }
+
+    #[test]
+    fn changed_and_unchanged_filters_for_every_type() {
+        for mem_type in MemType::iter() {
+            for (scan_type, filter) in [
+                (ScanType::Changed, "Changed"),
+                (ScanType::Unchanged, "Unchanged"),
+            ] {
+                let result = GenericScanFilter::new(scan_type, mem_type, None, None);
+                match mem_type {
+                    MemType::Vec3F32 | MemType::String | MemType::Unknown => {
+                        assert!(result.is_err(), "{} scan of {:?}", scan_type, mem_type)
+                    }
+                    _ => assert_eq!(
+                        format!("{:?}", result.unwrap()),
+                        format!("{}({})", filter_variant(mem_type), filter)
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn between_and_not_equal_filters_for_every_number_type() {
+        for mem_type in MemType::iter() {
+            let (Ok(one), Ok(two)) = (mem_type.parse_value("1"), mem_type.parse_value("0")) else {
+                continue;
+            };
+            if matches!(mem_type, MemType::String) {
+                continue;
+            }
+            let not_equal =
+                GenericScanFilter::new(ScanType::NotEqual, mem_type, Some(one.clone()), None)
+                    .unwrap();
+            let between =
+                GenericScanFilter::new(ScanType::Between, mem_type, Some(one), Some(two)).unwrap();
+            let (one, zero) = match mem_type {
+                MemType::F32 | MemType::F64 => ("1.0", "0.0"),
+                _ => ("1", "0"),
+            };
+            let variant = filter_variant(mem_type);
+            assert_eq!(
+                format!("{:?}", not_equal),
+                format!("{}(NotEqual({}))", variant, one)
+            );
+            assert_eq!(
+                format!("{:?}", between),
+                format!("{}(Between({}, {}))", variant, one, zero)
+            );
+        }
+        // Both bounds have to be the same type
+        assert!(GenericScanFilter::new(
+            ScanType::Between,
+            MemType::U8,
+            Some(MemValue::U8(1)),
+            Some(MemValue::U16(2))
+        )
+        .is_err());
+        assert!(GenericScanFilter::new(ScanType::NotEqual, MemType::U8, None, None).is_err());
+    }
+
+    #[test]
+    fn percent_filter_uses_max_type() {
+        let filter = GenericScanFilter::new(
+            ScanType::Percent,
+            MemType::U8,
+            Some(MemValue::F64(75.0)),
+            Some(MemValue::U8(200)),
+        )
+        .unwrap();
+        assert_eq!(
+            format!("{:?}", filter),
+            "U8(PercentOf { max: 200, percent: 75.0, tolerance: 2.0 })"
+        );
+        assert!(GenericScanFilter::new(
+            ScanType::Percent,
+            MemType::U8,
+            Some(MemValue::F64(75.0)),
+            None
+        )
+        .is_err());
+        assert!(GenericScanFilter::new(
+            ScanType::Percent,
+            MemType::String,
+            Some(MemValue::F64(75.0)),
+            Some(MemValue::String("a".into()))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn approximate_filters_for_every_number_type() {
+        for mem_type in MemType::iter() {
+            if matches!(
+                mem_type,
+                MemType::String | MemType::Bool | MemType::Vec3F32 | MemType::Unknown
+            ) {
+                continue;
+            }
+            let value = mem_type.parse_value("5").unwrap();
+            let threshold = mem_type.parse_value("1").unwrap();
+            let with_threshold = GenericScanFilter::new(
+                ScanType::Approximate,
+                mem_type,
+                Some(value.clone()),
+                Some(threshold),
+            )
+            .unwrap();
+            let without_threshold =
+                GenericScanFilter::new(ScanType::Approximate, mem_type, Some(value), None).unwrap();
+            let variant = filter_variant(mem_type);
+            let (expected, expected_default) = match mem_type {
+                MemType::F32 | MemType::F64 => ("5.0, 1.0", "5.0, 0.001"),
+                _ => ("5, 1", "5, 0"),
+            };
+            assert_eq!(
+                format!("{:?}", with_threshold),
+                format!("{}(Approximate({}))", variant, expected)
+            );
+            assert_eq!(
+                format!("{:?}", without_threshold),
+                format!("{}(Approximate({}))", variant, expected_default)
+            );
+        }
+        let vec3 = GenericScanFilter::new(
+            ScanType::Approximate,
+            MemType::Vec3F32,
+            Some(MemValue::Vec3F32([1.0, 2.0, 3.0])),
+            Some(MemValue::F32(0.5)),
+        )
+        .unwrap();
+        assert!(
+            matches!(vec3, GenericScanFilter::Vec3F32(Vec3Filter { tolerance, .. }) if tolerance == 0.5)
+        );
+
+        assert!(GenericScanFilter::new(ScanType::Approximate, MemType::U8, None, None).is_err());
+        assert!(GenericScanFilter::new(
+            ScanType::Approximate,
+            MemType::U8,
+            Some(MemValue::U8(5)),
+            Some(MemValue::U16(1))
+        )
+        .is_err());
+        assert!(GenericScanFilter::new(
+            ScanType::Approximate,
+            MemType::String,
+            Some(MemValue::String("a".into())),
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn exact_any_needs_values_of_one_type() {
+        let filter = GenericScanFilter::exact_any(vec![
+            MemValue::U32(1),
+            MemValue::U32(2),
+            MemValue::U32(4),
+        ])
+        .unwrap();
+        assert_eq!(format!("{:?}", filter), "U32(ExactAny([1, 2, 4]))");
+        let single = GenericScanFilter::new(
+            ScanType::ExactAny,
+            MemType::F32,
+            Some(MemValue::F32(1.5)),
+            None,
+        )
+        .unwrap();
+        assert_eq!(format!("{:?}", single), "F32(ExactAny([1.5]))");
+
+        assert!(GenericScanFilter::exact_any(vec![MemValue::U32(1), MemValue::U16(2)]).is_err());
+        assert!(GenericScanFilter::exact_any(vec![]).is_err());
+        assert!(GenericScanFilter::exact_any(vec![MemValue::String("a".into())]).is_err());
+    }
+
+    #[test]
+    fn values_format_in_each_display_format() {
+        let cases = [
+            (MemValue::U8(255), "255", "0xff"),
+            (MemValue::U32(4096), "4096", "0x1000"),
+            (MemValue::I8(-1), "-1", "0xff"),
+            (MemValue::I32(-2), "-2", "0xfffffffe"),
+            (MemValue::I64(-1), "-1", "0xffffffffffffffff"),
+            (MemValue::F32(1.0), "1", "0x3f800000"),
+            (MemValue::F64(-2.5), "-2.5", "0xc004000000000000"),
+        ];
+        for (value, decimal, hex) in cases {
+            assert_eq!(format_mem_value(&value, DisplayFormat::Decimal), decimal);
+            assert_eq!(format_mem_value(&value, DisplayFormat::Hex), hex);
+            assert_eq!(
+                format_mem_value(&value, DisplayFormat::Both),
+                format!("{} ({})", decimal, hex)
+            );
         }
+        // Values without a number are shown as they are in every format
+        for format in DisplayFormat::iter() {
+            assert_eq!(
+                format_mem_value(&MemValue::String("abc".into()), format),
+                "abc"
+            );
+            assert_eq!(
+                format_mem_value(&MemValue::Vec3F32([1.0, 2.0, 3.0]), format),
+                "(1, 2, 3)"
+            );
+            assert_eq!(
+                format_result_value(&MemValue::U8(1), MemType::Bool, format),
+                "true"
+            );
+        }
+    }
+
+    #[test]
+    fn divisible_by_filters_are_only_for_nonzero_integers() {
+        let filter = GenericScanFilter::new(
+            ScanType::DivisibleBy,
+            MemType::I16,
+            Some(MemValue::I16(5)),
+            None,
+        )
+        .unwrap();
+        assert_eq!(format!("{:?}", filter), "I16(DivisibleBy(5))");
+        // A divisor of 0 is refused rather than panicking when scanning
+        for mem_type in [MemType::U8, MemType::U32, MemType::I64] {
+            let zero = mem_type.parse_value("0").unwrap();
+            assert!(
+                GenericScanFilter::new(ScanType::DivisibleBy, mem_type, Some(zero), None).is_err()
+            );
+        }
+        assert!(GenericScanFilter::new(
+            ScanType::DivisibleBy,
+            MemType::F32,
+            Some(MemValue::F32(2.0)),
+            None
+        )
+        .is_err());
+        assert!(GenericScanFilter::new(
+            ScanType::DivisibleBy,
+            MemType::F64,
+            Some(MemValue::F64(2.0)),
+            None
+        )
+        .is_err());
+        assert!(GenericScanFilter::new(ScanType::DivisibleBy, MemType::U32, None, None).is_err());
+    }
+
+    #[test]
+    fn result_deltas_compare_with_previous_refresh() {
+        let deltas = |results: &[(u64, MemValue, Ordering)]| -> Vec<Ordering> {
+            results.iter().map(|(_, _, delta)| *delta).collect()
+        };
+        let mut tracker = ResultDeltas::default();
+        let first = tracker.apply(vec![
+            (1, MemValue::U32(5)),
+            (2, MemValue::U32(5)),
+            (3, MemValue::U32(5)),
+        ]);
+        assert_eq!(
+            deltas(&first),
+            vec![Ordering::Equal, Ordering::Equal, Ordering::Equal]
+        );
+
+        tracker.next_refresh();
+        let changed = vec![
+            (1, MemValue::U32(6)),
+            (2, MemValue::U32(4)),
+            (3, MemValue::U32(5)),
+        ];
+        let expected = vec![Ordering::Greater, Ordering::Less, Ordering::Equal];
+        assert_eq!(deltas(&tracker.apply(changed.clone())), expected);
+        // Looking again in the same refresh, such as on the next frame, keeps the changes
+        assert_eq!(deltas(&tracker.apply(changed.clone())), expected);
+
+        tracker.next_refresh();
+        assert_eq!(
+            deltas(&tracker.apply(changed)),
+            vec![Ordering::Equal, Ordering::Equal, Ordering::Equal]
+        );
+    }
+
+    #[test]
+    fn result_deltas_forget_results_not_looked_at() {
+        let mut tracker = ResultDeltas::default();
+        tracker.apply(vec![(1, MemValue::U32(5)), (2, MemValue::U32(5))]);
+        tracker.next_refresh();
+        tracker.apply(vec![(1, MemValue::U32(5))]);
+        tracker.next_refresh();
+        let results = tracker.apply(vec![
+            (2, MemValue::U32(9)),
+            (3, MemValue::String("a".into())),
+        ]);
+        assert_eq!(results[0].2, Ordering::Equal);
+
+        // Values without a numeric value can't be compared
+        tracker.next_refresh();
+        let results = tracker.apply(vec![(3, MemValue::String("b".into()))]);
+        assert_eq!(results[0].2, Ordering::Equal);
     }
 }